@@ -2,12 +2,15 @@
 
 mod validation;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
 use tracing_subscriber::EnvFilter;
 
 pub use validation::{ConfigError, Validate};
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Config {
     #[serde(default = "default_kafka_brokers")]
     pub kafka_brokers: String,
@@ -17,16 +20,336 @@ pub struct Config {
     pub kafka_topic_parsed: String,
     #[serde(default = "default_kafka_group_id")]
     pub kafka_group_id: String,
+    /// Input message format: `raw` (URL key, HTML payload) or `json_envelope`.
+    #[serde(default = "default_input_format")]
+    pub input_format: String,
+    /// Kafka header holding the page URL when the message has no key (empty disables).
+    #[serde(default = "default_url_header")]
+    pub url_header: String,
+    /// Kafka header carrying the crawler's HTTP status in `raw` input (unset: ignored).
+    #[serde(default)]
+    pub http_status_header: Option<String>,
+    /// Comma-separated `status=action` entries (`404=errors`, `3xx=redirect`);
+    /// actions are `parse`, `skip`, `errors` and `redirect`. Unlisted statuses:
+    /// 200 is parsed, anything else skipped.
+    #[serde(default)]
+    pub status_routes: String,
+    /// `status_routes` parsed once, by `validate()` or on first use.
+    #[serde(skip)]
+    pub(crate) parsed_status_routes: OnceLock<Vec<StatusRoute>>,
+    /// Topic receiving payloads routed with the `errors` status action.
+    #[serde(default)]
+    pub kafka_topic_errors: Option<String>,
+    /// Topic receiving the redirect target of pages routed with `redirect`.
+    #[serde(default)]
+    pub kafka_topic_redirects: Option<String>,
+    /// Base Kafka `client.id`; defaults to `sneakdex-parser-<hostname>`.
+    #[serde(default)]
+    pub kafka_client_id: Option<String>,
+    /// Producer `acks`: `0`, `1` or `all` (librdkafka's default).
+    #[serde(default = "default_kafka_acks")]
+    pub kafka_acks: String,
+    /// Topic receiving one record per extracted image (unset: images only ride along in the page).
+    #[serde(default)]
+    pub kafka_topic_images: Option<String>,
+    /// How many image records are produced concurrently before waiting for delivery.
+    #[serde(default = "default_image_batch_size")]
+    pub image_batch_size: usize,
+    /// Links kept inline in the page; the rest go to `kafka_topic_link_overflow` (0 disables).
+    #[serde(default)]
+    pub inline_links_limit: usize,
+    /// Topic receiving links beyond `inline_links_limit`, keyed by page URL.
+    #[serde(default)]
+    pub kafka_topic_link_overflow: Option<String>,
+    /// Images kept inline in the page; the rest go to `kafka_topic_image_overflow` (0 disables).
+    #[serde(default)]
+    pub inline_images_limit: usize,
+    /// Topic receiving images beyond `inline_images_limit`, keyed by page URL.
+    #[serde(default)]
+    pub kafka_topic_image_overflow: Option<String>,
+    /// Topic receiving a compact summary (URL, title, snippet, language, image, hash) per page.
+    #[serde(default)]
+    pub kafka_topic_summary: Option<String>,
+    /// Topic receiving the URLs listed in XML sitemaps (unset: sitemaps are parsed as pages).
+    #[serde(default)]
+    pub kafka_topic_discovered_urls: Option<String>,
+    /// Keep `noindex` pages out of the main topic.
+    #[serde(default)]
+    pub respect_noindex: bool,
+    /// Topic receiving `noindex` pages when `respect_noindex` is set (unset: dropped).
+    #[serde(default)]
+    pub kafka_topic_noindex: Option<String>,
+    /// Topic receiving pages flagged `link_farm_suspected` (unset: they go to the main topic).
+    #[serde(default)]
+    pub kafka_topic_quarantine: Option<String>,
+    /// Flag pages with more external links than this as `link_farm_suspected` (0 disables).
+    #[serde(default)]
+    pub max_outbound_links: usize,
+    /// Flag pages whose Flesch `readability_score` is below this as `low_quality` (0 disables).
+    #[serde(default)]
+    pub min_readability_score: f64,
+    /// Flag pages whose effective title is shorter than this many characters
+    /// as `low_quality` (0 disables).
+    #[serde(default)]
+    pub min_title_len: usize,
+    /// Topic receiving `low_quality` pages (unset: they go to the main topic).
+    #[serde(default)]
+    pub kafka_topic_low_quality: Option<String>,
+    /// Topic receiving payloads that failed with a dead-letter error (unset: dropped).
+    #[serde(default)]
+    pub kafka_topic_dead_letter: Option<String>,
+    /// Shadow a production deployment: consume with an ephemeral group without
+    /// committing offsets, and write only to `kafka_topic_validation`.
+    #[serde(default)]
+    pub read_only_validation: bool,
+    /// Topic receiving every page under `read_only_validation` (unset: pages are discarded).
+    #[serde(default)]
+    pub kafka_topic_validation: Option<String>,
+    /// Where parsed pages are written: `kafka`, `s3`, `both` or `stdout` (NDJSON).
+    #[serde(default = "default_output_sink")]
+    pub output_sink: String,
+    /// Bucket for the S3 sink (required when `output_sink` includes `s3`).
+    #[serde(default)]
+    pub s3_bucket: Option<String>,
+    #[serde(default = "default_s3_region")]
+    pub s3_region: String,
+    /// S3-compatible endpoint (e.g. MinIO); defaults to AWS for `s3_region`.
+    #[serde(default)]
+    pub s3_endpoint: Option<String>,
+    /// Key prefix for page objects, stored as `<prefix>/<sha256(url)>.json`.
+    #[serde(default = "default_s3_prefix")]
+    pub s3_prefix: String,
+    /// Retry pages rejected with `MessageSizeTooLarge` after stripping optional fields.
+    #[serde(default)]
+    pub strip_oversized_fields: bool,
+    /// `cleaned_text` is never truncated below this many characters when stripping.
+    #[serde(default = "default_oversize_min_text_length")]
+    pub oversize_min_text_length: usize,
+    /// Consecutive produce or S3 upload errors after which consumption pauses (0 disables the breaker).
+    #[serde(default = "default_circuit_breaker_threshold")]
+    pub circuit_breaker_threshold: u64,
+    /// Seconds consumption stays paused before probing the output again.
+    #[serde(default = "default_circuit_breaker_cooldown_secs")]
+    pub circuit_breaker_cooldown_secs: u64,
+    /// Kafka header carrying each produced page's `content_hash`.
+    #[serde(default = "default_content_hash_header")]
+    pub content_hash_header: String,
+    /// Derive `doc_id` from the canonical URL when present (otherwise from the page URL).
+    #[serde(default = "default_true")]
+    pub doc_id_use_canonical: bool,
+    /// Kafka key for produced pages: `url`, `domain` or `canonical`.
+    #[serde(default = "default_partition_key_strategy")]
+    pub partition_key_strategy: String,
     #[serde(default = "default_max_concurrency")]
     pub max_concurrency: usize,
+    /// Pause consumption while the payloads being processed add up to this many
+    /// bytes (0 disables).
+    #[serde(default)]
+    pub max_inflight_bytes: u64,
+    /// Maximum parsed-page Kafka sends awaiting delivery at once.
+    #[serde(default = "default_max_concurrent_sends")]
+    pub max_concurrent_sends: usize,
     #[serde(default = "default_max_content_length")]
     pub max_content_length: usize,
     #[serde(default = "default_min_content_length")]
     pub min_content_length: usize,
+    /// Use the longest of `og:description` / `<meta name="description">` as
+    /// `cleaned_text` when the content is shorter than `min_content_length`,
+    /// instead of rejecting the page.
+    #[serde(default)]
+    pub metadata_content_fallback: bool,
+    /// Comma-separated `content-type=min:max` entries overriding the length limits
+    /// per content type; an empty side keeps the global value.
+    #[serde(default)]
+    pub content_length_overrides: String,
     #[serde(default = "default_log_level")]
     pub rust_log: String,
     #[serde(default = "default_monitor_port")]
     pub monitor_port: u16,
+    /// Seconds to wait for the Kafka handler and sinks to initialize and a broker
+    /// to answer before exiting (0 disables).
+    #[serde(default = "default_startup_timeout_secs")]
+    pub startup_timeout_secs: u64,
+    /// Seconds after startup during which `/health` reports `starting` instead of `not_healthy`.
+    #[serde(default = "default_startup_grace_secs")]
+    pub startup_grace_secs: u64,
+    /// Seconds between heartbeat logs with throughput, inflight and lag (0 disables).
+    #[serde(default = "default_heartbeat_secs")]
+    pub heartbeat_secs: u64,
+    /// Serve admin endpoints (`/config`) on the monitor port, behind basic auth.
+    #[serde(default)]
+    pub admin_enabled: bool,
+    #[serde(default = "default_admin_username")]
+    pub admin_username: String,
+    /// Required when `admin_enabled` is set; redacted from `/config`.
+    #[serde(default)]
+    pub admin_password: Option<String>,
+    /// Skip URLs a bloom filter reports as already processed.
+    #[serde(default)]
+    pub seen_filter_enabled: bool,
+    /// Size of the seen-URL bloom filter in bits.
+    #[serde(default = "default_seen_filter_bits")]
+    pub seen_filter_bits: usize,
+    /// Number of hash functions used by the seen-URL bloom filter.
+    #[serde(default = "default_seen_filter_hashes")]
+    pub seen_filter_hashes: u32,
+    /// Reject payloads with a binary file signature (PNG, JPEG, PDF, ...) before parsing.
+    #[serde(default = "default_true")]
+    pub sniff_binary_payloads: bool,
+    /// Flag titles/descriptions seen more than `duplicate_threshold` times in this run.
+    #[serde(default)]
+    pub duplicate_detection_enabled: bool,
+    /// Occurrences a title/description may have before it counts as a duplicate.
+    #[serde(default = "default_duplicate_threshold")]
+    pub duplicate_threshold: u64,
+    /// Distinct titles (and, separately, descriptions) remembered for duplicate detection.
+    #[serde(default = "default_duplicate_cache_size")]
+    pub duplicate_cache_size: usize,
+    /// Pages with `cleaned_text` shorter than this (characters) are checked
+    /// against recent boilerplate clusters and dropped on a match (0 disables).
+    #[serde(default)]
+    pub boilerplate_max_length: usize,
+    /// Recent short-page fingerprints remembered for boilerplate detection.
+    #[serde(default = "default_boilerplate_window")]
+    pub boilerplate_window: usize,
+    /// Maximum simhash Hamming distance for a page to join a boilerplate cluster.
+    #[serde(default = "default_boilerplate_max_distance")]
+    pub boilerplate_max_distance: u32,
+    /// Check decoded text for replacement characters and re-decode with the declared charset.
+    #[serde(default)]
+    pub decode_validation_enabled: bool,
+    /// Share of U+FFFD characters in `cleaned_text` above which decoding counts as wrong.
+    #[serde(default = "default_max_replacement_ratio")]
+    pub max_replacement_ratio: f64,
+    /// Forces `ParsedPage.content_type` instead of using the input `content-type` header.
+    #[serde(default)]
+    pub content_type_override: Option<String>,
+    /// Extraction path: `full` (DOM + readability), `body` or `metadata_only`
+    /// (single streaming tokenizer pass, no DOM).
+    #[serde(default = "default_extraction_mode")]
+    pub extraction_mode: String,
+    /// Minimum length, in characters, of a paragraph picked as the `lead`.
+    #[serde(default = "default_lead_min_length")]
+    pub lead_min_length: usize,
+    /// Detect the language of `cleaned_text` with whatlang; when off, only
+    /// `<html lang>` is used (pages without one map to `simple`).
+    #[serde(default = "default_true")]
+    pub language_detection_enabled: bool,
+    /// Use `<html lang>` / `og:locale` when language detection gives up on short text.
+    #[serde(default = "default_true")]
+    pub declared_language_fallback: bool,
+    /// Extract `video` from schema.org `VideoObject` JSON-LD.
+    #[serde(default = "default_true")]
+    pub video_extraction_enabled: bool,
+    /// Comma-separated URL path patterns marking login/registration pages (`auth_page`).
+    #[serde(default = "default_auth_url_patterns")]
+    pub auth_url_patterns: String,
+    /// Fill `forms` with each `<form>`'s action, method and field names/types.
+    #[serde(default)]
+    pub extract_forms: bool,
+    /// Collapse images with the same resolved `src`, keeping the first occurrence.
+    #[serde(default)]
+    pub dedup_images: bool,
+    /// Fill `events` with every schema.org `Event` on the page, not just `event`.
+    #[serde(default)]
+    pub extract_all_events: bool,
+    /// Take FAQs from `<details>`/`<summary>` pairs when there is no `FAQPage` JSON-LD.
+    #[serde(default)]
+    pub faq_dom_fallback: bool,
+    /// Separator placed between breadcrumb labels in `category_path`.
+    #[serde(default = "default_category_path_separator")]
+    pub category_path_separator: String,
+    /// How `ParsedPage` dates are written: `rfc3339`, `epoch_millis` or `epoch_secs`.
+    #[serde(default = "default_timestamp_format")]
+    pub timestamp_format: String,
+    /// Keep paragraph and line breaks in `cleaned_text` (`full` mode only).
+    #[serde(default)]
+    pub preserve_paragraphs: bool,
+    /// Maximum length, in characters, of the `preview` snippet.
+    #[serde(default = "default_preview_length")]
+    pub preview_length: usize,
+    /// Emit the unfiltered `<body>` text as `body_text` alongside `cleaned_text`.
+    #[serde(default)]
+    pub include_body_text: bool,
+    /// Emit per-token counts of `cleaned_text` as `token_counts` for lightweight indexers.
+    #[serde(default)]
+    pub include_token_counts: bool,
+    /// Emit per-page parse/readability timings and input size as `processing_meta`.
+    #[serde(default)]
+    pub emit_processing_meta: bool,
+    /// How many of the most frequent tokens `token_counts` keeps.
+    #[serde(default = "default_max_token_counts")]
+    pub max_token_counts: usize,
+    /// Maximum element depth below `<body>` descended into during text extraction.
+    #[serde(default = "default_max_dom_depth")]
+    pub max_dom_depth: usize,
+    /// Keep only links whose host matches: comma-separated host suffixes
+    /// (`.gov,example.com`) or `regex:<pattern>` (unset: all links are kept).
+    #[serde(default)]
+    pub outlink_host_filter: Option<String>,
+    /// Comma-separated `platform=domain` entries classifying links into
+    /// `social_links`; a platform may list several domains (empty disables).
+    #[serde(default = "default_social_platforms")]
+    pub social_platforms: String,
+    /// CSS selector list of in-content junk (share widgets, newsletter boxes)
+    /// removed from the main content root before its text is collected.
+    #[serde(default)]
+    pub content_exclude_selectors: Option<String>,
+    /// Retry readability once on sanitized markup before falling back to body text.
+    #[serde(default = "default_true")]
+    pub readability_sanitize_retry: bool,
+    /// Fail pages with any extraction warning (routed to the dead-letter topic)
+    /// instead of emitting them partially.
+    #[serde(default)]
+    pub strict_extraction: bool,
+    /// Use the body text when readability's content is confidently in another
+    /// language than `<html lang>` and the body is in the declared one.
+    #[serde(default)]
+    pub language_mismatch_retry: bool,
+    /// Detection confidence needed to call readability's content mismatched.
+    #[serde(default = "default_language_mismatch_min_confidence")]
+    pub language_mismatch_min_confidence: f64,
+    /// Remove a trailing `| Site Name` style suffix from titles, keeping the raw title
+    /// in `additional_metadata.raw_title`.
+    #[serde(default)]
+    pub strip_site_suffix_from_title: bool,
+    /// Keep headings whose text is empty (whitespace or `&nbsp;` only) after cleaning.
+    #[serde(default)]
+    pub keep_empty_headings: bool,
+    /// Keep links whose anchor text is empty (e.g. image-only or `&nbsp;` anchors).
+    #[serde(default)]
+    pub keep_empty_anchors: bool,
+    /// Use `aria-label`, `title` or an image `alt` as the text of links without
+    /// visible text (full extraction mode only).
+    #[serde(default)]
+    pub link_text_fallback: bool,
+    /// Lowercase and trim heading text, keeping the original in `raw_text`.
+    #[serde(default)]
+    pub normalize_headings: bool,
+    /// Also replace punctuation with spaces when normalizing headings.
+    #[serde(default)]
+    pub normalize_headings_strip_punctuation: bool,
+    /// Content/body length ratio at or above which readability is counted as a no-op.
+    #[serde(default = "default_readability_noop_ratio")]
+    pub readability_noop_ratio: f64,
+    /// Maximum number of `outbound_domains` per page (0 disables them).
+    #[serde(default = "default_max_outbound_domains")]
+    pub max_outbound_domains: usize,
+    /// Maximum number of code blocks kept per page (0 disables code extraction).
+    #[serde(default = "default_max_code_blocks")]
+    pub max_code_blocks: usize,
+    /// Maximum number of in-page `anchors` per page (0 disables them).
+    #[serde(default = "default_max_anchors")]
+    pub max_anchors: usize,
+    /// Comma-separated `key=selector@data-attr` specs copied into `additional_metadata`.
+    #[serde(default)]
+    pub data_attr_selectors: String,
+    /// Per-host `DomainProfile` overrides: an inline JSON object, or the path of a JSON file.
+    #[serde(default)]
+    pub domain_profiles: Option<String>,
+    /// Comma-separated `<meta name>` values whose content is copied into `additional_metadata`.
+    #[serde(default)]
+    pub capture_meta_names: String,
 }
 
 impl Default for Config {
@@ -36,26 +359,512 @@ impl Default for Config {
             kafka_topic_html: default_kafka_topic_html(),
             kafka_topic_parsed: default_kafka_topic_parsed(),
             kafka_group_id: default_kafka_group_id(),
+            input_format: default_input_format(),
+            url_header: default_url_header(),
+            http_status_header: None,
+            status_routes: String::new(),
+            parsed_status_routes: OnceLock::new(),
+            kafka_topic_errors: None,
+            kafka_topic_redirects: None,
+            kafka_client_id: None,
+            kafka_acks: default_kafka_acks(),
+            kafka_topic_images: None,
+            inline_links_limit: 0,
+            kafka_topic_link_overflow: None,
+            inline_images_limit: 0,
+            kafka_topic_image_overflow: None,
+            image_batch_size: default_image_batch_size(),
+            kafka_topic_summary: None,
+            kafka_topic_discovered_urls: None,
+            respect_noindex: false,
+            kafka_topic_noindex: None,
+            kafka_topic_quarantine: None,
+            max_outbound_links: 0,
+            min_readability_score: 0.0,
+            min_title_len: 0,
+            kafka_topic_low_quality: None,
+            kafka_topic_dead_letter: None,
+            read_only_validation: false,
+            kafka_topic_validation: None,
+            output_sink: default_output_sink(),
+            s3_bucket: None,
+            s3_region: default_s3_region(),
+            s3_endpoint: None,
+            s3_prefix: default_s3_prefix(),
+            strip_oversized_fields: false,
+            oversize_min_text_length: default_oversize_min_text_length(),
+            circuit_breaker_threshold: default_circuit_breaker_threshold(),
+            circuit_breaker_cooldown_secs: default_circuit_breaker_cooldown_secs(),
+            content_hash_header: default_content_hash_header(),
+            doc_id_use_canonical: true,
+            partition_key_strategy: default_partition_key_strategy(),
             max_concurrency: default_max_concurrency(),
+            max_inflight_bytes: 0,
+            max_concurrent_sends: default_max_concurrent_sends(),
             max_content_length: default_max_content_length(),
             min_content_length: default_min_content_length(),
+            metadata_content_fallback: false,
+            content_length_overrides: String::new(),
             rust_log: default_log_level(),
             monitor_port: default_monitor_port(),
+            startup_timeout_secs: default_startup_timeout_secs(),
+            startup_grace_secs: default_startup_grace_secs(),
+            heartbeat_secs: default_heartbeat_secs(),
+            admin_enabled: false,
+            admin_username: default_admin_username(),
+            admin_password: None,
+            seen_filter_enabled: false,
+            seen_filter_bits: default_seen_filter_bits(),
+            seen_filter_hashes: default_seen_filter_hashes(),
+            sniff_binary_payloads: true,
+            duplicate_detection_enabled: false,
+            duplicate_threshold: default_duplicate_threshold(),
+            duplicate_cache_size: default_duplicate_cache_size(),
+            boilerplate_max_length: 0,
+            boilerplate_window: default_boilerplate_window(),
+            boilerplate_max_distance: default_boilerplate_max_distance(),
+            decode_validation_enabled: false,
+            max_replacement_ratio: default_max_replacement_ratio(),
+            content_type_override: None,
+            extraction_mode: default_extraction_mode(),
+            lead_min_length: default_lead_min_length(),
+            language_detection_enabled: true,
+            declared_language_fallback: true,
+            video_extraction_enabled: true,
+            auth_url_patterns: default_auth_url_patterns(),
+            extract_forms: false,
+            dedup_images: false,
+            extract_all_events: false,
+            faq_dom_fallback: false,
+            category_path_separator: default_category_path_separator(),
+            preserve_paragraphs: false,
+            timestamp_format: default_timestamp_format(),
+            preview_length: default_preview_length(),
+            include_body_text: false,
+            include_token_counts: false,
+            emit_processing_meta: false,
+            max_token_counts: default_max_token_counts(),
+            max_dom_depth: default_max_dom_depth(),
+            readability_sanitize_retry: true,
+            content_exclude_selectors: None,
+            outlink_host_filter: None,
+            social_platforms: default_social_platforms(),
+            strict_extraction: false,
+            language_mismatch_retry: false,
+            language_mismatch_min_confidence: default_language_mismatch_min_confidence(),
+            strip_site_suffix_from_title: false,
+            keep_empty_headings: false,
+            keep_empty_anchors: false,
+            link_text_fallback: false,
+            normalize_headings: false,
+            normalize_headings_strip_punctuation: false,
+            readability_noop_ratio: default_readability_noop_ratio(),
+            max_outbound_domains: default_max_outbound_domains(),
+            max_code_blocks: default_max_code_blocks(),
+            max_anchors: default_max_anchors(),
+            data_attr_selectors: String::new(),
+            domain_profiles: None,
+            capture_meta_names: String::new(),
         }
     }
 }
 
+/// Config fields holding credentials, redacted by `Config::sanitized`.
+///
+/// Matched by exact name; credential fields added later (a SASL password, a
+/// TLS key) must be listed here.
+const SECRET_FIELDS: &[&str] = &["admin_password"];
+
 impl Config {
     pub fn init_logging(&self) {
         std::env::set_var("RUST_LOG", &self.rust_log);
+        // Keep stdout clean for the NDJSON sink.
+        let writer = if self.output_sink == "stdout" {
+            BoxMakeWriter::new(std::io::stderr)
+        } else {
+            BoxMakeWriter::new(std::io::stdout)
+        };
         tracing_subscriber::fmt()
             .with_env_filter(EnvFilter::from_default_env())
+            .with_writer(writer)
             .init();
     }
 
     pub fn validate(&self) -> Result<(), ConfigError> {
         Validate::validate(self)
     }
+
+    /// Rewrites the config for `read_only_validation`, if set.
+    ///
+    /// The consumer group gets a unique `-validation-` suffix so production
+    /// offsets are untouched. Pages go to `kafka_topic_validation` only: the
+    /// noindex, quarantine and low-quality topics are redirected there, and
+    /// every side output (images, summaries, discovered URLs, dead letters, S3)
+    /// is turned off. Without a validation topic, nothing is written.
+    pub fn apply_read_only_validation(&mut self) {
+        if !self.read_only_validation {
+            return;
+        }
+
+        let nonce = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis());
+        self.kafka_group_id = format!(
+            "{}-validation-{}-{}",
+            self.kafka_group_id,
+            std::process::id(),
+            nonce
+        );
+
+        let validation = self.kafka_topic_validation.clone();
+        if let Some(topic) = &validation {
+            self.kafka_topic_parsed = topic.clone();
+        }
+        for routed in [
+            &mut self.kafka_topic_noindex,
+            &mut self.kafka_topic_quarantine,
+            &mut self.kafka_topic_low_quality,
+        ] {
+            if routed.is_some() {
+                routed.clone_from(&validation);
+            }
+        }
+        self.kafka_topic_images = None;
+        self.kafka_topic_link_overflow = None;
+        self.kafka_topic_image_overflow = None;
+        self.kafka_topic_summary = None;
+        self.kafka_topic_discovered_urls = None;
+        self.kafka_topic_errors = None;
+        self.kafka_topic_redirects = None;
+        self.kafka_topic_dead_letter = None;
+        self.output_sink = "kafka".to_string();
+    }
+
+    /// The configured `kafka_client_id`, or one derived from the host name so
+    /// each pod is identifiable in broker metrics.
+    pub fn kafka_client_id(&self) -> String {
+        if let Some(client_id) = &self.kafka_client_id {
+            return client_id.clone();
+        }
+
+        let hostname = std::env::var("HOSTNAME")
+            .ok()
+            .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty());
+
+        match hostname {
+            Some(hostname) => format!("sneakdex-parser-{hostname}"),
+            None => "sneakdex-parser".to_string(),
+        }
+    }
+
+    /// The configuration as JSON with secrets replaced by `"[REDACTED]"`.
+    pub fn sanitized(&self) -> serde_json::Value {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        if let Some(fields) = value.as_object_mut() {
+            for (name, field) in fields.iter_mut() {
+                if SECRET_FIELDS.contains(&name.as_str()) && !field.is_null() {
+                    *field = serde_json::Value::String("[REDACTED]".to_string());
+                }
+            }
+        }
+        value
+    }
+
+    /// Returns the parsed `data_attr_selectors` specs.
+    ///
+    /// Malformed entries are skipped here; `validate()` rejects them at startup.
+    pub fn data_attr_specs(&self) -> Vec<DataAttrSpec> {
+        split_list(&self.data_attr_selectors)
+            .filter_map(DataAttrSpec::parse)
+            .collect()
+    }
+
+    /// Returns the `status_routes` entries, parsed on the first call.
+    ///
+    /// Malformed entries are skipped here; `validate()` rejects them at startup.
+    pub fn status_routes(&self) -> &[StatusRoute] {
+        self.parsed_status_routes.get_or_init(|| {
+            split_list(&self.status_routes)
+                .filter_map(StatusRoute::parse)
+                .collect()
+        })
+    }
+
+    /// What to do with a page fetched with HTTP `status`: the action of an
+    /// exact `status_routes` entry, else of its class (`4xx`), else `parse`
+    /// for 200 and `skip` otherwise.
+    pub fn status_action(&self, status: u16) -> StatusAction {
+        let routes = self.status_routes();
+        routes
+            .iter()
+            .find(|route| route.status == StatusPattern::Exact(status))
+            .or_else(|| {
+                routes
+                    .iter()
+                    .find(|route| route.status == StatusPattern::Class(status / 100))
+            })
+            .map(|route| route.action)
+            .unwrap_or(if status == 200 {
+                StatusAction::Parse
+            } else {
+                StatusAction::Skip
+            })
+    }
+
+    /// Returns the `capture_meta_names` entries, lowercased (meta names match
+    /// case-insensitively).
+    pub fn capture_meta_names(&self) -> Vec<String> {
+        split_list(&self.capture_meta_names)
+            .map(str::to_ascii_lowercase)
+            .collect()
+    }
+
+    /// Returns the `social_platforms` entries as `(platform, domain)` pairs,
+    /// lowercased. Malformed entries are skipped; `validate()` rejects them.
+    pub fn social_platforms(&self) -> Vec<(String, String)> {
+        split_list(&self.social_platforms)
+            .filter_map(|entry| {
+                let (platform, domain) = entry.split_once('=')?;
+                let (platform, domain) = (platform.trim(), domain.trim());
+                (!platform.is_empty() && !domain.is_empty())
+                    .then(|| (platform.to_ascii_lowercase(), domain.to_ascii_lowercase()))
+            })
+            .collect()
+    }
+
+    /// Returns the `auth_url_patterns` entries, lowercased.
+    pub fn auth_url_patterns(&self) -> Vec<String> {
+        split_list(&self.auth_url_patterns)
+            .map(str::to_ascii_lowercase)
+            .collect()
+    }
+
+    /// Loads `domain_profiles`, keyed by lowercased host.
+    ///
+    /// A value starting with `{` is parsed as inline JSON; anything else is
+    /// read as a path to a JSON file. Unset means no profiles.
+    ///
+    /// # Errors
+    /// Returns a description of the problem if the file can't be read or the
+    /// JSON doesn't match the profile format.
+    pub fn domain_profiles(&self) -> Result<HashMap<String, DomainProfile>, String> {
+        let Some(source) = self.domain_profiles.as_deref().map(str::trim) else {
+            return Ok(HashMap::new());
+        };
+
+        let json = if source.starts_with('{') {
+            source.to_string()
+        } else {
+            std::fs::read_to_string(source).map_err(|e| format!("{source}: {e}"))?
+        };
+
+        let profiles: HashMap<String, DomainProfile> =
+            serde_json::from_str(&json).map_err(|e| e.to_string())?;
+        Ok(profiles
+            .into_iter()
+            .map(|(host, profile)| (host.trim().to_ascii_lowercase(), profile))
+            .collect())
+    }
+
+    /// The global `min_content_length` / `max_content_length` pair.
+    pub fn default_length_gate(&self) -> LengthGate {
+        LengthGate {
+            min: self.min_content_length,
+            max: self.max_content_length,
+        }
+    }
+
+    /// Returns the per-content-type length limits from `content_length_overrides`,
+    /// with missing sides filled in from the global limits.
+    ///
+    /// Malformed entries are skipped here; `validate()` rejects them at startup.
+    pub fn length_gates(&self) -> HashMap<String, LengthGate> {
+        split_list(&self.content_length_overrides)
+            .filter_map(LengthOverride::parse)
+            .map(|entry| {
+                let gate = entry.apply(self.default_length_gate());
+                (entry.content_type, gate)
+            })
+            .collect()
+    }
+}
+
+/// Minimum cleaned-text length and maximum payload length for a page.
+#[derive(Debug, Clone, Copy)]
+pub struct LengthGate {
+    pub min: usize,
+    pub max: usize,
+}
+
+/// A single `content-type=min:max` entry of `content_length_overrides`.
+#[derive(Debug, Clone)]
+pub struct LengthOverride {
+    /// Lowercased content type without parameters, e.g. `text/plain`.
+    pub content_type: String,
+    pub min: Option<usize>,
+    pub max: Option<usize>,
+}
+
+impl LengthOverride {
+    /// Parses `content-type=min:max`, where either side may be empty.
+    ///
+    /// Returns `None` if the content type is missing, a side isn't a number, or
+    /// both sides are empty.
+    pub fn parse(entry: &str) -> Option<Self> {
+        let (content_type, limits) = entry.split_once('=')?;
+        let (min, max) = limits.split_once(':')?;
+        let content_type = content_type.trim().to_ascii_lowercase();
+
+        let side = |value: &str| -> Option<Option<usize>> {
+            let value = value.trim();
+            if value.is_empty() {
+                Some(None)
+            } else {
+                value.parse().ok().map(Some)
+            }
+        };
+        let (min, max) = (side(min)?, side(max)?);
+
+        if content_type.is_empty() || (min.is_none() && max.is_none()) {
+            return None;
+        }
+
+        Some(Self {
+            content_type,
+            min,
+            max,
+        })
+    }
+
+    /// Applies this override on top of `base`.
+    pub fn apply(&self, base: LengthGate) -> LengthGate {
+        LengthGate {
+            min: self.min.unwrap_or(base.min),
+            max: self.max.unwrap_or(base.max),
+        }
+    }
+}
+
+/// Site-specific extraction settings from `domain_profiles`.
+///
+/// A profile applies to its host and every subdomain of it; unset fields keep
+/// the global configuration.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DomainProfile {
+    /// CSS selector of the main content, used instead of readability when it matches.
+    pub content_selector: Option<String>,
+    /// CSS selector of boilerplate elements removed before extraction.
+    pub remove_selectors: Option<String>,
+    /// Drop query strings from extracted link URLs.
+    pub strip_query: Option<bool>,
+    /// Overrides `extraction_mode`.
+    pub extraction_mode: Option<String>,
+    /// Overrides the minimum content length.
+    pub min_content_length: Option<usize>,
+    /// Overrides the maximum content length.
+    pub max_content_length: Option<usize>,
+}
+
+impl DomainProfile {
+    /// Applies the profile's length limits on top of `base`.
+    pub fn length_gate(&self, base: LengthGate) -> LengthGate {
+        LengthGate {
+            min: self.min_content_length.unwrap_or(base.min),
+            max: self.max_content_length.unwrap_or(base.max),
+        }
+    }
+}
+
+/// The status side of a `status_routes` entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusPattern {
+    /// A single status, e.g. `404`.
+    Exact(u16),
+    /// A status class by its first digit, e.g. `4xx`.
+    Class(u16),
+}
+
+/// What happens to a page with a routed HTTP status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusAction {
+    /// Parse the page normally.
+    Parse,
+    /// Drop it (counted in `pages_skipped_status`).
+    Skip,
+    /// Forward the payload unchanged to `kafka_topic_errors`.
+    Errors,
+    /// Produce the `Location` target to `kafka_topic_redirects`.
+    Redirect,
+}
+
+/// A single `status=action` entry of `status_routes`.
+#[derive(Debug, Clone)]
+pub struct StatusRoute {
+    pub status: StatusPattern,
+    pub action: StatusAction,
+}
+
+impl StatusRoute {
+    /// Parses `404=errors` or `3xx=redirect`, returning `None` for an unknown
+    /// action or a status outside 100–599.
+    pub fn parse(entry: &str) -> Option<Self> {
+        let (status, action) = entry.split_once('=')?;
+        let status = status.trim().to_ascii_lowercase();
+
+        let status = match status.strip_suffix("xx") {
+            Some(class) => {
+                StatusPattern::Class(class.parse().ok().filter(|c| (1..=5).contains(c))?)
+            }
+            None => StatusPattern::Exact(status.parse().ok().filter(|s| (100..=599).contains(s))?),
+        };
+        let action = match action.trim() {
+            "parse" => StatusAction::Parse,
+            "skip" => StatusAction::Skip,
+            "errors" => StatusAction::Errors,
+            "redirect" => StatusAction::Redirect,
+            _ => return None,
+        };
+
+        Some(Self { status, action })
+    }
+}
+
+/// A single `key=selector@data-attr` entry of `data_attr_selectors`.
+#[derive(Debug, Clone)]
+pub struct DataAttrSpec {
+    /// Key under which the value is stored in `additional_metadata`.
+    pub key: String,
+    /// CSS selector of the element carrying the attribute.
+    pub selector: String,
+    /// Name of the `data-*` attribute to read.
+    pub attribute: String,
+}
+
+impl DataAttrSpec {
+    /// Parses `key=selector@data-attr`, returning `None` if any part is missing.
+    pub fn parse(entry: &str) -> Option<Self> {
+        let (key, rest) = entry.split_once('=')?;
+        let (selector, attribute) = rest.rsplit_once('@')?;
+        let (key, selector, attribute) = (key.trim(), selector.trim(), attribute.trim());
+
+        if key.is_empty() || selector.is_empty() || !attribute.starts_with("data-") {
+            return None;
+        }
+
+        Some(Self {
+            key: key.to_string(),
+            selector: selector.to_string(),
+            attribute: attribute.to_string(),
+        })
+    }
+}
+
+/// Splits a comma-separated config list, trimming and dropping empty items.
+fn split_list(value: &str) -> impl Iterator<Item = &str> {
+    value.split(',').map(str::trim).filter(|s| !s.is_empty())
 }
 
 // defaults
@@ -71,9 +880,59 @@ fn default_kafka_topic_parsed() -> String {
 fn default_kafka_group_id() -> String {
     "parser-group".into()
 }
+fn default_auth_url_patterns() -> String {
+    "/login,/log-in,/signin,/sign-in,/register,/signup,/sign-up,/password-reset,/reset-password,/forgot-password".into()
+}
+fn default_timestamp_format() -> String {
+    "rfc3339".into()
+}
+fn default_category_path_separator() -> String {
+    " > ".into()
+}
+fn default_kafka_acks() -> String {
+    "all".into()
+}
+fn default_input_format() -> String {
+    "raw".into()
+}
+fn default_output_sink() -> String {
+    "kafka".into()
+}
+fn default_s3_region() -> String {
+    "us-east-1".into()
+}
+fn default_s3_prefix() -> String {
+    "parsed-pages".into()
+}
+fn default_oversize_min_text_length() -> usize {
+    1024
+}
+fn default_circuit_breaker_threshold() -> u64 {
+    5
+}
+fn default_circuit_breaker_cooldown_secs() -> u64 {
+    30
+}
+fn default_social_platforms() -> String {
+    "twitter=twitter.com,twitter=x.com,facebook=facebook.com,linkedin=linkedin.com,\
+     instagram=instagram.com,youtube=youtube.com,youtube=youtu.be,github=github.com"
+        .into()
+}
+fn default_url_header() -> String {
+    "url".to_string()
+}
+fn default_content_hash_header() -> String {
+    "x-content-hash".into()
+}
+fn default_partition_key_strategy() -> String {
+    "url".into()
+}
 fn default_max_concurrency() -> usize {
     32
 }
+fn default_max_concurrent_sends() -> usize {
+    64
+}
 fn default_max_content_length() -> usize {
     5_242_880
 }
@@ -86,3 +945,159 @@ fn default_log_level() -> String {
 fn default_monitor_port() -> u16 {
     8080
 }
+fn default_startup_timeout_secs() -> u64 {
+    60
+}
+fn default_startup_grace_secs() -> u64 {
+    30
+}
+fn default_heartbeat_secs() -> u64 {
+    30
+}
+fn default_admin_username() -> String {
+    "admin".to_string()
+}
+fn default_seen_filter_bits() -> usize {
+    16_777_216
+}
+fn default_seen_filter_hashes() -> u32 {
+    7
+}
+fn default_duplicate_threshold() -> u64 {
+    1
+}
+fn default_duplicate_cache_size() -> usize {
+    100_000
+}
+fn default_boilerplate_window() -> usize {
+    1000
+}
+fn default_boilerplate_max_distance() -> u32 {
+    3
+}
+fn default_image_batch_size() -> usize {
+    32
+}
+fn default_max_replacement_ratio() -> f64 {
+    0.05
+}
+fn default_extraction_mode() -> String {
+    "full".into()
+}
+fn default_lead_min_length() -> usize {
+    80
+}
+fn default_preview_length() -> usize {
+    300
+}
+fn default_max_dom_depth() -> usize {
+    256
+}
+fn default_true() -> bool {
+    true
+}
+fn default_language_mismatch_min_confidence() -> f64 {
+    0.9
+}
+fn default_readability_noop_ratio() -> f64 {
+    0.95
+}
+fn default_max_outbound_domains() -> usize {
+    100
+}
+fn default_max_token_counts() -> usize {
+    200
+}
+fn default_max_code_blocks() -> usize {
+    50
+}
+fn default_max_anchors() -> usize {
+    200
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitized_redacts_only_credentials() {
+        let config = Config {
+            admin_password: Some("hunter2".to_string()),
+            include_token_counts: true,
+            ..Config::default()
+        };
+        let sanitized = config.sanitized();
+
+        assert_eq!(sanitized["admin_password"], "[REDACTED]");
+        assert_eq!(sanitized["include_token_counts"], true);
+        assert_eq!(sanitized["max_token_counts"], config.max_token_counts);
+    }
+
+    #[test]
+    fn status_action_prefers_exact_routes_over_classes() {
+        let config = Config {
+            status_routes: "4xx=skip,404=errors,3xx=redirect".to_string(),
+            kafka_topic_errors: Some("errors".to_string()),
+            kafka_topic_redirects: Some("redirects".to_string()),
+            ..Config::default()
+        };
+        config.validate().unwrap();
+
+        assert_eq!(config.status_routes().len(), 3);
+        assert_eq!(config.status_action(404), StatusAction::Errors);
+        assert_eq!(config.status_action(410), StatusAction::Skip);
+        assert_eq!(config.status_action(301), StatusAction::Redirect);
+        assert_eq!(config.status_action(200), StatusAction::Parse);
+        assert_eq!(config.status_action(503), StatusAction::Skip);
+    }
+
+    #[test]
+    fn domain_profile_length_limits_follow_the_global_rule() {
+        let profile = |limits: &str| Config {
+            domain_profiles: Some(format!(r#"{{"example.com": {{{limits}}}}}"#)),
+            ..Config::default()
+        };
+
+        assert!(
+            profile(r#""min_content_length": 10, "max_content_length": 20"#)
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            profile(r#""min_content_length": 20, "max_content_length": 20"#)
+                .validate()
+                .is_err()
+        );
+        assert!(
+            profile(r#""min_content_length": 30, "max_content_length": 20"#)
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn capture_meta_names_reject_typed_fields() {
+        let capture = |names: &str| Config {
+            capture_meta_names: names.to_string(),
+            ..Config::default()
+        };
+
+        assert!(capture("referrer, generator").validate().is_ok());
+        assert!(capture("generator,viewport").validate().is_err());
+        assert!(capture("Rating").validate().is_err());
+    }
+
+    #[test]
+    fn outlink_host_filter_requires_every_suffix_valid() {
+        let filter = |value: &str| Config {
+            outlink_host_filter: Some(value.to_string()),
+            ..Config::default()
+        };
+
+        assert!(filter(".gov, example.com").validate().is_ok());
+        assert!(filter("regex:^(www\\.)?example\\.org$").validate().is_ok());
+        assert!(filter("example.com, bad host!").validate().is_err());
+        assert!(filter("example..com").validate().is_err());
+        assert!(filter(" , ").validate().is_err());
+    }
+}
@@ -1,6 +1,39 @@
-use super::Config;
+use super::{split_list, Config, DataAttrSpec, LengthOverride, StatusAction, StatusRoute};
+use regex::Regex;
+use scraper::Selector;
 use std::fmt;
 
+/// Meta names already extracted into typed `ParsedPage` fields.
+const TYPED_META_NAMES: &[&str] = &[
+    "description",
+    "keywords",
+    "robots",
+    "category",
+    "theme-color",
+    "viewport",
+    "rating",
+];
+
+/// Keys the parser itself writes into `additional_metadata`.
+const RESERVED_METADATA_KEYS: &[&str] = &[
+    "raw_title",
+    "stripped_fields",
+    "theme_color",
+    "category_schema_article_section",
+    "category_og_article_section",
+    "category_meta",
+];
+
+/// Whether `suffix` is a host or domain suffix: dot-separated labels of ASCII
+/// letters, digits and hyphens, optionally with a leading dot (`.gov`).
+fn is_host_suffix(suffix: &str) -> bool {
+    let host = suffix.strip_prefix('.').unwrap_or(suffix);
+    !host.is_empty()
+        && host.split('.').all(|label| {
+            !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
 #[derive(Debug)]
 pub struct ConfigError {
     pub field: &'static str,
@@ -29,10 +62,34 @@ pub trait Validate {
 impl Validate for Config {
     fn validate(&self) -> Result<(), ConfigError> {
         self.validate_kafka()?;
+        self.validate_input_format()?;
+        self.validate_kafka_acks()?;
+        self.validate_status_routes()?;
+        self.validate_inline_limits()?;
+        self.validate_timestamp_format()?;
+        self.validate_output_sink()?;
         self.validate_concurrency()?;
         self.validate_content_length()?;
+        self.validate_extraction_mode()?;
+        self.validate_max_dom_depth()?;
+        self.validate_readability_noop_ratio()?;
+        self.validate_language_mismatch_min_confidence()?;
+        self.validate_min_readability_score()?;
+        self.validate_max_token_counts()?;
         self.validate_log_level()?;
         self.validate_monitor_port()?;
+        self.validate_admin()?;
+        self.validate_seen_filter()?;
+        self.validate_duplicate_detection()?;
+        self.validate_boilerplate()?;
+        self.validate_max_replacement_ratio()?;
+        self.validate_content_type_override()?;
+        self.validate_data_attr_selectors()?;
+        self.validate_social_platforms()?;
+        self.validate_content_exclude_selectors()?;
+        self.validate_outlink_host_filter()?;
+        self.validate_capture_meta_names()?;
+        self.validate_domain_profiles()?;
         Ok(())
     }
 }
@@ -71,6 +128,163 @@ impl Config {
                 example: "parser-group",
             });
         }
+        let header = self.content_hash_header.as_str();
+        if header.is_empty() || !header.bytes().all(|b| b.is_ascii_graphic()) {
+            return Err(ConfigError {
+                field: "content_hash_header",
+                value: header.to_string(),
+                reason: "must be a non-empty header name without whitespace",
+                example: "x-content-hash",
+            });
+        }
+        if let Some(client_id) = &self.kafka_client_id {
+            if client_id.trim().is_empty() {
+                return Err(ConfigError {
+                    field: "kafka_client_id",
+                    value: client_id.clone(),
+                    reason: "cannot be empty when set",
+                    example: "parser-eu-1",
+                });
+            }
+        }
+        if let Some(topic) = &self.kafka_topic_images {
+            if topic.trim().is_empty() {
+                return Err(ConfigError {
+                    field: "kafka_topic_images",
+                    value: topic.clone(),
+                    reason: "cannot be empty when set",
+                    example: "parsed-images",
+                });
+            }
+            if self.image_batch_size == 0 {
+                return Err(ConfigError {
+                    field: "image_batch_size",
+                    value: self.image_batch_size.to_string(),
+                    reason: "must be greater than 0",
+                    example: "32",
+                });
+            }
+        }
+        if let Some(topic) = &self.kafka_topic_summary {
+            if topic.trim().is_empty() {
+                return Err(ConfigError {
+                    field: "kafka_topic_summary",
+                    value: topic.clone(),
+                    reason: "cannot be empty when set",
+                    example: "parsed-summaries",
+                });
+            }
+        }
+        if let Some(topic) = &self.kafka_topic_discovered_urls {
+            if topic.trim().is_empty() {
+                return Err(ConfigError {
+                    field: "kafka_topic_discovered_urls",
+                    value: topic.clone(),
+                    reason: "cannot be empty when set",
+                    example: "discovered-urls",
+                });
+            }
+        }
+        if let Some(topic) = &self.kafka_topic_noindex {
+            if topic.trim().is_empty() {
+                return Err(ConfigError {
+                    field: "kafka_topic_noindex",
+                    value: topic.clone(),
+                    reason: "cannot be empty when set",
+                    example: "parsed-pages-noindex",
+                });
+            }
+        }
+        if let Some(topic) = &self.kafka_topic_quarantine {
+            if topic.trim().is_empty() {
+                return Err(ConfigError {
+                    field: "kafka_topic_quarantine",
+                    value: topic.clone(),
+                    reason: "cannot be empty when set",
+                    example: "parsed-pages-quarantine",
+                });
+            }
+        }
+        if let Some(topic) = &self.kafka_topic_low_quality {
+            if topic.trim().is_empty() {
+                return Err(ConfigError {
+                    field: "kafka_topic_low_quality",
+                    value: topic.clone(),
+                    reason: "cannot be empty when set",
+                    example: "parsed-pages-low-quality",
+                });
+            }
+        }
+        for (field, topic, example) in [
+            (
+                "kafka_topic_link_overflow",
+                &self.kafka_topic_link_overflow,
+                "parsed-links-overflow",
+            ),
+            (
+                "kafka_topic_image_overflow",
+                &self.kafka_topic_image_overflow,
+                "parsed-images-overflow",
+            ),
+        ] {
+            if topic.as_ref().is_some_and(|topic| topic.trim().is_empty()) {
+                return Err(ConfigError {
+                    field,
+                    value: topic.clone().unwrap_or_default(),
+                    reason: "cannot be empty when set",
+                    example,
+                });
+            }
+        }
+        if let Some(topic) = &self.kafka_topic_errors {
+            if topic.trim().is_empty() {
+                return Err(ConfigError {
+                    field: "kafka_topic_errors",
+                    value: topic.clone(),
+                    reason: "cannot be empty when set",
+                    example: "crawl-errors",
+                });
+            }
+        }
+        if let Some(topic) = &self.kafka_topic_redirects {
+            if topic.trim().is_empty() {
+                return Err(ConfigError {
+                    field: "kafka_topic_redirects",
+                    value: topic.clone(),
+                    reason: "cannot be empty when set",
+                    example: "crawl-redirects",
+                });
+            }
+        }
+        if let Some(topic) = &self.kafka_topic_validation {
+            if topic.trim().is_empty() {
+                return Err(ConfigError {
+                    field: "kafka_topic_validation",
+                    value: topic.clone(),
+                    reason: "cannot be empty when set",
+                    example: "parsed-pages-validation",
+                });
+            }
+        }
+        if let Some(topic) = &self.kafka_topic_dead_letter {
+            if topic.trim().is_empty() {
+                return Err(ConfigError {
+                    field: "kafka_topic_dead_letter",
+                    value: topic.clone(),
+                    reason: "cannot be empty when set",
+                    example: "parser-dead-letter",
+                });
+            }
+        }
+        let valid_strategies = ["url", "domain", "canonical"];
+        if !valid_strategies.contains(&self.partition_key_strategy.as_str()) {
+            return Err(ConfigError {
+                field: "partition_key_strategy",
+                value: self.partition_key_strategy.clone(),
+                reason: "must be one of: url, domain, canonical",
+                example: "domain",
+            });
+        }
         Ok(())
     }
 
@@ -83,6 +297,162 @@ impl Config {
                 example: "8",
             });
         }
+        if self.circuit_breaker_threshold > 0
+            && !(1..=300).contains(&self.circuit_breaker_cooldown_secs)
+        {
+            // Longer pauses would exceed the consumer's max.poll.interval.ms and
+            // trigger a rebalance.
+            return Err(ConfigError {
+                field: "circuit_breaker_cooldown_secs",
+                value: self.circuit_breaker_cooldown_secs.to_string(),
+                reason: "must be between 1 and 300",
+                example: "30",
+            });
+        }
+        if self.max_concurrent_sends == 0 {
+            return Err(ConfigError {
+                field: "max_concurrent_sends",
+                value: self.max_concurrent_sends.to_string(),
+                reason: "must be greater than 0",
+                example: "64",
+            });
+        }
+        Ok(())
+    }
+
+    fn validate_timestamp_format(&self) -> Result<(), ConfigError> {
+        let valid_formats = ["rfc3339", "epoch_millis", "epoch_secs"];
+        if !valid_formats.contains(&self.timestamp_format.as_str()) {
+            return Err(ConfigError {
+                field: "timestamp_format",
+                value: self.timestamp_format.clone(),
+                reason: "must be one of: rfc3339, epoch_millis, epoch_secs",
+                example: "epoch_millis",
+            });
+        }
+        Ok(())
+    }
+
+    fn validate_input_format(&self) -> Result<(), ConfigError> {
+        let valid_formats = ["raw", "json_envelope"];
+        if !valid_formats.contains(&self.input_format.as_str()) {
+            return Err(ConfigError {
+                field: "input_format",
+                value: self.input_format.clone(),
+                reason: "must be one of: raw, json_envelope",
+                example: "raw",
+            });
+        }
+        Ok(())
+    }
+
+    fn validate_kafka_acks(&self) -> Result<(), ConfigError> {
+        let valid_acks = ["0", "1", "all"];
+        if !valid_acks.contains(&self.kafka_acks.as_str()) {
+            return Err(ConfigError {
+                field: "kafka_acks",
+                value: self.kafka_acks.clone(),
+                reason: "must be one of: 0, 1, all",
+                example: "all",
+            });
+        }
+        Ok(())
+    }
+
+    fn validate_inline_limits(&self) -> Result<(), ConfigError> {
+        for (field, limit, topic, reason) in [
+            (
+                "inline_links_limit",
+                self.inline_links_limit,
+                &self.kafka_topic_link_overflow,
+                "requires kafka_topic_link_overflow for the overflow when greater than 0",
+            ),
+            (
+                "inline_images_limit",
+                self.inline_images_limit,
+                &self.kafka_topic_image_overflow,
+                "requires kafka_topic_image_overflow for the overflow when greater than 0",
+            ),
+        ] {
+            if limit > 0 && topic.is_none() {
+                return Err(ConfigError {
+                    field,
+                    value: limit.to_string(),
+                    reason,
+                    example: "500",
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_status_routes(&self) -> Result<(), ConfigError> {
+        for entry in split_list(&self.status_routes) {
+            let Some(route) = StatusRoute::parse(entry) else {
+                return Err(ConfigError {
+                    field: "status_routes",
+                    value: entry.to_string(),
+                    reason: "each entry must be status=action with a status like 404 or 4xx and an action of parse, skip, errors or redirect",
+                    example: "3xx=redirect,4xx=errors,5xx=errors",
+                });
+            };
+            let (topic, reason) = match route.action {
+                StatusAction::Errors => (
+                    &self.kafka_topic_errors,
+                    "the errors action requires kafka_topic_errors",
+                ),
+                StatusAction::Redirect => (
+                    &self.kafka_topic_redirects,
+                    "the redirect action requires kafka_topic_redirects",
+                ),
+                StatusAction::Parse | StatusAction::Skip => continue,
+            };
+            if topic.is_none() {
+                return Err(ConfigError {
+                    field: "status_routes",
+                    value: entry.to_string(),
+                    reason,
+                    example: "3xx=redirect,4xx=errors,5xx=errors",
+                });
+            }
+        }
+        // Parse the routes once here instead of per message.
+        self.status_routes();
+        Ok(())
+    }
+
+    fn validate_output_sink(&self) -> Result<(), ConfigError> {
+        let valid_sinks = ["kafka", "s3", "both", "stdout"];
+        if !valid_sinks.contains(&self.output_sink.as_str()) {
+            return Err(ConfigError {
+                field: "output_sink",
+                value: self.output_sink.clone(),
+                reason: "must be one of: kafka, s3, both, stdout",
+                example: "kafka",
+            });
+        }
+
+        if matches!(self.output_sink.as_str(), "s3" | "both") {
+            let bucket = self.s3_bucket.as_deref().unwrap_or_default();
+            if bucket.trim().is_empty() {
+                return Err(ConfigError {
+                    field: "s3_bucket",
+                    value: bucket.to_string(),
+                    reason: "is required when output_sink includes s3",
+                    example: "sneakdex-pages",
+                });
+            }
+            if let Some(endpoint) = &self.s3_endpoint {
+                if url::Url::parse(endpoint).is_err() {
+                    return Err(ConfigError {
+                        field: "s3_endpoint",
+                        value: endpoint.clone(),
+                        reason: "must be a valid URL",
+                        example: "http://minio:9000",
+                    });
+                }
+            }
+        }
         Ok(())
     }
 
@@ -96,6 +466,99 @@ impl Config {
             });
         }
 
+        for entry in split_list(&self.content_length_overrides) {
+            let Some(length_override) = LengthOverride::parse(entry) else {
+                return Err(ConfigError {
+                    field: "content_length_overrides",
+                    value: entry.to_string(),
+                    reason: "each entry must be content-type=min:max with numeric limits",
+                    example: "text/plain=50:1048576",
+                });
+            };
+            let gate = length_override.apply(self.default_length_gate());
+            if gate.max <= gate.min {
+                return Err(ConfigError {
+                    field: "content_length_overrides",
+                    value: entry.to_string(),
+                    reason: "max must be greater than min (after applying global defaults)",
+                    example: "text/plain=50:1048576",
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_extraction_mode(&self) -> Result<(), ConfigError> {
+        let valid_modes = ["full", "body", "metadata_only"];
+        if !valid_modes.contains(&self.extraction_mode.as_str()) {
+            return Err(ConfigError {
+                field: "extraction_mode",
+                value: self.extraction_mode.clone(),
+                reason: "must be one of: full, body, metadata_only",
+                example: "full",
+            });
+        }
+        Ok(())
+    }
+
+    fn validate_max_dom_depth(&self) -> Result<(), ConfigError> {
+        if self.max_dom_depth == 0 {
+            return Err(ConfigError {
+                field: "max_dom_depth",
+                value: self.max_dom_depth.to_string(),
+                reason: "must be greater than 0",
+                example: "256",
+            });
+        }
+        Ok(())
+    }
+
+    fn validate_readability_noop_ratio(&self) -> Result<(), ConfigError> {
+        if !(self.readability_noop_ratio > 0.0 && self.readability_noop_ratio <= 1.0) {
+            return Err(ConfigError {
+                field: "readability_noop_ratio",
+                value: self.readability_noop_ratio.to_string(),
+                reason: "must be in (0, 1]",
+                example: "0.95",
+            });
+        }
+        Ok(())
+    }
+
+    fn validate_language_mismatch_min_confidence(&self) -> Result<(), ConfigError> {
+        if !(0.0..=1.0).contains(&self.language_mismatch_min_confidence) {
+            return Err(ConfigError {
+                field: "language_mismatch_min_confidence",
+                value: self.language_mismatch_min_confidence.to_string(),
+                reason: "must be between 0 and 1",
+                example: "0.9",
+            });
+        }
+        Ok(())
+    }
+
+    fn validate_max_token_counts(&self) -> Result<(), ConfigError> {
+        if self.include_token_counts && self.max_token_counts == 0 {
+            return Err(ConfigError {
+                field: "max_token_counts",
+                value: self.max_token_counts.to_string(),
+                reason: "must be greater than 0 when include_token_counts is set",
+                example: "200",
+            });
+        }
+        Ok(())
+    }
+
+    fn validate_min_readability_score(&self) -> Result<(), ConfigError> {
+        if !(0.0..=100.0).contains(&self.min_readability_score) {
+            return Err(ConfigError {
+                field: "min_readability_score",
+                value: self.min_readability_score.to_string(),
+                reason: "must be between 0 and 100",
+                example: "30",
+            });
+        }
         Ok(())
     }
 
@@ -113,7 +576,7 @@ impl Config {
     }
 
     fn validate_monitor_port(&self) -> Result<(), ConfigError> {
-        if self.monitor_port <= 0 {
+        if self.monitor_port == 0 {
             return Err(ConfigError {
                 field: "monitor_port",
                 value: self.monitor_port.to_string(),
@@ -123,4 +586,247 @@ impl Config {
         }
         Ok(())
     }
+
+    fn validate_admin(&self) -> Result<(), ConfigError> {
+        if !self.admin_enabled {
+            return Ok(());
+        }
+        if self.admin_username.trim().is_empty() {
+            return Err(ConfigError {
+                field: "admin_username",
+                value: self.admin_username.clone(),
+                reason: "cannot be empty when admin_enabled is set",
+                example: "admin",
+            });
+        }
+        if self
+            .admin_password
+            .as_deref()
+            .unwrap_or_default()
+            .is_empty()
+        {
+            return Err(ConfigError {
+                field: "admin_password",
+                value: String::new(),
+                reason: "is required when admin_enabled is set",
+                example: "a-long-random-string",
+            });
+        }
+        Ok(())
+    }
+
+    fn validate_seen_filter(&self) -> Result<(), ConfigError> {
+        if !self.seen_filter_enabled {
+            return Ok(());
+        }
+        if self.seen_filter_bits < 64 {
+            return Err(ConfigError {
+                field: "seen_filter_bits",
+                value: self.seen_filter_bits.to_string(),
+                reason: "must be at least 64",
+                example: "16777216",
+            });
+        }
+        if self.seen_filter_hashes == 0 || self.seen_filter_hashes > 32 {
+            return Err(ConfigError {
+                field: "seen_filter_hashes",
+                value: self.seen_filter_hashes.to_string(),
+                reason: "must be between 1 and 32",
+                example: "7",
+            });
+        }
+        Ok(())
+    }
+
+    fn validate_duplicate_detection(&self) -> Result<(), ConfigError> {
+        if self.duplicate_detection_enabled && self.duplicate_cache_size == 0 {
+            return Err(ConfigError {
+                field: "duplicate_cache_size",
+                value: self.duplicate_cache_size.to_string(),
+                reason: "must be greater than 0 when duplicate detection is enabled",
+                example: "100000",
+            });
+        }
+        Ok(())
+    }
+
+    fn validate_boilerplate(&self) -> Result<(), ConfigError> {
+        if self.boilerplate_max_length == 0 {
+            return Ok(());
+        }
+        if self.boilerplate_window == 0 {
+            return Err(ConfigError {
+                field: "boilerplate_window",
+                value: self.boilerplate_window.to_string(),
+                reason: "must be greater than 0 when the boilerplate filter is enabled",
+                example: "1000",
+            });
+        }
+        if self.boilerplate_max_distance > 32 {
+            return Err(ConfigError {
+                field: "boilerplate_max_distance",
+                value: self.boilerplate_max_distance.to_string(),
+                reason: "must be at most 32 bits of the 64-bit simhash",
+                example: "3",
+            });
+        }
+        Ok(())
+    }
+
+    fn validate_max_replacement_ratio(&self) -> Result<(), ConfigError> {
+        if !(0.0..=1.0).contains(&self.max_replacement_ratio) {
+            return Err(ConfigError {
+                field: "max_replacement_ratio",
+                value: self.max_replacement_ratio.to_string(),
+                reason: "must be between 0 and 1",
+                example: "0.05",
+            });
+        }
+        Ok(())
+    }
+
+    fn validate_content_type_override(&self) -> Result<(), ConfigError> {
+        if let Some(content_type) = &self.content_type_override {
+            if !content_type.contains('/') || content_type.trim() != content_type {
+                return Err(ConfigError {
+                    field: "content_type_override",
+                    value: content_type.clone(),
+                    reason: "must be a MIME type without surrounding whitespace",
+                    example: "text/html",
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_data_attr_selectors(&self) -> Result<(), ConfigError> {
+        for entry in split_list(&self.data_attr_selectors) {
+            let valid = DataAttrSpec::parse(entry)
+                .is_some_and(|spec| Selector::parse(&spec.selector).is_ok());
+            if !valid {
+                return Err(ConfigError {
+                    field: "data_attr_selectors",
+                    value: entry.to_string(),
+                    reason: "each entry must be key=selector@data-attr with a valid CSS selector",
+                    example: "product_id=.product@data-product-id",
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_social_platforms(&self) -> Result<(), ConfigError> {
+        if split_list(&self.social_platforms).count() != self.social_platforms().len() {
+            return Err(ConfigError {
+                field: "social_platforms",
+                value: self.social_platforms.clone(),
+                reason: "each entry must be platform=domain",
+                example: "twitter=x.com,mastodon=mastodon.social",
+            });
+        }
+        Ok(())
+    }
+
+    fn validate_content_exclude_selectors(&self) -> Result<(), ConfigError> {
+        if let Some(selector) = &self.content_exclude_selectors {
+            if Selector::parse(selector).is_err() {
+                return Err(ConfigError {
+                    field: "content_exclude_selectors",
+                    value: selector.clone(),
+                    reason: "must be a valid CSS selector list",
+                    example: ".share-buttons, .newsletter-signup",
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_outlink_host_filter(&self) -> Result<(), ConfigError> {
+        if let Some(filter) = &self.outlink_host_filter {
+            let valid = match filter.strip_prefix("regex:") {
+                Some(pattern) => Regex::new(pattern).is_ok(),
+                None => {
+                    split_list(filter).next().is_some() && split_list(filter).all(is_host_suffix)
+                }
+            };
+            if !valid {
+                return Err(ConfigError {
+                    field: "outlink_host_filter",
+                    value: filter.clone(),
+                    reason: "must be a comma-separated list of host suffixes or regex:<pattern>",
+                    example: ".gov,example.com",
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_capture_meta_names(&self) -> Result<(), ConfigError> {
+        let data_attr_keys: Vec<String> = self
+            .data_attr_specs()
+            .into_iter()
+            .map(|spec| spec.key)
+            .collect();
+
+        for name in self.capture_meta_names() {
+            let collides = TYPED_META_NAMES.contains(&name.as_str())
+                || RESERVED_METADATA_KEYS.contains(&name.as_str())
+                || data_attr_keys.contains(&name);
+            if collides {
+                return Err(ConfigError {
+                    field: "capture_meta_names",
+                    value: name,
+                    reason:
+                        "must not collide with a typed field or another additional_metadata key",
+                    example: "rating,referrer,generator",
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_domain_profiles(&self) -> Result<(), ConfigError> {
+        let profiles = self.domain_profiles().map_err(|e| ConfigError {
+            field: "domain_profiles",
+            value: e,
+            reason: "must be a JSON object mapping host to profile, inline or in a readable file",
+            example: r#"{"example.com": {"content_selector": "article", "strip_query": true}}"#,
+        })?;
+
+        for (host, profile) in &profiles {
+            let selectors = [&profile.content_selector, &profile.remove_selectors];
+            for selector in selectors.into_iter().flatten() {
+                if Selector::parse(selector).is_err() {
+                    return Err(ConfigError {
+                        field: "domain_profiles",
+                        value: format!("{host}: {selector}"),
+                        reason: "content_selector and remove_selectors must be valid CSS selectors",
+                        example: "article.post-body",
+                    });
+                }
+            }
+
+            if let Some(mode) = &profile.extraction_mode {
+                if !["full", "body", "metadata_only"].contains(&mode.as_str()) {
+                    return Err(ConfigError {
+                        field: "domain_profiles",
+                        value: format!("{host}: {mode}"),
+                        reason: "extraction_mode must be one of: full, body, metadata_only",
+                        example: "full",
+                    });
+                }
+            }
+
+            let gate = profile.length_gate(self.default_length_gate());
+            if gate.max <= gate.min {
+                return Err(ConfigError {
+                    field: "domain_profiles",
+                    value: format!("{host}: {}..{}", gate.min, gate.max),
+                    reason: "max_content_length must be greater than min_content_length",
+                    example: "100",
+                });
+            }
+        }
+        Ok(())
+    }
 }
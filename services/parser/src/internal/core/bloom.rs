@@ -0,0 +1,61 @@
+//! Lock-free, in-memory bloom filter of already-processed URLs.
+//!
+//! A hit means the URL was *probably* seen before: with `m` bits, `k` hashes
+//! and `n` inserted URLs the false-positive rate is roughly
+//! `(1 - e^(-k·n/m))^k`, so an undersized filter starts skipping pages that
+//! were never processed. Misses are always exact. The filter lives only as
+//! long as the process and is never cleared.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Bloom filter backed by atomic words so workers can share it without locking.
+pub struct BloomFilter {
+    words: Vec<AtomicU64>,
+    num_bits: u64,
+    num_hashes: u32,
+}
+
+impl BloomFilter {
+    /// Creates a filter with `num_bits` bits (rounded up to a multiple of 64)
+    /// and `num_hashes` hash functions.
+    pub fn new(num_bits: usize, num_hashes: u32) -> Self {
+        let num_words = num_bits.div_ceil(64).max(1);
+        Self {
+            words: (0..num_words).map(|_| AtomicU64::new(0)).collect(),
+            num_bits: num_words as u64 * 64,
+            num_hashes: num_hashes.max(1),
+        }
+    }
+
+    /// Returns `true` if `key` was probably inserted before.
+    pub fn contains(&self, key: &str) -> bool {
+        self.bit_indexes(key).all(|bit| {
+            let word = self.words[(bit / 64) as usize].load(Ordering::Relaxed);
+            word & (1 << (bit % 64)) != 0
+        })
+    }
+
+    /// Records `key` as seen.
+    pub fn insert(&self, key: &str) {
+        for bit in self.bit_indexes(key) {
+            self.words[(bit / 64) as usize].fetch_or(1 << (bit % 64), Ordering::Relaxed);
+        }
+    }
+
+    /// Bit positions for `key` using double hashing (`h1 + i·h2`).
+    fn bit_indexes(&self, key: &str) -> impl Iterator<Item = u64> + '_ {
+        let h1 = hash_with_seed(key, 0);
+        let h2 = hash_with_seed(key, 0x9e37_79b9_7f4a_7c15) | 1;
+        (0..self.num_hashes as u64)
+            .map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % self.num_bits)
+    }
+}
+
+fn hash_with_seed(key: &str, seed: u64) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    seed.hash(&mut hasher);
+    key.hash(&mut hasher);
+    hasher.finish()
+}
@@ -0,0 +1,93 @@
+//! In-memory detection of boilerplate-only pages.
+//!
+//! Pages that are nothing but navigation and template chrome produce a short
+//! `cleaned_text` that is nearly identical across a site. Short pages are
+//! fingerprinted with a 64-bit simhash over word shingles; a page whose
+//! fingerprint is within `max_distance` bits of one of the last `window`
+//! short pages joins that cluster and is dropped. The first page of a cluster
+//! is always kept. Like duplicate detection this is per process, and a
+//! cluster evicted from the window starts over.
+
+use hashlink::LruCache;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+
+/// Words per shingle hashed into the simhash.
+const SHINGLE_WORDS: usize = 3;
+
+/// Recent short-page fingerprints, with how many pages matched each.
+pub struct BoilerplateFilter {
+    clusters: Mutex<LruCache<u64, u64>>,
+    max_length: usize,
+    max_distance: u32,
+}
+
+impl BoilerplateFilter {
+    /// Creates a filter for pages under `max_length` characters, remembering
+    /// `window` recent fingerprints.
+    pub fn new(max_length: usize, window: usize, max_distance: u32) -> Self {
+        Self {
+            clusters: Mutex::new(LruCache::new(window.max(1))),
+            max_length,
+            max_distance,
+        }
+    }
+
+    /// Records a page's text and returns whether it is boilerplate: shorter
+    /// than `max_length` and close to a recently seen short page.
+    pub fn is_boilerplate(&self, cleaned_text: &str) -> bool {
+        if cleaned_text.chars().count() >= self.max_length {
+            return false;
+        }
+        let Some(hash) = simhash(cleaned_text) else {
+            return false;
+        };
+
+        let mut clusters = self
+            .clusters
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let cluster = clusters
+            .iter()
+            .map(|(fingerprint, _)| *fingerprint)
+            .find(|fingerprint| (fingerprint ^ hash).count_ones() <= self.max_distance);
+
+        match cluster.and_then(|fingerprint| clusters.get_mut(&fingerprint)) {
+            Some(count) => {
+                *count += 1;
+                true
+            }
+            None => {
+                clusters.insert(hash, 1);
+                false
+            }
+        }
+    }
+}
+
+/// 64-bit simhash of lowercased word shingles; `None` for text without words.
+fn simhash(text: &str) -> Option<u64> {
+    let words: Vec<String> = text.split_whitespace().map(str::to_lowercase).collect();
+    if words.is_empty() {
+        return None;
+    }
+
+    let mut weights = [0i64; 64];
+    for shingle in words.windows(SHINGLE_WORDS.min(words.len())) {
+        let mut hasher = DefaultHasher::new();
+        shingle.hash(&mut hasher);
+        let hash = hasher.finish();
+        for (bit, weight) in weights.iter_mut().enumerate() {
+            *weight += if hash >> bit & 1 == 1 { 1 } else { -1 };
+        }
+    }
+
+    Some(
+        weights
+            .iter()
+            .enumerate()
+            .filter(|(_, weight)| **weight > 0)
+            .fold(0, |hash, (bit, _)| hash | 1 << bit),
+    )
+}
@@ -0,0 +1,82 @@
+//! Circuit breaker pausing consumption while producing keeps failing.
+//!
+//! After `threshold` consecutive produce errors the breaker opens: the
+//! processing loop pauses its partitions and stops receiving for `cooldown`,
+//! so no further offsets are consumed for pages that can't be delivered, while
+//! the failed ones wait out the cooldown before their retry. When it ends the
+//! breaker is half-open and consumption resumes as a probe; the next success
+//! closes it, the next failure opens it again.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::internal::monitor::{BreakerState, Metrics};
+
+/// Tracks consecutive produce failures and the open/half-open/closed state.
+pub struct CircuitBreaker {
+    threshold: u64,
+    cooldown: Duration,
+    failures: AtomicU64,
+    /// `Some(until)` while open.
+    open_until: Mutex<Option<Instant>>,
+    metrics: Arc<Metrics>,
+}
+
+impl CircuitBreaker {
+    /// Creates a closed breaker tripping after `threshold` consecutive failures.
+    pub fn new(threshold: u64, cooldown: Duration, metrics: Arc<Metrics>) -> Self {
+        Self {
+            threshold,
+            cooldown,
+            failures: AtomicU64::new(0),
+            open_until: Mutex::new(None),
+            metrics,
+        }
+    }
+
+    /// Records a delivered page, closing the breaker if it was probing.
+    pub fn record_success(&self) {
+        self.failures.store(0, Ordering::Relaxed);
+        if self.metrics.get_breaker_state() != BreakerState::Closed {
+            info!("Circuit breaker closed: producing succeeded again");
+            self.metrics.set_breaker_state(BreakerState::Closed);
+        }
+    }
+
+    /// Records a produce error, opening the breaker at the threshold (or on any
+    /// failure while probing).
+    pub fn record_failure(&self) {
+        let failures = self.failures.fetch_add(1, Ordering::Relaxed) + 1;
+        let probing = self.metrics.get_breaker_state() == BreakerState::HalfOpen;
+        if failures < self.threshold && !probing {
+            return;
+        }
+
+        let mut open_until = self.open_until.lock().unwrap_or_else(|e| e.into_inner());
+        if open_until.is_none() {
+            warn!(
+                "Circuit breaker opened after {} consecutive produce failures; pausing consumption for {}s",
+                failures,
+                self.cooldown.as_secs()
+            );
+            *open_until = Some(Instant::now() + self.cooldown);
+            self.metrics.set_breaker_state(BreakerState::Open);
+        }
+    }
+
+    /// Remaining cooldown while the breaker is open.
+    pub fn open_for(&self) -> Option<Duration> {
+        let open_until = self.open_until.lock().unwrap_or_else(|e| e.into_inner());
+        open_until.map(|until| until.saturating_duration_since(Instant::now()))
+    }
+
+    /// Ends the cooldown and lets consumption resume as a probe.
+    pub fn half_open(&self) {
+        *self.open_until.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        self.failures.store(0, Ordering::Relaxed);
+        self.metrics.set_breaker_state(BreakerState::HalfOpen);
+        info!("Circuit breaker half-open: resuming consumption to probe the output");
+    }
+}
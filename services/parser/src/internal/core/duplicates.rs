@@ -0,0 +1,72 @@
+//! In-memory frequency tracking of page titles and descriptions.
+//!
+//! Templated sites repeat the same title or description across many pages.
+//! Each field keeps an LRU of recently seen values with how often they
+//! occurred; a value seen more than `threshold` times is flagged as a
+//! duplicate. This is an approximation: only 64-bit hashes are stored (so two
+//! different strings can, very rarely, collide), counts are per process, and a
+//! value evicted from the LRU starts counting from zero again.
+
+use hashlink::LruCache;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+
+use crate::internal::parser::models::ParsedPage;
+use crate::internal::parser::PLACEHOLDER_TITLE;
+
+/// Bounded occurrence counts for titles and descriptions.
+pub struct DuplicateTracker {
+    titles: Mutex<LruCache<u64, u64>>,
+    descriptions: Mutex<LruCache<u64, u64>>,
+    threshold: u64,
+}
+
+impl DuplicateTracker {
+    /// Creates a tracker remembering up to `capacity` values per field.
+    pub fn new(capacity: usize, threshold: u64) -> Self {
+        Self {
+            titles: Mutex::new(LruCache::new(capacity.max(1))),
+            descriptions: Mutex::new(LruCache::new(capacity.max(1))),
+            threshold,
+        }
+    }
+
+    /// Records the page's title and description and sets its duplicate flags.
+    pub fn observe(&self, page: &mut ParsedPage) {
+        // Every untitled page shares the placeholder; never flag it.
+        if page.title != PLACEHOLDER_TITLE {
+            page.title_is_duplicate = self.record(&self.titles, &page.title);
+        }
+        if let Some(description) = &page.description {
+            page.description_is_duplicate = self.record(&self.descriptions, description);
+        }
+    }
+
+    /// Bumps `value`'s count and returns whether it now exceeds the threshold.
+    fn record(&self, cache: &Mutex<LruCache<u64, u64>>, value: &str) -> bool {
+        if value.is_empty() {
+            return false;
+        }
+
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        let key = hasher.finish();
+
+        let mut cache = cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let count = match cache.get_mut(&key) {
+            Some(count) => {
+                *count += 1;
+                *count
+            }
+            None => {
+                cache.insert(key, 1);
+                1
+            }
+        };
+
+        count > self.threshold
+    }
+}
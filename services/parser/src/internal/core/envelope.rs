@@ -0,0 +1,48 @@
+//! JSON-wrapped crawl messages (`input_format = json_envelope`).
+//!
+//! Some crawlers send one JSON object per page instead of a raw HTML payload
+//! keyed by URL:
+//!
+//! ```json
+//! {"url": "https://example.com/", "html": "<html>…", "status": 200,
+//!  "headers": {"Content-Type": "text/html; charset=utf-8"}}
+//! ```
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// A crawled page wrapped in a JSON envelope.
+#[derive(Debug, Deserialize)]
+pub struct CrawlEnvelope {
+    /// Page URL; the Kafka key is used when missing.
+    #[serde(default)]
+    pub url: Option<String>,
+    /// The HTML document.
+    pub html: String,
+    /// HTTP status of the fetch, routed by `status_routes`; a missing status is treated as 200.
+    #[serde(default)]
+    pub status: Option<u16>,
+    /// HTTP response headers.
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+impl CrawlEnvelope {
+    /// Parses an envelope from a message payload.
+    ///
+    /// # Errors
+    /// Returns an error if the payload is not a JSON envelope with an `html` string.
+    pub fn parse(payload: &[u8]) -> Result<Self> {
+        serde_json::from_slice(payload).context("Invalid JSON crawl envelope")
+    }
+
+    /// Returns a response header, matching the name case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.trim())
+            .filter(|value| !value.is_empty())
+    }
+}
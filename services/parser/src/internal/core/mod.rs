@@ -4,26 +4,64 @@
 //! from Kafka, parses them, and produces structured `ParsedPage` messages
 //! back to another Kafka topic.
 
+mod bloom;
+mod boilerplate;
+mod breaker;
+mod duplicates;
+mod envelope;
+mod offsets;
+mod retry;
+mod s3;
+mod sink;
+mod source;
+
 use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use futures::future::join_all;
 use rdkafka::config::ClientConfig;
-use rdkafka::consumer::{Consumer, StreamConsumer};
-use rdkafka::message::Message;
+use rdkafka::consumer::Consumer;
+use rdkafka::message::{Header, Headers, Message, OwnedHeaders, OwnedMessage};
 use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::Offset;
+use serde::Serialize;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use tokio::sync::Semaphore;
-use tokio::time::{sleep, Duration};
+use tokio::sync::{Notify, Semaphore};
+use tokio::time::{sleep, timeout, Duration};
 use tracing::{debug, error, info, warn};
 
-use crate::internal::config::Config;
+use crate::internal::config::{Config, StatusAction};
 use crate::internal::monitor::Metrics;
-use crate::internal::parser::HtmlParser;
+use crate::internal::parser::models::{
+    DiscoveredUrlRecord, ImageData, ImageRecord, OverflowRecord, ParsedPage, RedirectRecord,
+    SitemapEntry, SummaryRecord,
+};
+use crate::internal::parser::{DeadLetterError, HtmlParser};
+use bloom::BloomFilter;
+use boilerplate::BoilerplateFilter;
+use breaker::CircuitBreaker;
+use duplicates::DuplicateTracker;
+use envelope::CrawlEnvelope;
+use offsets::OffsetTracker;
+use retry::DeliveryRetry;
+use sink::{build_sinks, Delivery, PageSink, Route};
+use source::{MessageSource, PageConsumer, RebalanceContext};
+
+/// How long the processing loop waits at most for in-flight workers after a shutdown signal.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(10);
 
 /// Handles Kafka interactions: consuming raw HTML and producing parsed pages.
 pub struct KafkaHandler {
-    consumer: StreamConsumer,
+    consumer: Arc<PageConsumer>,
+    /// Commit points of the consumer's partitions, shared with its rebalance callback.
+    offsets: Arc<OffsetTracker>,
     producer: FutureProducer,
     config: Arc<Config>,
+    seen_urls: Option<Arc<BloomFilter>>,
+    duplicates: Option<Arc<DuplicateTracker>>,
+    boilerplate: Option<Arc<BoilerplateFilter>>,
+    breaker: Option<Arc<CircuitBreaker>>,
+    sinks: Arc<Vec<Box<dyn PageSink>>>,
 }
 
 impl KafkaHandler {
@@ -33,27 +71,35 @@ impl KafkaHandler {
     /// configured `kafka_topic_html`.
     ///
     /// # Errors
-    /// Returns an error if the Kafka consumer or producer cannot be created or
-    /// if subscribing to the topic fails.
-    pub async fn new(config: Arc<Config>) -> Result<Self> {
+    /// Returns an error if the Kafka consumer, producer or an output sink cannot
+    /// be created, or if subscribing to the topic fails.
+    pub async fn new(config: Arc<Config>, metrics: &Arc<Metrics>) -> Result<Self> {
         info!("SneakDex Parser Starting...");
         debug!("Configuration: {:?}", config);
 
+        let client_id = config.kafka_client_id();
+        info!("Kafka client id: {}", client_id);
+
         // Initialize Kafka consumer.
-        let consumer: StreamConsumer = ClientConfig::new()
+        let offsets = Arc::new(OffsetTracker::new());
+        let consumer: PageConsumer = ClientConfig::new()
+            .set("client.id", format!("{client_id}-consumer"))
             .set("group.id", &config.kafka_group_id)
             .set("bootstrap.servers", &config.kafka_brokers)
             .set("enable.partition.eof", "false")
             .set("session.timeout.ms", "6000")
-            .set("enable.auto.commit", "true")
-            .create()
+            // Offsets are committed once pages are settled (see `offsets`).
+            .set("enable.auto.commit", "false")
+            .create_with_context(RebalanceContext::new(Arc::clone(&offsets)))
             .context("Failed to create Kafka consumer")?;
 
         // Initialize Kafka producer.
         let producer: FutureProducer = ClientConfig::new()
+            .set("client.id", format!("{client_id}-producer"))
             .set("bootstrap.servers", &config.kafka_brokers)
             .set("message.timeout.ms", "5000")
             .set("compression.type", "snappy")
+            .set("acks", &config.kafka_acks)
             .create()
             .context("Failed to create Kafka producer")?;
 
@@ -63,14 +109,102 @@ impl KafkaHandler {
             .context("Failed to subscribe to topics")?;
 
         info!("Subscribed to topic: {}", config.kafka_topic_html);
+        if config.read_only_validation {
+            warn!(
+                "Read-only validation: group {}, offsets not committed, output to {}",
+                config.kafka_group_id,
+                config
+                    .kafka_topic_validation
+                    .as_deref()
+                    .unwrap_or("nowhere")
+            );
+        }
+
+        let seen_urls = config.seen_filter_enabled.then(|| {
+            info!(
+                "Seen-URL filter enabled ({} bits, {} hashes)",
+                config.seen_filter_bits, config.seen_filter_hashes
+            );
+            Arc::new(BloomFilter::new(
+                config.seen_filter_bits,
+                config.seen_filter_hashes,
+            ))
+        });
+
+        let duplicates = config.duplicate_detection_enabled.then(|| {
+            info!(
+                "Duplicate title/description detection enabled (threshold {}, {} entries)",
+                config.duplicate_threshold, config.duplicate_cache_size
+            );
+            Arc::new(DuplicateTracker::new(
+                config.duplicate_cache_size,
+                config.duplicate_threshold,
+            ))
+        });
+
+        let boilerplate = (config.boilerplate_max_length > 0).then(|| {
+            info!(
+                "Boilerplate filter enabled (pages under {} chars, window {}, distance {})",
+                config.boilerplate_max_length,
+                config.boilerplate_window,
+                config.boilerplate_max_distance
+            );
+            Arc::new(BoilerplateFilter::new(
+                config.boilerplate_max_length,
+                config.boilerplate_window,
+                config.boilerplate_max_distance,
+            ))
+        });
+
+        let breaker = (config.circuit_breaker_threshold > 0).then(|| {
+            Arc::new(CircuitBreaker::new(
+                config.circuit_breaker_threshold,
+                Duration::from_secs(config.circuit_breaker_cooldown_secs),
+                Arc::clone(metrics),
+            ))
+        });
+
+        let sinks = Arc::new(build_sinks(&config, &producer, metrics, breaker.as_ref())?);
+        info!("Output sink: {}", config.output_sink);
 
         Ok(Self {
-            consumer,
+            consumer: Arc::new(consumer),
+            offsets,
             producer,
             config: config.clone(),
+            seen_urls,
+            duplicates,
+            boilerplate,
+            breaker,
+            sinks,
         })
     }
 
+    /// Waits until a broker answers a metadata request for the input topic.
+    ///
+    /// The blocking librdkafka call runs on the blocking pool, so a
+    /// surrounding `startup_timeout_secs` timeout can fire while no broker
+    /// is reachable.
+    pub async fn wait_until_connected(&self) {
+        loop {
+            let consumer = Arc::clone(&self.consumer);
+            let topic = self.config.kafka_topic_html.clone();
+            let probe = tokio::task::spawn_blocking(move || {
+                consumer
+                    .fetch_metadata(Some(&topic), std::time::Duration::from_secs(2))
+                    .map(|_| ())
+            })
+            .await;
+
+            match probe {
+                Ok(Ok(())) => return,
+                Ok(Err(e)) => warn!("Waiting for Kafka brokers: {}", e),
+                Err(e) => warn!("Kafka metadata probe failed: {}", e),
+            }
+            sleep(Duration::from_secs(1)).await;
+        }
+    }
+
     pub async fn is_connected(&self) -> bool {
         let client = self.consumer.client();
         match client.fetch_metadata(None, std::time::Duration::from_secs(2)) {
@@ -82,194 +216,1299 @@ impl KafkaHandler {
         }
     }
 
-    /// Start processing messages in an infinite loop with graceful shutdown.
+    /// Logs throughput, inflight pages and consumer lag every `heartbeat_secs`
+    /// until shutdown.
     ///
-    /// For each message, the HTML payload is parsed using the provided `HtmlParser`
-    /// and the result is sent to the parsed-pages Kafka topic.
-    pub async fn start_processing(
+    /// Runs on its own timer, so a stalled consumer still shows up as a zero
+    /// rate instead of a missing log line.
+    pub async fn run_heartbeat(
         &self,
-        parser: HtmlParser,
         metrics: Arc<Metrics>,
         mut shutdown: tokio::sync::watch::Receiver<bool>,
-        shutdown_tx: tokio::sync::watch::Sender<bool>,
-    ) -> anyhow::Result<()> {
-        let semaphore = Arc::new(Semaphore::new(self.config.max_concurrency));
-
-        info!(
-            "Starting with max {} concurrent workers, waiting for messages...",
-            self.config.max_concurrency
-        );
+    ) {
+        let period = Duration::from_secs(self.config.heartbeat_secs);
+        let mut ticker = tokio::time::interval(period);
+        ticker.tick().await;
+        let mut last_processed = metrics.get_pages_processed();
 
         loop {
             tokio::select! {
-                // watch for shutdown
-                res = shutdown.changed() => {
-                    let _ = shutdown_tx.send(true);
-                    if res.is_ok() {
-                        info!("Shutdown signal received, stopping Kafka processing loop.");
-                        sleep(Duration::from_secs(10)).await;
-                        break;
-                    } else {
-                        error!("Shutdown channel closed unexpectedly.");
-                        sleep(Duration::from_secs(10)).await;
-                        break;
-                    }
-                }
+                _ = shutdown.changed() => break,
+                _ = ticker.tick() => {
+                    let processed = metrics.get_pages_processed();
+                    let rate = (processed - last_processed) as f64 / period.as_secs_f64();
+                    last_processed = processed;
 
-                // process Kafka messages
-                msg_res = self.consumer.recv() => {
-                    let msg = match msg_res {
-                        Ok(msg) => msg,
-                        Err(e) => {
-                            error!("Failed to receive message from Kafka: {}", e);
-                            metrics.inc_kafka_errored();
-                            continue;
-                        }
-                    };
+                    let lag = self
+                        .consumer_lag()
+                        .map_or_else(|| "unknown".to_string(), |lag| lag.to_string());
+                    let last_message_age = metrics
+                        .get_last_message_age()
+                        .await
+                        .map_or_else(|| "none".to_string(), |age| format!("{age}s"));
 
-                    let permit = match semaphore.clone().acquire_owned().await {
-                        Ok(permit) => permit,
-                        Err(e) => {
-                            error!("Semaphore acquisition failed: {}", e);
-                            continue;
-                        }
-                    };
-
-                    let parser_clone = parser.clone();
-                    let metrics_clone = metrics.clone();
-                    let producer_clone = self.producer.clone();
-                    let config_clone = self.config.clone();
-                    let owned_msg = msg.detach();
-
-                    // spawn a task to process the message
-                    tokio::spawn(async move {
-                        if metrics_clone.pages_processed.load(Ordering::Relaxed) % 100 == 0 {
-                            info!(
-                                "Metrics: inflight={}, processed={}, successful={}, failed={}, kafka_ok={}, kafka_fail={}, kafka_err={}",
-                                metrics_clone.get_inflight_pages(),
-                                metrics_clone.get_pages_processed(),
-                                metrics_clone.get_pages_successful(),
-                                metrics_clone.get_pages_failed(),
-                                metrics_clone.get_kafka_successful(),
-                                metrics_clone.get_kafka_failed(),
-                                metrics_clone.get_kafka_errored(),
-                            );
-                        }
-
-                        metrics_clone.inc_pages_processed();
-                        metrics_clone.inc_inflight_pages();
-
-                        if let Err(e) = KafkaHandler::process_message(
-                            &owned_msg,
-                            &parser_clone,
-                            &metrics_clone,
-                            &producer_clone,
-                            Arc::clone(&config_clone),
-                        ).await {
-                            error!("Error processing message: {}", e);
-                            metrics_clone.inc_pages_failed();
-                        }
-
-                        metrics_clone.dec_inflight_pages();
-                        drop(permit); // release the semaphore slot
-                    });
+                    info!(
+                        "Heartbeat: rate={:.2} pages/s, inflight={}, inflight_sends={}, processed={}, failed={}, lag={}, last_message_age={}",
+                        rate,
+                        metrics.get_inflight_pages(),
+                        metrics.get_inflight_sends(),
+                        processed,
+                        metrics.get_pages_failed(),
+                        lag,
+                        last_message_age,
+                    );
                 }
             }
         }
+    }
+
+    /// Messages between the consumer's position and the high watermark, summed
+    /// over assigned partitions. `None` before an assignment or if a broker
+    /// query fails.
+    fn consumer_lag(&self) -> Option<i64> {
+        let positions = self.consumer.position().ok()?;
+        if positions.count() == 0 {
+            return None;
+        }
 
-        info!("Kafka processing loop exited.");
-        Ok(())
+        let mut lag = 0;
+        for element in positions.elements() {
+            let (low, high) = self
+                .consumer
+                .fetch_watermarks(
+                    element.topic(),
+                    element.partition(),
+                    std::time::Duration::from_secs(2),
+                )
+                .ok()?;
+            lag += match element.offset() {
+                Offset::Offset(position) => (high - position).max(0),
+                // Nothing consumed yet on this partition; everything retained is lag.
+                _ => (high - low).max(0),
+            };
+        }
+        Some(lag)
+    }
+
+    /// Start processing messages in an infinite loop with graceful shutdown.
+    ///
+    /// For each message, the HTML payload is parsed using the provided `HtmlParser`
+    /// and the result is sent to the parsed-pages Kafka topic. The parser is
+    /// shared by all workers rather than cloned per message, so its `Config` and
+    /// compiled selectors are built once. Besides the `max_concurrency`
+    /// workers, consumption pauses while the payloads in flight add up to
+    /// `max_inflight_bytes`. A page the output fails to take is retried by its
+    /// worker until it is delivered or shutdown begins.
+    pub async fn start_processing(
+        &self,
+        parser: Arc<HtmlParser>,
+        metrics: Arc<Metrics>,
+        shutdown: tokio::sync::watch::Receiver<bool>,
+        shutdown_tx: tokio::sync::watch::Sender<bool>,
+    ) -> anyhow::Result<()> {
+        let processor = Arc::new(PageProcessor {
+            parser,
+            metrics: Arc::clone(&metrics),
+            producer: self.producer.clone(),
+            sinks: Arc::clone(&self.sinks),
+            config: Arc::clone(&self.config),
+            seen_urls: self.seen_urls.clone(),
+            duplicates: self.duplicates.clone(),
+            boilerplate: self.boilerplate.clone(),
+            retry: DeliveryRetry::new(self.breaker.clone(), shutdown.clone()),
+        });
+
+        run_workers(
+            Arc::clone(&self.consumer),
+            Arc::clone(&self.offsets),
+            processor,
+            &self.config,
+            self.breaker.as_deref(),
+            metrics,
+            shutdown,
+            shutdown_tx,
+        )
+        .await
     }
 
     /// Process a single Kafka message.
     ///
-    /// Decodes the key and payload, parses the HTML, and sends the parsed result
-    /// to the parsed-pages topic.
+    /// Decodes the key and payload (or, with `input_format = json_envelope`, the
+    /// JSON envelope), parses the HTML, and writes the parsed result to every
+    /// output sink. The crawl status, from the envelope or `http_status_header`,
+    /// is looked up in `status_routes`: the page is parsed, skipped, or sent on
+    /// to the errors or redirects topic instead.
+    ///
+    /// With `kafka_topic_discovered_urls`, XML sitemaps are not parsed as
+    /// pages; their URLs are produced to that topic instead. URLs the seen
+    /// filter reports as already processed are skipped without parsing, and
+    /// short pages matching a recent boilerplate cluster are dropped after
+    /// parsing. With `respect_noindex`, pages whose robots meta says `noindex`
+    /// go to `kafka_topic_noindex`, or are dropped.
+    ///
+    /// Sinks failing to write the page, and failed discovered-URL sends, are
+    /// retried as paced by `retry`. Returns `Delivery::Failed` when shutdown
+    /// ended those retries; skipped and dropped pages are settled.
+    #[allow(clippy::too_many_arguments)]
     async fn process_message(
-        message: &rdkafka::message::OwnedMessage,
+        message: &OwnedMessage,
         parser: &HtmlParser,
         metrics: &Arc<Metrics>,
         producer: &FutureProducer,
+        sinks: &[Box<dyn PageSink>],
         config: Arc<Config>,
-    ) -> Result<()> {
-        // Extract URL (key).
-        let url = match message.key() {
-            Some(key) => String::from_utf8_lossy(key).to_string(),
-            None => {
-                bail!("No URL key, page skipped");
+        seen_urls: Option<&BloomFilter>,
+        duplicates: Option<&DuplicateTracker>,
+        boilerplate: Option<&BoilerplateFilter>,
+        retry: &DeliveryRetry,
+    ) -> Result<Delivery> {
+        // With `json_envelope`, URL, HTML and headers come from the payload.
+        let envelope = match config.input_format.as_str() {
+            "json_envelope" => {
+                let Some(data) = message.payload() else {
+                    bail!("No Payload, page skipped");
+                };
+                Some(CrawlEnvelope::parse(data)?)
             }
+            _ => None,
         };
 
-        // Extract HTML payload.
-        let payload = match message.payload() {
-            Some(data) => data,
-            None => {
-                bail!("No Payload, page skipped");
+        let url = envelope
+            .as_ref()
+            .and_then(|e| e.url.clone())
+            .or_else(|| message_url(message, &config.url_header));
+        let Some(url) = url else {
+            bail!("No URL key or header, page skipped");
+        };
+
+        // Route by the crawler's HTTP status (envelope, else the status header).
+        let status = match &envelope {
+            Some(envelope) => envelope.status,
+            None => config
+                .http_status_header
+                .as_deref()
+                .and_then(|name| header_value(message, name))
+                .and_then(|status| status.parse::<u16>().ok()),
+        };
+        if let Some(status) = status {
+            match config.status_action(status) {
+                StatusAction::Parse => {}
+                StatusAction::Skip => {
+                    debug!("Crawl status {}, page skipped: {}", status, url);
+                    metrics.inc_pages_skipped_status();
+                    return Ok(Delivery::Settled);
+                }
+                StatusAction::Errors => {
+                    if let Some(topic) = config.kafka_topic_errors.as_deref() {
+                        KafkaHandler::send_error_page(
+                            topic, &url, message, status, metrics, producer,
+                        )
+                        .await;
+                    }
+                    return Ok(Delivery::Settled);
+                }
+                StatusAction::Redirect => {
+                    let location = match &envelope {
+                        Some(envelope) => envelope.header("location").map(str::to_string),
+                        None => header_value(message, "location"),
+                    };
+                    if let Some(topic) = config.kafka_topic_redirects.as_deref() {
+                        KafkaHandler::send_redirect(
+                            topic,
+                            &url,
+                            location.as_deref(),
+                            status,
+                            metrics,
+                            producer,
+                        )
+                        .await;
+                    }
+                    return Ok(Delivery::Settled);
+                }
             }
+        }
+
+        if seen_urls.is_some_and(|seen| seen.contains(&url)) {
+            debug!("URL probably seen before, page skipped: {}", url);
+            metrics.inc_pages_skipped_seen();
+            return Ok(Delivery::Settled);
+        }
+
+        // Extract HTML payload.
+        let (payload, content_type) = match &envelope {
+            Some(envelope) => (
+                envelope.html.as_bytes(),
+                envelope.header("content-type").map(str::to_string),
+            ),
+            None => match message.payload() {
+                Some(data) => (data, header_value(message, "content-type")),
+                None => {
+                    bail!("No Payload, page skipped");
+                }
+            },
         };
 
-        let html = String::from_utf8_lossy(payload);
+        // With a discovered-URLs topic, sitemaps feed discovery instead of the parser.
+        if let Some(topic) = config.kafka_topic_discovered_urls.as_deref() {
+            if let Some(entries) = parser.parse_sitemap(payload, &url, content_type.as_deref()) {
+                metrics.inc_sitemaps_processed();
+                // Like page sinks, a failing send is retried until it goes through.
+                loop {
+                    let delivery = KafkaHandler::send_discovered_urls(
+                        topic,
+                        &url,
+                        &entries,
+                        config.image_batch_size,
+                        metrics,
+                        producer,
+                    )
+                    .await;
+                    if delivery == Delivery::Settled || !retry.wait().await {
+                        return Ok(delivery);
+                    }
+                    debug!("Retrying discovered URLs of {}", url);
+                }
+            }
+        }
+
         info!("Processing HTML from URL: {}", url);
 
-        // Parse the HTML.
-        match parser.parse_html(&html, &url) {
-            Ok(parsed) => {
+        // Decode and parse the HTML.
+        let delivery = match parser.parse_payload(payload, &url, content_type.as_deref()) {
+            Ok(mut parsed) => {
                 metrics.inc_pages_successful();
-                KafkaHandler::send_parsed_page(
-                    &url,
-                    &parsed,
-                    metrics,
-                    producer,
-                    Arc::clone(&config),
-                )
-                .await?;
+                if let Some(duplicates) = duplicates {
+                    duplicates.observe(&mut parsed);
+                }
+                if let Some(seen) = seen_urls {
+                    seen.insert(&url);
+                }
+
+                if boilerplate.is_some_and(|filter| filter.is_boilerplate(&parsed.cleaned_text)) {
+                    debug!("Boilerplate-only page dropped: {}", url);
+                    metrics.inc_boilerplate_dropped();
+                    return Ok(Delivery::Settled);
+                }
+
+                if parsed.link_farm_suspected {
+                    metrics.inc_link_farm_suspected();
+                }
+                if parsed.readability_score.is_some_and(|score| {
+                    config.min_readability_score > 0.0 && score < config.min_readability_score
+                }) {
+                    metrics.inc_pages_below_readability();
+                }
+                if parsed.title_too_short {
+                    metrics.inc_pages_title_too_short();
+                }
+
+                let noindex = config.respect_noindex && parsed.noindex;
+                if noindex {
+                    metrics.inc_pages_noindex();
+                    if config.kafka_topic_noindex.is_none() {
+                        info!("Dropping noindex page: {}", url);
+                        return Ok(Delivery::Settled);
+                    }
+                }
+
+                // Bound the page: items beyond the inline limits go to side topics.
+                let link_overflow = split_overflow(&mut parsed.links, config.inline_links_limit);
+                let image_overflow = split_overflow(&mut parsed.images, config.inline_images_limit);
+                parsed.has_overflow = !link_overflow.is_empty() || !image_overflow.is_empty();
+                if parsed.has_overflow {
+                    metrics.inc_pages_with_overflow();
+                }
+
+                // Serialize once and hand the page to every sink, retrying
+                // the failing ones until they take it.
+                let json_data =
+                    serde_json::to_string(&parsed).context("Failed to serialize parsed page")?;
+                let route = Route::for_page(&config, &parsed);
+                let mut pending: Vec<&dyn PageSink> = sinks.iter().map(Box::as_ref).collect();
+                loop {
+                    let mut failed = Vec::new();
+                    for sink in pending {
+                        if sink.write(&url, &parsed, &json_data, route).await? == Delivery::Failed {
+                            failed.push(sink);
+                        }
+                    }
+                    pending = failed;
+                    if pending.is_empty() || !retry.wait().await {
+                        break;
+                    }
+                    debug!("Retrying delivery of {}", url);
+                }
+                let delivery = if pending.is_empty() {
+                    Delivery::Settled
+                } else {
+                    Delivery::Failed
+                };
+                if let Some(topic) = config.kafka_topic_summary.as_deref().filter(|_| !noindex) {
+                    KafkaHandler::send_summary(topic, &parsed, metrics, producer).await;
+                }
+                if let Some(topic) = config
+                    .kafka_topic_link_overflow
+                    .as_deref()
+                    .filter(|_| !noindex)
+                {
+                    KafkaHandler::send_overflow(
+                        topic,
+                        &url,
+                        config.inline_links_limit,
+                        &link_overflow,
+                        metrics,
+                        producer,
+                    )
+                    .await;
+                }
+                if let Some(topic) = config
+                    .kafka_topic_image_overflow
+                    .as_deref()
+                    .filter(|_| !noindex)
+                {
+                    KafkaHandler::send_overflow(
+                        topic,
+                        &url,
+                        config.inline_images_limit,
+                        &image_overflow,
+                        metrics,
+                        producer,
+                    )
+                    .await;
+                }
+                if let Some(topic) = config.kafka_topic_images.as_deref().filter(|_| !noindex) {
+                    KafkaHandler::send_images(
+                        topic,
+                        &parsed.url,
+                        parsed.images.iter().chain(&image_overflow),
+                        config.image_batch_size,
+                        metrics,
+                        producer,
+                    )
+                    .await;
+                }
+                delivery
             }
             Err(e) => {
                 error!("Failed to parse HTML from {}: {}", url, e);
+                if let (Some(dead_letter), Some(topic)) = (
+                    e.downcast_ref::<DeadLetterError>(),
+                    config.kafka_topic_dead_letter.as_deref(),
+                ) {
+                    // Forward the message as received (the whole envelope, if any).
+                    KafkaHandler::send_dead_letter(
+                        topic,
+                        &url,
+                        message.payload().unwrap_or_default(),
+                        dead_letter.reason(),
+                        metrics,
+                        producer,
+                    )
+                    .await;
+                }
                 return Err(e);
             }
+        };
+
+        Ok(delivery)
+    }
+
+    /// Produce one `ImageRecord` per image on the page to the images topic.
+    ///
+    /// Records are sent `batch_size` at a time, waiting for each batch to be
+    /// delivered before queueing the next, so image-heavy pages don't flood the
+    /// producer queue. Failures are logged and counted per record.
+    async fn send_images(
+        topic: &str,
+        page_url: &str,
+        images: impl Iterator<Item = &ImageData>,
+        batch_size: usize,
+        metrics: &Arc<Metrics>,
+        producer: &FutureProducer,
+    ) {
+        let records: Vec<String> = images
+            .filter_map(|image| {
+                let record = ImageRecord {
+                    page_url,
+                    src: &image.src,
+                    alt: image.alt.as_deref(),
+                    title: image.title.as_deref(),
+                    width: image.width,
+                    height: image.height,
+                };
+                serde_json::to_string(&record)
+                    .map_err(|e| error!("Failed to serialize image record: {}", e))
+                    .ok()
+            })
+            .collect();
+
+        for batch in records.chunks(batch_size) {
+            let sends = batch.iter().map(|payload| {
+                let record = FutureRecord::to(topic).key(page_url).payload(payload);
+                producer.send(record, Duration::from_secs(0))
+            });
+
+            for result in join_all(sends).await {
+                match result {
+                    Ok(_) => metrics.inc_images_produced(),
+                    Err((e, _)) => {
+                        error!("Failed to send image record for {}: {}", page_url, e);
+                        metrics.inc_kafka_errored();
+                    }
+                }
+            }
         }
 
-        Ok(())
+        debug!("Sent {} image records for {}", records.len(), page_url);
     }
 
-    /// Serialize and send a parsed page to the `parsed-pages` Kafka topic.
-    async fn send_parsed_page(
+    /// Produce the links or images beyond an inline limit as one
+    /// `OverflowRecord`, keyed by the page URL. Nothing is sent when there is
+    /// no overflow. Failures are logged and counted but not propagated.
+    async fn send_overflow<T: Serialize>(
+        topic: &str,
         url: &str,
-        parsed: &crate::internal::parser::models::ParsedPage,
+        offset: usize,
+        items: &[T],
         metrics: &Arc<Metrics>,
         producer: &FutureProducer,
-        config: Arc<Config>,
-    ) -> Result<()> {
-        // Serialize the parsed page to JSON.
-        let json_data = serde_json::to_string(parsed).context("Failed to serialize parsed page")?;
+    ) {
+        if items.is_empty() {
+            return;
+        }
+
+        let record = OverflowRecord { url, offset, items };
+        let payload = match serde_json::to_string(&record) {
+            Ok(payload) => payload,
+            Err(e) => {
+                error!("Failed to serialize overflow record: {}", e);
+                return;
+            }
+        };
+
+        let record = FutureRecord::to(topic).key(url).payload(&payload);
+        match producer.send(record, Duration::from_secs(0)).await {
+            Ok(_) => debug!(
+                "Sent {} overflow items for {} to {}",
+                items.len(),
+                url,
+                topic
+            ),
+            Err((e, _)) => {
+                error!("Failed to send overflow record for {}: {}", url, e);
+                metrics.inc_kafka_errored();
+            }
+        }
+    }
+
+    /// Produce one `DiscoveredUrlRecord` per sitemap entry to the discovered-URLs topic.
+    ///
+    /// Records are keyed by the discovered URL and sent `batch_size` at a time,
+    /// like image records. Failures are logged and counted per record; any of
+    /// them makes the sitemap `Delivery::Failed`, so its offset isn't committed
+    /// past URLs that were never produced.
+    async fn send_discovered_urls(
+        topic: &str,
+        sitemap_url: &str,
+        entries: &[SitemapEntry],
+        batch_size: usize,
+        metrics: &Arc<Metrics>,
+        producer: &FutureProducer,
+    ) -> Delivery {
+        let mut delivery = Delivery::Settled;
+        let records: Vec<(&str, String)> = entries
+            .iter()
+            .filter_map(|entry| {
+                let record = DiscoveredUrlRecord {
+                    url: &entry.loc,
+                    sitemap_url,
+                    lastmod: entry.lastmod.as_deref(),
+                    priority: entry.priority,
+                    is_sitemap: entry.is_sitemap,
+                };
+                serde_json::to_string(&record)
+                    .map_err(|e| error!("Failed to serialize discovered URL record: {}", e))
+                    .ok()
+                    .map(|payload| (entry.loc.as_str(), payload))
+            })
+            .collect();
+
+        for batch in records.chunks(batch_size) {
+            let sends = batch.iter().map(|(key, payload)| {
+                let record = FutureRecord::to(topic).key(*key).payload(payload);
+                producer.send(record, Duration::from_secs(0))
+            });
+
+            for result in join_all(sends).await {
+                match result {
+                    Ok(_) => metrics.inc_discovered_urls_produced(),
+                    Err((e, _)) => {
+                        error!("Failed to send discovered URL from {}: {}", sitemap_url, e);
+                        metrics.inc_kafka_errored();
+                        delivery = Delivery::Failed;
+                    }
+                }
+            }
+        }
+
+        info!(
+            "Sent {} discovered URLs from sitemap {}",
+            records.len(),
+            sitemap_url
+        );
+        delivery
+    }
+
+    /// Produce a compact `SummaryRecord` of the page to the summary topic.
+    ///
+    /// Failures are logged and counted but not propagated, like image records.
+    async fn send_summary(
+        topic: &str,
+        parsed: &ParsedPage,
+        metrics: &Arc<Metrics>,
+        producer: &FutureProducer,
+    ) {
+        let record = SummaryRecord {
+            url: &parsed.url,
+            title: &parsed.title,
+            snippet: parsed.description.as_deref().unwrap_or(&parsed.preview),
+            language: parsed.language.as_deref(),
+            primary_image: parsed.images.first().map(|image| image.src.as_str()),
+            content_hash: &parsed.content_hash,
+        };
+        let payload = match serde_json::to_string(&record) {
+            Ok(payload) => payload,
+            Err(e) => {
+                error!("Failed to serialize summary record: {}", e);
+                return;
+            }
+        };
 
-        let record = FutureRecord::to(&config.kafka_topic_parsed)
+        let record = FutureRecord::to(topic)
+            .key(parsed.url.as_str())
+            .payload(&payload);
+        match producer.send(record, Duration::from_secs(0)).await {
+            Ok(_) => metrics.inc_summaries_produced(),
+            Err((e, _)) => {
+                error!("Failed to send summary record for {}: {}", parsed.url, e);
+                metrics.inc_kafka_errored();
+            }
+        }
+    }
+
+    /// Forward the payload of a page routed with `errors`, unchanged, to the errors topic.
+    ///
+    /// The status is attached as the `x-http-status` header. Failures are
+    /// logged and counted but not propagated.
+    async fn send_error_page(
+        topic: &str,
+        url: &str,
+        message: &OwnedMessage,
+        status: u16,
+        metrics: &Arc<Metrics>,
+        producer: &FutureProducer,
+    ) {
+        let status = status.to_string();
+        let headers = OwnedHeaders::new().insert(Header {
+            key: "x-http-status",
+            value: Some(&status),
+        });
+        let record = FutureRecord::to(topic)
             .key(url)
-            .payload(&json_data);
+            .payload(message.payload().unwrap_or_default())
+            .headers(headers);
 
-        // Send to Kafka.
         match producer.send(record, Duration::from_secs(0)).await {
             Ok(_) => {
-                metrics.inc_kafka_successful();
-                info!(
-                    "Parsed and sent page: {} (words: {}, total: {})",
-                    url,
-                    parsed.word_count,
-                    metrics.pages_processed.load(Ordering::Relaxed)
-                );
+                metrics.inc_pages_routed_errors();
+                debug!("Sent {} to errors topic (status {})", url, status);
+            }
+            Err((e, _)) => {
+                error!("Failed to send {} to errors topic: {}", url, e);
+                metrics.inc_kafka_errored();
+            }
+        }
+    }
+
+    /// Produce a `RedirectRecord` for a page routed with `redirect`.
+    ///
+    /// Pages without a resolvable `Location` are only counted as skipped.
+    /// Failures are logged and counted but not propagated.
+    async fn send_redirect(
+        topic: &str,
+        url: &str,
+        location: Option<&str>,
+        status: u16,
+        metrics: &Arc<Metrics>,
+        producer: &FutureProducer,
+    ) {
+        let target = location.and_then(|location| {
+            url::Url::parse(url)
+                .and_then(|base| base.join(location))
+                .or_else(|_| url::Url::parse(location))
+                .ok()
+        });
+        let Some(target) = target else {
+            debug!("Redirect without a usable Location, page skipped: {}", url);
+            metrics.inc_pages_skipped_status();
+            return;
+        };
+
+        let record = RedirectRecord {
+            url,
+            target: target.as_str(),
+            status,
+        };
+        let payload = match serde_json::to_string(&record) {
+            Ok(payload) => payload,
+            Err(e) => {
+                error!("Failed to serialize redirect record: {}", e);
+                return;
+            }
+        };
+
+        let record = FutureRecord::to(topic).key(url).payload(&payload);
+        match producer.send(record, Duration::from_secs(0)).await {
+            Ok(_) => metrics.inc_redirects_produced(),
+            Err((e, _)) => {
+                error!("Failed to send redirect record for {}: {}", url, e);
+                metrics.inc_kafka_errored();
+            }
+        }
+    }
+
+    /// Forward an unparsable payload, unchanged, to the dead-letter topic.
+    ///
+    /// The reason is attached as the `x-dead-letter-reason` header. Failures are
+    /// logged and counted but not propagated: the page has already failed.
+    async fn send_dead_letter(
+        topic: &str,
+        url: &str,
+        payload: &[u8],
+        reason: &str,
+        metrics: &Arc<Metrics>,
+        producer: &FutureProducer,
+    ) {
+        let headers = OwnedHeaders::new().insert(Header {
+            key: "x-dead-letter-reason",
+            value: Some(reason),
+        });
+        let record = FutureRecord::to(topic)
+            .key(url)
+            .payload(payload)
+            .headers(headers);
+
+        match producer.send(record, Duration::from_secs(0)).await {
+            Ok(_) => {
+                metrics.inc_pages_dead_lettered();
+                info!("Sent {} to dead-letter topic ({})", url, reason);
             }
             Err((e, _)) => {
-                error!("Failed to send message to Kafka: {}", e);
-                // Heuristically decide if it’s a payload / message size or network error
-                if e.to_string().contains("MessageSizeTooLarge") {
-                    metrics.inc_kafka_failed();
+                error!("Failed to send {} to dead-letter topic: {}", url, e);
+                metrics.inc_kafka_errored();
+            }
+        }
+    }
+}
+
+/// Handles one message inside a worker task.
+#[async_trait]
+trait MessageProcessor: Send + Sync + 'static {
+    async fn process(&self, message: &OwnedMessage) -> Result<Delivery>;
+}
+
+/// Everything a worker needs to parse a page and write it to the sinks.
+struct PageProcessor {
+    parser: Arc<HtmlParser>,
+    metrics: Arc<Metrics>,
+    producer: FutureProducer,
+    sinks: Arc<Vec<Box<dyn PageSink>>>,
+    config: Arc<Config>,
+    seen_urls: Option<Arc<BloomFilter>>,
+    duplicates: Option<Arc<DuplicateTracker>>,
+    boilerplate: Option<Arc<BoilerplateFilter>>,
+    retry: DeliveryRetry,
+}
+
+#[async_trait]
+impl MessageProcessor for PageProcessor {
+    async fn process(&self, message: &OwnedMessage) -> Result<Delivery> {
+        KafkaHandler::process_message(
+            message,
+            &self.parser,
+            &self.metrics,
+            &self.producer,
+            &self.sinks,
+            Arc::clone(&self.config),
+            self.seen_urls.as_deref(),
+            self.duplicates.as_deref(),
+            self.boilerplate.as_deref(),
+            &self.retry,
+        )
+        .await
+    }
+}
+
+/// Feeds messages from `source` to `max_concurrency` workers running
+/// `processor` until shutdown.
+///
+/// Each message's offset is committed to `source` once it is settled and every
+/// earlier message on its partition is too, as tracked by `offsets`. A page
+/// whose delivery failed holds its partition back (see `offsets`). Nothing is
+/// committed under `read_only_validation`.
+///
+/// On shutdown the loop stops receiving and returns once the workers in
+/// flight are done, or after `SHUTDOWN_GRACE` at most.
+#[allow(clippy::too_many_arguments)]
+async fn run_workers<S, P>(
+    source: Arc<S>,
+    offsets: Arc<OffsetTracker>,
+    processor: Arc<P>,
+    config: &Config,
+    breaker: Option<&CircuitBreaker>,
+    metrics: Arc<Metrics>,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+    shutdown_tx: tokio::sync::watch::Sender<bool>,
+) -> Result<()>
+where
+    S: MessageSource + 'static,
+    P: MessageProcessor,
+{
+    let semaphore = Arc::new(Semaphore::new(config.max_concurrency));
+    let offsets = (!config.read_only_validation).then_some(offsets);
+    // Signalled whenever a worker releases its payload bytes.
+    let bytes_released = Arc::new(Notify::new());
+    let mut paused_for_bytes = false;
+
+    info!(
+        "Starting with max {} concurrent workers, waiting for messages...",
+        config.max_concurrency
+    );
+
+    loop {
+        let cooldown = breaker.and_then(CircuitBreaker::open_for);
+        if cooldown.is_some() {
+            source.set_paused(true);
+        }
+
+        // Stop fetching while the payloads in flight exceed the byte budget.
+        let max_bytes = config.max_inflight_bytes;
+        let over_budget = max_bytes > 0 && metrics.get_inflight_bytes() >= max_bytes;
+        if over_budget != paused_for_bytes {
+            paused_for_bytes = over_budget;
+            if over_budget {
+                debug!(
+                    "In-flight payloads at {} bytes, pausing consumption",
+                    metrics.get_inflight_bytes()
+                );
+                source.set_paused(true);
+            } else if cooldown.is_none() {
+                source.set_paused(false);
+            }
+        }
+
+        tokio::select! {
+            // watch for shutdown
+            res = shutdown.changed() => {
+                let _ = shutdown_tx.send(true);
+                if res.is_ok() {
+                    info!("Shutdown signal received, stopping Kafka processing loop.");
                 } else {
-                    metrics.inc_kafka_errored();
+                    error!("Shutdown channel closed unexpectedly.");
+                }
+                // Every permit back means no worker is left in flight.
+                let workers = config.max_concurrency as u32;
+                if timeout(SHUTDOWN_GRACE, semaphore.acquire_many(workers)).await.is_err() {
+                    warn!(
+                        "{} pages still in flight after {}s, stopping without them",
+                        metrics.get_inflight_pages(),
+                        SHUTDOWN_GRACE.as_secs()
+                    );
                 }
+                break;
+            }
+
+            // probe the output once the breaker's cooldown is over
+            _ = tokio::time::sleep(cooldown.unwrap_or_default()), if cooldown.is_some() => {
+                if let Some(breaker) = breaker {
+                    breaker.half_open();
+                }
+                source.set_paused(false);
+            }
+
+            // re-check the byte budget once a worker finishes
+            _ = bytes_released.notified(), if over_budget => {}
+
+            // process Kafka messages
+            msg_res = source.recv(), if cooldown.is_none() && !over_budget => {
+                let owned_msg = match msg_res {
+                    Ok(msg) => msg,
+                    Err(e) => {
+                        error!("Failed to receive message from Kafka: {}", e);
+                        metrics.inc_kafka_errored();
+                        continue;
+                    }
+                };
+
+                let permit = match semaphore.clone().acquire_owned().await {
+                    Ok(permit) => permit,
+                    Err(e) => {
+                        error!("Semaphore acquisition failed: {}", e);
+                        continue;
+                    }
+                };
+
+                if let Some(offsets) = &offsets {
+                    offsets.start(owned_msg.topic(), owned_msg.partition(), owned_msg.offset());
+                }
+
+                let processor_clone = Arc::clone(&processor);
+                let source_clone = Arc::clone(&source);
+                let offsets_clone = offsets.clone();
+                let metrics_clone = metrics.clone();
+                let payload_bytes = owned_msg.payload().map_or(0, <[u8]>::len) as u64;
+                metrics.add_inflight_bytes(payload_bytes);
+                let released_clone = Arc::clone(&bytes_released);
+
+                // spawn a task to process the message
+                tokio::spawn(async move {
+                    if metrics_clone
+                        .pages_processed
+                        .load(Ordering::Relaxed)
+                        .is_multiple_of(100)
+                    {
+                        info!(
+                            "Metrics: inflight={}, processed={}, successful={}, failed={}, kafka_ok={}, kafka_fail={}, kafka_err={}",
+                            metrics_clone.get_inflight_pages(),
+                            metrics_clone.get_pages_processed(),
+                            metrics_clone.get_pages_successful(),
+                            metrics_clone.get_pages_failed(),
+                            metrics_clone.get_kafka_successful(),
+                            metrics_clone.get_kafka_failed(),
+                            metrics_clone.get_kafka_errored(),
+                        );
+                    }
+
+                    metrics_clone.inc_pages_processed();
+                    metrics_clone.inc_inflight_pages();
+
+                    let delivery = processor_clone.process(&owned_msg).await.unwrap_or_else(|e| {
+                        error!("Error processing message: {}", e);
+                        metrics_clone.inc_pages_failed();
+                        // Parse failures are dead-lettered; reprocessing won't help.
+                        Delivery::Settled
+                    });
+
+                    if let (Some(offsets), Delivery::Settled) = (&offsets_clone, delivery) {
+                        let (topic, partition) = (owned_msg.topic(), owned_msg.partition());
+                        if let Some(next) = offsets.settle(topic, partition, owned_msg.offset()) {
+                            source_clone.commit(topic, partition, next);
+                        }
+                    }
+
+                    metrics_clone.dec_inflight_pages();
+                    metrics_clone.sub_inflight_bytes(payload_bytes);
+                    released_clone.notify_one();
+                    drop(permit); // release the semaphore slot
+                });
+            }
+        }
+    }
+
+    info!("Kafka processing loop exited.");
+    Ok(())
+}
+
+/// The page URL of a raw message: its key, else the `url_header` header
+/// (an empty name disables the fallback).
+fn message_url(message: &OwnedMessage, url_header: &str) -> Option<String> {
+    message
+        .key()
+        .map(|key| String::from_utf8_lossy(key).trim().to_string())
+        .filter(|key| !key.is_empty())
+        .or_else(|| {
+            Some(url_header)
+                .filter(|name| !name.is_empty())
+                .and_then(|name| header_value(message, name))
+        })
+}
+
+/// Removes and returns the items beyond `limit` (0 keeps everything inline).
+fn split_overflow<T>(items: &mut Vec<T>, limit: usize) -> Vec<T> {
+    if limit == 0 || items.len() <= limit {
+        return Vec::new();
+    }
+    items.split_off(limit)
+}
+
+/// Returns the UTF-8 value of a Kafka header, matching the name case-insensitively.
+fn header_value(message: &OwnedMessage, name: &str) -> Option<String> {
+    message
+        .headers()?
+        .iter()
+        .find(|header| header.key.eq_ignore_ascii_case(name))
+        .and_then(|header| header.value)
+        .map(|value| String::from_utf8_lossy(value).trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rdkafka::error::KafkaResult;
+    use rdkafka::message::Timestamp;
+    use std::collections::VecDeque;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::Mutex;
+    use tokio::sync::watch;
+    use tokio::time::Instant;
+
+    const PAGE: &str = "<html><head><title>Page</title></head><body><p>This page carries \
+        enough ordinary prose to count as real content rather than a stub that the \
+        length gate would reject outright.</p></body></html>";
+
+    /// Hands out the queued messages, then idles like an empty topic.
+    /// Commits are recorded instead of sent.
+    struct QueueSource {
+        messages: Mutex<VecDeque<OwnedMessage>>,
+        commits: Mutex<Vec<i64>>,
+    }
+
+    impl QueueSource {
+        fn with_messages(count: i64) -> Self {
+            let messages = (0..count)
+                .map(|offset| {
+                    OwnedMessage::new(
+                        Some(PAGE.as_bytes().to_vec()),
+                        Some(format!("https://example.com/{offset}").into_bytes()),
+                        "raw-html".to_string(),
+                        Timestamp::NotAvailable,
+                        0,
+                        offset,
+                        None,
+                    )
+                })
+                .collect();
+            Self {
+                messages: Mutex::new(messages),
+                commits: Mutex::new(Vec::new()),
+            }
+        }
+
+        fn last_commit(&self) -> Option<i64> {
+            self.commits.lock().unwrap().last().copied()
+        }
+    }
+
+    #[async_trait]
+    impl MessageSource for QueueSource {
+        async fn recv(&self) -> KafkaResult<OwnedMessage> {
+            let next = self.messages.lock().unwrap().pop_front();
+            match next {
+                Some(message) => Ok(message),
+                None => std::future::pending().await,
+            }
+        }
+
+        fn set_paused(&self, _paused: bool) {}
+
+        fn commit(&self, _topic: &str, _partition: i32, next: i64) {
+            self.commits.lock().unwrap().push(next);
+        }
+    }
+
+    /// Takes `delay` per message and counts the ones it finished. Delivery
+    /// fails for the message at `failing_offset`, if any.
+    struct SlowParser {
+        delay: Duration,
+        failing_offset: Option<i64>,
+        completed: AtomicUsize,
+    }
+
+    impl SlowParser {
+        fn new(delay: Duration) -> Self {
+            Self {
+                delay,
+                failing_offset: None,
+                completed: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl MessageProcessor for SlowParser {
+        async fn process(&self, message: &OwnedMessage) -> Result<Delivery> {
+            sleep(self.delay).await;
+            self.completed.fetch_add(1, Ordering::SeqCst);
+            if self.failing_offset == Some(message.offset()) {
+                return Ok(Delivery::Failed);
+            }
+            Ok(Delivery::Settled)
+        }
+    }
+
+    /// Runs the loop over `messages` slow pages, signalling shutdown once they
+    /// are all in flight. Returns the source and how long the loop ran.
+    async fn shut_down_with_inflight(
+        messages: i64,
+        parser: &Arc<SlowParser>,
+        config: Config,
+    ) -> (Arc<QueueSource>, Duration) {
+        let source = Arc::new(QueueSource::with_messages(messages));
+        let metrics = Arc::new(Metrics::new());
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+
+        let started = Instant::now();
+        let workers = run_workers(
+            Arc::clone(&source),
+            Arc::new(OffsetTracker::new()),
+            Arc::clone(parser),
+            &config,
+            None,
+            Arc::clone(&metrics),
+            shutdown_rx,
+            shutdown_tx.clone(),
+        );
+        let trigger = async {
+            sleep(Duration::from_millis(100)).await;
+            assert_eq!(metrics.get_inflight_pages(), messages as u64);
+            shutdown_tx.send(true).unwrap();
+        };
+
+        let (result, ()) = tokio::join!(workers, trigger);
+        result.unwrap();
+        (source, started.elapsed())
+    }
+
+    fn config() -> Config {
+        Config {
+            max_concurrency: 4,
+            ..Config::default()
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn shutdown_lets_inflight_messages_finish() {
+        let parser = Arc::new(SlowParser::new(Duration::from_secs(2)));
+        let (_, elapsed) = shut_down_with_inflight(3, &parser, config()).await;
+
+        // Shutdown waits for the 2s jobs, not for the whole grace period.
+        assert_eq!(parser.completed.load(Ordering::SeqCst), 3);
+        assert!(elapsed < Duration::from_secs(3));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn shutdown_does_not_wait_past_the_grace_period() {
+        let parser = Arc::new(SlowParser::new(Duration::from_secs(60)));
+        let (source, elapsed) = shut_down_with_inflight(2, &parser, config()).await;
+
+        assert_eq!(parser.completed.load(Ordering::SeqCst), 0);
+        assert!(elapsed >= SHUTDOWN_GRACE);
+        assert!(elapsed <= SHUTDOWN_GRACE + Duration::from_millis(200));
+        assert_eq!(source.last_commit(), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn commits_settled_messages() {
+        let parser = Arc::new(SlowParser::new(Duration::from_secs(1)));
+        let (source, _) = shut_down_with_inflight(3, &parser, config()).await;
+
+        assert_eq!(source.last_commit(), Some(3));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn failed_delivery_is_not_committed() {
+        let parser = Arc::new(SlowParser {
+            failing_offset: Some(1),
+            ..SlowParser::new(Duration::from_secs(1))
+        });
+        let (source, _) = shut_down_with_inflight(3, &parser, config()).await;
+
+        // Offset 0 settled; 1 failed, so 2 is not committed past it.
+        assert_eq!(source.last_commit(), Some(1));
+    }
+
+    /// Fails the first `failures` writes, then takes every page.
+    struct FlakySink {
+        failures: AtomicUsize,
+        written: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl PageSink for FlakySink {
+        async fn write(
+            &self,
+            _url: &str,
+            _page: &ParsedPage,
+            _json: &str,
+            _route: Route,
+        ) -> Result<Delivery> {
+            let failing = self
+                .failures
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok();
+            if failing {
+                return Ok(Delivery::Failed);
             }
+            self.written.fetch_add(1, Ordering::SeqCst);
+            Ok(Delivery::Settled)
         }
+    }
+
+    /// Runs the real page processor over `messages` pages written to `sink`,
+    /// signalling shutdown after `run_for`.
+    async fn deliver_pages(messages: i64, sink: FlakySink, run_for: Duration) -> Arc<QueueSource> {
+        let config = Arc::new(config());
+        let metrics = Arc::new(Metrics::new());
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let processor = Arc::new(PageProcessor {
+            parser: Arc::new(HtmlParser::new(&config, Arc::clone(&metrics))),
+            metrics: Arc::clone(&metrics),
+            producer: ClientConfig::new()
+                .set("bootstrap.servers", "127.0.0.1:9")
+                .create()
+                .unwrap(),
+            sinks: Arc::new(vec![Box::new(sink)]),
+            config: Arc::clone(&config),
+            seen_urls: None,
+            duplicates: None,
+            boilerplate: None,
+            retry: DeliveryRetry::new(None, shutdown_rx.clone()),
+        });
+        let source = Arc::new(QueueSource::with_messages(messages));
+
+        let workers = run_workers(
+            Arc::clone(&source),
+            Arc::new(OffsetTracker::new()),
+            processor,
+            &config,
+            None,
+            metrics,
+            shutdown_rx,
+            shutdown_tx.clone(),
+        );
+        let trigger = async {
+            sleep(run_for).await;
+            shutdown_tx.send(true).unwrap();
+        };
+
+        let (result, ()) = tokio::join!(workers, trigger);
+        result.unwrap();
+        source
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn failed_delivery_is_retried_until_committed() {
+        let written = Arc::new(AtomicUsize::new(0));
+        let sink = FlakySink {
+            failures: AtomicUsize::new(3),
+            written: Arc::clone(&written),
+        };
+        let source = deliver_pages(3, sink, Duration::from_secs(60)).await;
+
+        // The output recovered, so the commit point moved past the failures.
+        assert_eq!(written.load(Ordering::SeqCst), 3);
+        assert_eq!(source.last_commit(), Some(3));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn shutdown_ends_delivery_retries() {
+        let written = Arc::new(AtomicUsize::new(0));
+        let sink = FlakySink {
+            failures: AtomicUsize::new(usize::MAX),
+            written: Arc::clone(&written),
+        };
+        let source = deliver_pages(1, sink, Duration::from_secs(60)).await;
+
+        assert_eq!(written.load(Ordering::SeqCst), 0);
+        assert_eq!(source.last_commit(), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn read_only_validation_never_commits() {
+        let parser = Arc::new(SlowParser::new(Duration::from_secs(1)));
+        let config = Config {
+            read_only_validation: true,
+            ..config()
+        };
+        let (source, _) = shut_down_with_inflight(3, &parser, config).await;
+
+        assert_eq!(source.last_commit(), None);
+    }
+
+    #[tokio::test]
+    async fn startup_timeout_fires_with_an_unreachable_broker() {
+        let config = Arc::new(Config {
+            // Nothing listens on the discard port.
+            kafka_brokers: "127.0.0.1:9".to_string(),
+            ..Config::default()
+        });
+        let metrics = Arc::new(Metrics::new());
+
+        let startup = async {
+            let handler = KafkaHandler::new(config, &metrics).await?;
+            handler.wait_until_connected().await;
+            anyhow::Ok(handler)
+        };
+        let result = timeout(Duration::from_secs(1), startup).await;
+
+        assert!(result.is_err(), "startup finished without a broker");
+    }
+
+    fn raw_message(key: Option<&str>, headers: &[(&str, &str)]) -> OwnedMessage {
+        let headers = headers
+            .iter()
+            .fold(OwnedHeaders::new(), |headers, (key, value)| {
+                headers.insert(Header {
+                    key,
+                    value: Some(*value),
+                })
+            });
+        OwnedMessage::new(
+            Some(b"<html></html>".to_vec()),
+            key.map(|key| key.as_bytes().to_vec()),
+            "raw-html".to_string(),
+            Timestamp::NotAvailable,
+            0,
+            0,
+            Some(headers),
+        )
+    }
+
+    #[test]
+    fn url_comes_from_the_key() {
+        let message = raw_message(
+            Some("https://example.com/key"),
+            &[("url", "https://example.com/header")],
+        );
+
+        assert_eq!(
+            message_url(&message, "url").as_deref(),
+            Some("https://example.com/key")
+        );
+    }
+
+    #[test]
+    fn url_falls_back_to_the_header_without_a_key() {
+        let message = raw_message(None, &[("URL", " https://example.com/header ")]);
+
+        assert_eq!(
+            message_url(&message, "url").as_deref(),
+            Some("https://example.com/header")
+        );
+        // A blank key counts as absent.
+        let message = raw_message(Some("  "), &[("url", "https://example.com/header")]);
+        assert_eq!(
+            message_url(&message, "url").as_deref(),
+            Some("https://example.com/header")
+        );
+    }
 
-        Ok(())
+    #[test]
+    fn no_url_without_key_or_header() {
+        assert_eq!(message_url(&raw_message(None, &[]), "url"), None);
+        // An empty header name disables the fallback.
+        let message = raw_message(None, &[("url", "https://example.com/header")]);
+        assert_eq!(message_url(&message, ""), None);
     }
 }
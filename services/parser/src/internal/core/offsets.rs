@@ -0,0 +1,123 @@
+//! Tracks which consumed offsets are safe to commit.
+//!
+//! Auto-commit is off: a message's offset is committed only once it is
+//! settled, that is written to every sink or skipped for a reason a retry
+//! won't fix. Workers finish out of order, so per partition the commit point is
+//! the lowest offset still pending. Failed deliveries are retried in place
+//! (see `retry`), so a message only stays pending for good when shutdown
+//! interrupts its retries; it is then consumed again after the restart.
+//!
+//! A revoked partition's state is dropped: its new owner resumes from the
+//! last commit, and a later reassignment starts tracking afresh.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Mutex;
+
+/// Pending and settled offsets of one partition.
+struct PartitionOffsets {
+    /// Consumed offsets not yet settled (in flight or failed).
+    pending: BTreeSet<i64>,
+    /// One past the highest settled offset.
+    next: i64,
+    /// The last offset handed out for committing.
+    committed: i64,
+}
+
+/// Commit points for every partition this consumer has received from.
+#[derive(Default)]
+pub struct OffsetTracker {
+    partitions: Mutex<HashMap<(String, i32), PartitionOffsets>>,
+}
+
+impl OffsetTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a consumed message as pending.
+    pub fn start(&self, topic: &str, partition: i32, offset: i64) {
+        let mut partitions = self.partitions.lock().unwrap_or_else(|e| e.into_inner());
+        partitions
+            .entry((topic.to_string(), partition))
+            // Everything before the first message seen is already committed.
+            .or_insert_with(|| PartitionOffsets {
+                pending: BTreeSet::new(),
+                next: offset,
+                committed: offset,
+            })
+            .pending
+            .insert(offset);
+    }
+
+    /// Marks a message as settled.
+    ///
+    /// Returns the offset to commit for its partition (the next offset to
+    /// consume) when the commit point moved forward.
+    ///
+    /// Messages started before their partition was revoked are ignored.
+    pub fn settle(&self, topic: &str, partition: i32, offset: i64) -> Option<i64> {
+        let mut partitions = self.partitions.lock().unwrap_or_else(|e| e.into_inner());
+        let state = partitions.get_mut(&(topic.to_string(), partition))?;
+        if !state.pending.remove(&offset) {
+            return None;
+        }
+        state.next = state.next.max(offset + 1);
+
+        let commit = state.pending.first().copied().unwrap_or(state.next);
+        (commit > state.committed).then(|| {
+            state.committed = commit;
+            commit
+        })
+    }
+
+    /// Forgets a partition taken away by a rebalance.
+    pub fn revoke(&self, topic: &str, partition: i32) {
+        let mut partitions = self.partitions.lock().unwrap_or_else(|e| e.into_inner());
+        partitions.remove(&(topic.to_string(), partition));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commits_only_the_settled_prefix() {
+        let tracker = OffsetTracker::new();
+        for offset in 10..13 {
+            tracker.start("raw-html", 0, offset);
+        }
+
+        // 11 finishes first; 10 is still in flight.
+        assert_eq!(tracker.settle("raw-html", 0, 11), None);
+        assert_eq!(tracker.settle("raw-html", 0, 10), Some(12));
+        assert_eq!(tracker.settle("raw-html", 0, 12), Some(13));
+    }
+
+    #[test]
+    fn failed_offset_holds_back_its_partition() {
+        let tracker = OffsetTracker::new();
+        for offset in 0..3 {
+            tracker.start("raw-html", 0, offset);
+        }
+        tracker.start("raw-html", 1, 0);
+
+        // Offset 1 failed and is never settled.
+        assert_eq!(tracker.settle("raw-html", 0, 0), Some(1));
+        assert_eq!(tracker.settle("raw-html", 0, 2), None);
+        assert_eq!(tracker.settle("raw-html", 1, 0), Some(1));
+    }
+
+    #[test]
+    fn revoked_partition_starts_over() {
+        let tracker = OffsetTracker::new();
+        tracker.start("raw-html", 0, 5);
+        tracker.start("raw-html", 0, 6);
+        tracker.revoke("raw-html", 0);
+
+        // Reassigned and resumed from the last commit while 6 was in flight.
+        tracker.start("raw-html", 0, 5);
+        assert_eq!(tracker.settle("raw-html", 0, 6), None);
+        assert_eq!(tracker.settle("raw-html", 0, 5), Some(6));
+    }
+}
@@ -0,0 +1,48 @@
+//! Pacing of in-place delivery retries.
+//!
+//! A page whose sink write failed is retried by the worker holding it, which
+//! keeps its concurrency slot meanwhile. An output outage therefore backs up
+//! consumption instead of leaving failed offsets behind the commit point.
+//! Between attempts the worker waits out the breaker's cooldown, and at least
+//! `RETRY_DELAY`. Only shutdown ends the retries; the page then stays
+//! uncommitted and is consumed again after the restart.
+
+use std::sync::Arc;
+use tokio::sync::watch;
+use tokio::time::{sleep, Duration};
+
+use super::breaker::CircuitBreaker;
+
+/// Shortest wait between two attempts to deliver a page.
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Decides when a failed delivery is attempted again.
+pub struct DeliveryRetry {
+    breaker: Option<Arc<CircuitBreaker>>,
+    shutdown: watch::Receiver<bool>,
+}
+
+impl DeliveryRetry {
+    pub fn new(breaker: Option<Arc<CircuitBreaker>>, shutdown: watch::Receiver<bool>) -> Self {
+        Self { breaker, shutdown }
+    }
+
+    /// Waits before the next attempt. Returns `false` once shutdown is
+    /// signalled, when the delivery should be given up.
+    pub async fn wait(&self) -> bool {
+        let mut shutdown = self.shutdown.clone();
+        if *shutdown.borrow_and_update() {
+            return false;
+        }
+
+        let cooldown = self
+            .breaker
+            .as_deref()
+            .and_then(CircuitBreaker::open_for)
+            .unwrap_or_default();
+        tokio::select! {
+            () = sleep(cooldown.max(RETRY_DELAY)) => true,
+            _ = shutdown.changed() => false,
+        }
+    }
+}
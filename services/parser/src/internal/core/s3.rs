@@ -0,0 +1,158 @@
+//! S3 (or S3-compatible) sink writing each parsed page as a JSON object.
+//!
+//! Objects are stored at `<s3_prefix>/<sha256(url)>.json` through
+//! `object_store`, with path-style addressing so MinIO and other
+//! S3-compatible stores work through `s3_endpoint`. Credentials come from the
+//! standard `AWS_ACCESS_KEY_ID` / `AWS_SECRET_ACCESS_KEY` /
+//! `AWS_SESSION_TOKEN` environment variables. Routed pages (noindex, link-farm,
+//! low-quality) have no S3 destination and are skipped.
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use object_store::aws::{AmazonS3, AmazonS3Builder};
+use object_store::path::Path;
+use object_store::{ClientOptions, ObjectStore, PutPayload, RetryConfig};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error};
+
+use super::breaker::CircuitBreaker;
+use super::sink::{Delivery, PageSink, Route};
+use crate::internal::config::Config;
+use crate::internal::monitor::Metrics;
+use crate::internal::parser::models::ParsedPage;
+
+/// Writes pages to an S3 bucket. Upload outcomes feed the circuit breaker, if any.
+pub struct S3Sink {
+    store: AmazonS3,
+    bucket: String,
+    prefix: String,
+    metrics: Arc<Metrics>,
+    breaker: Option<Arc<CircuitBreaker>>,
+}
+
+impl S3Sink {
+    /// Creates the sink from config and the AWS credential environment variables.
+    ///
+    /// # Errors
+    /// Returns an error if `s3_bucket` is missing or the store cannot be built
+    /// from the endpoint and credentials.
+    pub fn new(
+        config: &Config,
+        metrics: Arc<Metrics>,
+        breaker: Option<Arc<CircuitBreaker>>,
+    ) -> Result<Self> {
+        let Some(bucket) = config.s3_bucket.clone() else {
+            bail!("s3_bucket is required for the S3 sink");
+        };
+
+        let mut builder = AmazonS3Builder::from_env()
+            .with_bucket_name(&bucket)
+            .with_region(&config.s3_region)
+            .with_client_options(ClientOptions::new().with_timeout(Duration::from_secs(10)))
+            // A page is written once; a struggling store trips the breaker
+            // instead of holding workers in long retry loops.
+            .with_retry(RetryConfig {
+                max_retries: 2,
+                retry_timeout: Duration::from_secs(10),
+                ..RetryConfig::default()
+            });
+        if let Some(endpoint) = &config.s3_endpoint {
+            builder = builder
+                .with_endpoint(endpoint)
+                .with_allow_http(endpoint.starts_with("http://"));
+        }
+        let store = builder.build().context("Failed to create S3 client")?;
+
+        Ok(Self {
+            store,
+            bucket,
+            prefix: config.s3_prefix.trim_matches('/').to_string(),
+            metrics,
+            breaker,
+        })
+    }
+
+    /// Object key for a page: the hex SHA-256 of its URL under the prefix.
+    fn object_key(&self, url: &str) -> String {
+        let hash = format!("{:x}", Sha256::digest(url.as_bytes()));
+        if self.prefix.is_empty() {
+            format!("{hash}.json")
+        } else {
+            format!("{}/{hash}.json", self.prefix)
+        }
+    }
+}
+
+#[async_trait]
+impl PageSink for S3Sink {
+    async fn write(
+        &self,
+        url: &str,
+        _page: &ParsedPage,
+        json: &str,
+        route: Route,
+    ) -> Result<Delivery> {
+        if route != Route::Parsed {
+            debug!("Routed page not written to S3: {}", url);
+            return Ok(Delivery::Settled);
+        }
+
+        let key = self.object_key(url);
+        let payload = PutPayload::from(json.to_string());
+
+        match self.store.put(&Path::from(key.as_str()), payload).await {
+            Ok(_) => {
+                self.metrics.inc_s3_successful();
+                if let Some(breaker) = &self.breaker {
+                    breaker.record_success();
+                }
+                debug!("Wrote {} to s3://{}/{}", url, self.bucket, key);
+                Ok(Delivery::Settled)
+            }
+            Err(e) => {
+                self.metrics.inc_s3_errored();
+                if let Some(breaker) = &self.breaker {
+                    breaker.record_failure();
+                }
+                error!("Failed to write {} to S3: {:#}", url, e);
+                Ok(Delivery::Failed)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sink(prefix: &str) -> S3Sink {
+        let config = Config {
+            s3_bucket: Some("pages".to_string()),
+            s3_endpoint: Some("http://minio:9000".to_string()),
+            s3_prefix: prefix.to_string(),
+            ..Config::default()
+        };
+        S3Sink::new(&config, Arc::new(Metrics::new()), None).unwrap()
+    }
+
+    #[test]
+    fn object_key_is_url_hash_under_prefix() {
+        let hash = format!("{:x}", Sha256::digest(b"https://example.com/"));
+
+        assert_eq!(
+            sink("/parsed-pages/").object_key("https://example.com/"),
+            format!("parsed-pages/{hash}.json")
+        );
+        assert_eq!(
+            sink("").object_key("https://example.com/"),
+            format!("{hash}.json")
+        );
+    }
+
+    #[test]
+    fn requires_a_bucket() {
+        assert!(S3Sink::new(&Config::default(), Arc::new(Metrics::new()), None).is_err());
+    }
+}
@@ -0,0 +1,443 @@
+//! Destinations for parsed pages.
+//!
+//! Every parsed page is serialized once and handed to each configured
+//! `PageSink`, together with its `Route`. `output_sink` selects Kafka (the
+//! default), S3, both, or NDJSON on stdout for local debugging.
+//!
+//! Noindex, link-farm and low-quality pages are routed to their own Kafka
+//! topics when those are configured. Only Kafka has a destination for them, so
+//! the other sinks skip routed pages.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use rdkafka::error::{KafkaError, RDKafkaErrorCode};
+use rdkafka::message::{Header, OwnedHeaders};
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use serde_json::Value;
+use std::io::Write;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::time::Duration;
+use tracing::{error, info, warn};
+use url::Url;
+
+use super::breaker::CircuitBreaker;
+use super::s3::S3Sink;
+use crate::internal::config::Config;
+use crate::internal::monitor::Metrics;
+use crate::internal::parser::models::ParsedPage;
+
+/// What became of a page handed to a sink.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delivery {
+    /// Written, or dropped for a reason a retry won't fix (an oversized page).
+    Settled,
+    /// Not written because the output is failing; the message is not committed.
+    Failed,
+}
+
+/// Where a page belongs, picked once per page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Route {
+    /// The main output.
+    Parsed,
+    /// `kafka_topic_noindex`.
+    Noindex,
+    /// `kafka_topic_quarantine`.
+    Quarantine,
+    /// `kafka_topic_low_quality`.
+    LowQuality,
+}
+
+impl Route {
+    /// Routes a page by its flags, in the order noindex, link farm, low
+    /// quality. A flag without a configured topic leaves the page in the main
+    /// output.
+    pub fn for_page(config: &Config, page: &ParsedPage) -> Self {
+        if config.respect_noindex && page.noindex && config.kafka_topic_noindex.is_some() {
+            Self::Noindex
+        } else if page.link_farm_suspected && config.kafka_topic_quarantine.is_some() {
+            Self::Quarantine
+        } else if page.low_quality && config.kafka_topic_low_quality.is_some() {
+            Self::LowQuality
+        } else {
+            Self::Parsed
+        }
+    }
+}
+
+/// A destination parsed pages are written to.
+#[async_trait]
+pub trait PageSink: Send + Sync {
+    /// Writes one page; `json` is `page` already serialized.
+    ///
+    /// Delivery failures are logged and counted by the sink itself and
+    /// reported as `Delivery::Failed`; an `Err` means the page could not be
+    /// handed to the sink at all. A sink without a destination for `route`
+    /// skips the page and reports it settled.
+    async fn write(
+        &self,
+        url: &str,
+        page: &ParsedPage,
+        json: &str,
+        route: Route,
+    ) -> Result<Delivery>;
+}
+
+/// Builds the sinks selected by `output_sink` (none for a `read_only_validation` dry run).
+pub fn build_sinks(
+    config: &Config,
+    producer: &FutureProducer,
+    metrics: &Arc<Metrics>,
+    breaker: Option<&Arc<CircuitBreaker>>,
+) -> Result<Vec<Box<dyn PageSink>>> {
+    let mut sinks: Vec<Box<dyn PageSink>> = Vec::new();
+
+    // Dry run: validation without a validation topic discards every page.
+    if config.read_only_validation && config.kafka_topic_validation.is_none() {
+        return Ok(sinks);
+    }
+
+    if matches!(config.output_sink.as_str(), "kafka" | "both") {
+        sinks.push(Box::new(KafkaSink::new(
+            config,
+            producer.clone(),
+            Arc::clone(metrics),
+            breaker.cloned(),
+        )));
+    }
+    if matches!(config.output_sink.as_str(), "s3" | "both") {
+        sinks.push(Box::new(
+            S3Sink::new(config, Arc::clone(metrics), breaker.cloned())
+                .context("Failed to create S3 sink")?,
+        ));
+    }
+
+    if config.output_sink == "stdout" {
+        sinks.push(Box::new(StdoutSink));
+    }
+
+    Ok(sinks)
+}
+
+/// Writes each page as one JSON line to stdout, for piping into `jq` or a file.
+///
+/// Logs go to stderr when this sink is selected, so stdout stays valid NDJSON.
+pub struct StdoutSink;
+
+#[async_trait]
+impl PageSink for StdoutSink {
+    async fn write(
+        &self,
+        url: &str,
+        _page: &ParsedPage,
+        json: &str,
+        route: Route,
+    ) -> Result<Delivery> {
+        if route != Route::Parsed {
+            return Ok(Delivery::Settled);
+        }
+
+        // Lock for the whole line so concurrent workers don't interleave records.
+        let mut stdout = std::io::stdout().lock();
+        writeln!(stdout, "{json}")
+            .and_then(|()| stdout.flush())
+            .with_context(|| format!("Failed to write {url} to stdout"))?;
+        Ok(Delivery::Settled)
+    }
+}
+
+/// Produces pages to the parsed-pages topic, or the topic of their `Route`, with
+/// the page's `content_hash` as a header. At most `max_concurrent_sends` sends are
+/// awaiting delivery at once, independently of `max_concurrency`. With
+/// `strip_oversized_fields`, pages rejected as too large are retried with
+/// optional fields removed. Delivery outcomes feed the circuit breaker, if any.
+pub struct KafkaSink {
+    producer: FutureProducer,
+    metrics: Arc<Metrics>,
+    topic_parsed: String,
+    topic_noindex: Option<String>,
+    topic_quarantine: Option<String>,
+    topic_low_quality: Option<String>,
+    partition_key_strategy: String,
+    content_hash_header: String,
+    /// Bounds sends awaiting delivery to `max_concurrent_sends`.
+    sends: Semaphore,
+    strip_oversized: bool,
+    oversize_min_text_length: usize,
+    breaker: Option<Arc<CircuitBreaker>>,
+}
+
+impl KafkaSink {
+    pub fn new(
+        config: &Config,
+        producer: FutureProducer,
+        metrics: Arc<Metrics>,
+        breaker: Option<Arc<CircuitBreaker>>,
+    ) -> Self {
+        Self {
+            producer,
+            metrics,
+            topic_parsed: config.kafka_topic_parsed.clone(),
+            topic_noindex: config.kafka_topic_noindex.clone(),
+            topic_quarantine: config.kafka_topic_quarantine.clone(),
+            topic_low_quality: config.kafka_topic_low_quality.clone(),
+            partition_key_strategy: config.partition_key_strategy.clone(),
+            content_hash_header: config.content_hash_header.clone(),
+            sends: Semaphore::new(config.max_concurrent_sends),
+            strip_oversized: config.strip_oversized_fields,
+            oversize_min_text_length: config.oversize_min_text_length,
+            breaker,
+        }
+    }
+
+    /// Sends one payload, holding a `max_concurrent_sends` slot until delivery.
+    async fn send(
+        &self,
+        topic: &str,
+        key: &str,
+        payload: &str,
+        content_hash: &str,
+    ) -> Result<std::result::Result<(), KafkaError>> {
+        // The fingerprint rides in a header so consumers can dedup without
+        // deserializing the payload.
+        let headers = OwnedHeaders::new().insert(Header {
+            key: &self.content_hash_header,
+            value: Some(content_hash),
+        });
+        let record = FutureRecord::to(topic)
+            .key(key)
+            .payload(payload)
+            .headers(headers);
+
+        // Wait for a free slot if the broker is slow.
+        let _permit = self
+            .sends
+            .acquire()
+            .await
+            .context("Kafka send semaphore closed")?;
+        self.metrics.inc_inflight_sends();
+        let result = self.producer.send(record, Duration::from_secs(0)).await;
+        self.metrics.dec_inflight_sends();
+
+        Ok(result.map(|_| ()).map_err(|(e, _)| e))
+    }
+
+    /// Re-sends an oversized page with progressively fewer optional fields.
+    ///
+    /// Fields are dropped in the order `body_text`, `images`, `links`, then
+    /// `cleaned_text` is halved down to `oversize_min_text_length`. The dropped
+    /// fields are listed in `additional_metadata.stripped_fields`. Returns the
+    /// result of the last attempt.
+    async fn send_stripped(
+        &self,
+        topic: &str,
+        key: &str,
+        page: &ParsedPage,
+    ) -> Result<std::result::Result<(), KafkaError>> {
+        let mut value = serde_json::to_value(page).context("Failed to serialize parsed page")?;
+        let mut stripped: Vec<&str> = Vec::new();
+        let mut result = Ok(());
+
+        while let Some(field) = strip_next_field(&mut value, self.oversize_min_text_length) {
+            if !stripped.contains(&field) {
+                stripped.push(field);
+            }
+            value["additional_metadata"]["stripped_fields"] = Value::String(stripped.join(","));
+
+            let json = serde_json::to_string(&value).context("Failed to serialize parsed page")?;
+            result = self.send(topic, key, &json, &page.content_hash).await?;
+            if !result.as_ref().is_err_and(is_size_error) {
+                break;
+            }
+        }
+
+        if result.is_ok() {
+            self.metrics.inc_pages_stripped();
+            warn!(
+                "Sent {} after stripping oversized fields: {}",
+                page.url,
+                stripped.join(", ")
+            );
+        }
+        Ok(result)
+    }
+}
+
+#[async_trait]
+impl PageSink for KafkaSink {
+    async fn write(
+        &self,
+        url: &str,
+        page: &ParsedPage,
+        json: &str,
+        route: Route,
+    ) -> Result<Delivery> {
+        let routed = match route {
+            Route::Parsed => None,
+            Route::Noindex => self.topic_noindex.as_deref(),
+            Route::Quarantine => self.topic_quarantine.as_deref(),
+            Route::LowQuality => self.topic_low_quality.as_deref(),
+        };
+        let topic = routed.unwrap_or(&self.topic_parsed);
+
+        let key = partition_key(url, page, &self.partition_key_strategy);
+        let mut result = self.send(topic, &key, json, &page.content_hash).await?;
+        if self.strip_oversized && result.as_ref().is_err_and(is_size_error) {
+            result = self.send_stripped(topic, &key, page).await?;
+        }
+
+        let delivery = match result {
+            Ok(()) => {
+                self.metrics.inc_kafka_successful();
+                if let Some(breaker) = &self.breaker {
+                    breaker.record_success();
+                }
+                info!(
+                    "Parsed and sent page: {} (words: {}, total: {})",
+                    url,
+                    page.word_count,
+                    self.metrics.pages_processed.load(Ordering::Relaxed)
+                );
+                Delivery::Settled
+            }
+            Err(e) => {
+                error!("Failed to send message to Kafka: {}", e);
+                if is_size_error(&e) {
+                    self.metrics.inc_kafka_failed();
+                    // Resending won't make it fit; skip the page.
+                    Delivery::Settled
+                } else {
+                    self.metrics.inc_kafka_errored();
+                    // Only delivery errors point at an output outage; a single
+                    // oversized page says nothing about the broker.
+                    if let Some(breaker) = &self.breaker {
+                        breaker.record_failure();
+                    }
+                    Delivery::Failed
+                }
+            }
+        };
+
+        Ok(delivery)
+    }
+}
+
+/// Whether a produce error means the message exceeded the size limit.
+///
+/// librdkafka reports `MessageSizeTooLarge` both when the local
+/// `message.max.bytes` check fails and when the broker rejects the record;
+/// `MessageBatchTooLarge` is the broker rejecting the batch holding it.
+fn is_size_error(error: &KafkaError) -> bool {
+    matches!(
+        error,
+        KafkaError::MessageProduction(
+            RDKafkaErrorCode::MessageSizeTooLarge | RDKafkaErrorCode::MessageBatchTooLarge
+        )
+    )
+}
+
+/// Drops or shrinks the next optional field of a serialized page.
+///
+/// Returns the name of the field that was changed, or `None` once nothing is
+/// left to strip.
+fn strip_next_field(page: &mut Value, min_text_len: usize) -> Option<&'static str> {
+    if !page["body_text"].is_null() {
+        page["body_text"] = Value::Null;
+        return Some("body_text");
+    }
+
+    for field in ["images", "links"] {
+        if page[field]
+            .as_array()
+            .is_some_and(|items| !items.is_empty())
+        {
+            page[field] = Value::Array(Vec::new());
+            return Some(field);
+        }
+    }
+
+    let text = page["cleaned_text"].as_str().unwrap_or_default();
+    let len = text.chars().count();
+    if len > min_text_len {
+        let truncated: String = text.chars().take((len / 2).max(min_text_len)).collect();
+        page["cleaned_text"] = Value::String(truncated);
+        return Some("cleaned_text");
+    }
+
+    None
+}
+
+/// Chooses the Kafka key for a parsed page according to `partition_key_strategy`.
+///
+/// - `url`: the page URL (default).
+/// - `domain`: the page host, so all pages of a domain share a partition.
+/// - `canonical`: the canonical URL resolved against the page URL.
+///
+/// Falls back to the page URL whenever the strategy's value can't be derived.
+fn partition_key(url: &str, parsed: &ParsedPage, strategy: &str) -> String {
+    let key = match strategy {
+        "domain" => Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string)),
+        "canonical" => parsed.canonical_url.as_deref().and_then(|canonical| {
+            Url::parse(url)
+                .and_then(|base| base.join(canonical))
+                .ok()
+                .map(String::from)
+        }),
+        _ => None,
+    };
+
+    key.unwrap_or_else(|| url.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes_flagged_pages_with_a_topic() {
+        let config = Config {
+            respect_noindex: true,
+            kafka_topic_noindex: Some("noindex".to_string()),
+            kafka_topic_quarantine: Some("quarantine".to_string()),
+            ..Config::default()
+        };
+        let page = |noindex, link_farm_suspected, low_quality| ParsedPage {
+            noindex,
+            link_farm_suspected,
+            low_quality,
+            ..ParsedPage::default()
+        };
+
+        assert_eq!(
+            Route::for_page(&config, &page(true, true, false)),
+            Route::Noindex
+        );
+        assert_eq!(
+            Route::for_page(&config, &page(false, true, true)),
+            Route::Quarantine
+        );
+        // No low-quality topic: the page stays in the main output.
+        assert_eq!(
+            Route::for_page(&config, &page(false, false, true)),
+            Route::Parsed
+        );
+    }
+
+    #[test]
+    fn size_errors_match_on_error_code() {
+        assert!(is_size_error(&KafkaError::MessageProduction(
+            RDKafkaErrorCode::MessageSizeTooLarge
+        )));
+        assert!(is_size_error(&KafkaError::MessageProduction(
+            RDKafkaErrorCode::MessageBatchTooLarge
+        )));
+        assert!(!is_size_error(&KafkaError::MessageProduction(
+            RDKafkaErrorCode::MessageTimedOut
+        )));
+        assert!(!is_size_error(&KafkaError::Canceled));
+    }
+}
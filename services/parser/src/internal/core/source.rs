@@ -0,0 +1,105 @@
+//! Where the processing loop reads raw HTML messages from.
+//!
+//! In production this is the Kafka `StreamConsumer`; the trait lets the
+//! worker loop run against an in-memory source in tests.
+
+use async_trait::async_trait;
+use rdkafka::client::ClientContext;
+use rdkafka::consumer::{
+    BaseConsumer, CommitMode, Consumer, ConsumerContext, Rebalance, StreamConsumer,
+};
+use rdkafka::error::KafkaResult;
+use rdkafka::message::OwnedMessage;
+use rdkafka::topic_partition_list::TopicPartitionList;
+use rdkafka::Offset;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use super::offsets::OffsetTracker;
+
+/// The raw HTML consumer.
+pub type PageConsumer = StreamConsumer<RebalanceContext>;
+
+/// Consumer context dropping the tracked offsets of revoked partitions.
+pub struct RebalanceContext {
+    offsets: Arc<OffsetTracker>,
+}
+
+impl RebalanceContext {
+    pub fn new(offsets: Arc<OffsetTracker>) -> Self {
+        Self { offsets }
+    }
+}
+
+impl ClientContext for RebalanceContext {}
+
+impl ConsumerContext for RebalanceContext {
+    fn pre_rebalance(&self, _consumer: &BaseConsumer<Self>, rebalance: &Rebalance<'_>) {
+        if let Rebalance::Revoke(partitions) = rebalance {
+            for element in partitions.elements() {
+                info!(
+                    "Partition {}/{} revoked",
+                    element.topic(),
+                    element.partition()
+                );
+                self.offsets.revoke(element.topic(), element.partition());
+            }
+        }
+    }
+}
+
+/// A stream of raw messages that can be paused.
+#[async_trait]
+pub trait MessageSource: Send + Sync {
+    /// Waits for the next message.
+    async fn recv(&self) -> KafkaResult<OwnedMessage>;
+
+    /// Pauses or resumes fetching.
+    ///
+    /// Failures are logged; the breaker still stops `recv()` while open.
+    fn set_paused(&self, paused: bool);
+
+    /// Commits `next` (the next offset to consume) for a partition.
+    ///
+    /// Failures are logged; a later commit on the partition covers this one.
+    fn commit(&self, topic: &str, partition: i32, next: i64);
+}
+
+#[async_trait]
+impl MessageSource for PageConsumer {
+    async fn recv(&self) -> KafkaResult<OwnedMessage> {
+        StreamConsumer::recv(self).await.map(|msg| msg.detach())
+    }
+
+    fn set_paused(&self, paused: bool) {
+        let assignment = match self.assignment() {
+            Ok(assignment) => assignment,
+            Err(e) => {
+                warn!("Failed to read consumer assignment: {}", e);
+                TopicPartitionList::new()
+            }
+        };
+
+        let result = if paused {
+            self.pause(&assignment)
+        } else {
+            self.resume(&assignment)
+        };
+        if let Err(e) = result {
+            warn!("Failed to pause/resume consumer partitions: {}", e);
+        }
+    }
+
+    fn commit(&self, topic: &str, partition: i32, next: i64) {
+        let mut offsets = TopicPartitionList::new();
+        let result = offsets
+            .add_partition_offset(topic, partition, Offset::Offset(next))
+            .and_then(|()| Consumer::commit(self, &offsets, CommitMode::Async));
+        if let Err(e) = result {
+            warn!(
+                "Failed to commit offset {} for {}/{}: {}",
+                next, topic, partition, e
+            );
+        }
+    }
+}
@@ -2,26 +2,84 @@
 //!
 //! Provides HTTP endpoints for liveness, health checks, and basic metrics.
 
-use actix_web::{get, web, App, HttpResponse, HttpServer, Responder};
+use actix_web::{get, web, App, HttpRequest, HttpResponse, HttpServer, Responder};
+use base64::Engine;
 use serde::Serialize;
+use sha2::{Digest, Sha256};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::watch;
 use tracing::info;
 
+use crate::internal::config::Config;
 use crate::internal::core::KafkaHandler;
 
+/// State of the produce circuit breaker, as exposed in `/health` and `/metrics`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+impl BreakerState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BreakerState::Closed => "closed",
+            BreakerState::Open => "open",
+            BreakerState::HalfOpen => "half_open",
+        }
+    }
+}
+
 /// Metrics shared across the service.
 #[derive(Debug, Clone)]
 pub struct Metrics {
     pub inflight_pages: Arc<AtomicU64>,
+    pub inflight_sends: Arc<AtomicU64>,
+    pub inflight_bytes: Arc<AtomicU64>,
+    /// `BreakerState` as 0 (closed), 1 (open) or 2 (half-open).
+    pub breaker_state: Arc<AtomicU64>,
     pub pages_processed: Arc<AtomicU64>,
     pub pages_successful: Arc<AtomicU64>,
     pub pages_failed: Arc<AtomicU64>,
     pub kafka_successful: Arc<AtomicU64>,
     pub kafka_failed: Arc<AtomicU64>,
     pub kafka_errored: Arc<AtomicU64>,
+    pub pages_stripped: Arc<AtomicU64>,
+    pub s3_successful: Arc<AtomicU64>,
+    pub s3_errored: Arc<AtomicU64>,
+    pub pages_skipped_seen: Arc<AtomicU64>,
+    pub pages_skipped_status: Arc<AtomicU64>,
+    pub boilerplate_dropped: Arc<AtomicU64>,
+    pub pages_noindex: Arc<AtomicU64>,
+    pub link_farm_suspected: Arc<AtomicU64>,
+    pub pages_below_readability: Arc<AtomicU64>,
+    pub pages_title_too_short: Arc<AtomicU64>,
+    pub links_filtered_out: Arc<AtomicU64>,
+    pub schema_errors: Arc<AtomicU64>,
+    pub link_resolve_errors: Arc<AtomicU64>,
+    pub image_resolve_errors: Arc<AtomicU64>,
+    pub relative_urls_dropped: Arc<AtomicU64>,
+    pub readability_errors: Arc<AtomicU64>,
+    pub readability_recovered_after_sanitize: Arc<AtomicU64>,
+    pub readability_noop: Arc<AtomicU64>,
+    pub content_from_metadata: Arc<AtomicU64>,
+    pub content_language_mismatch: Arc<AtomicU64>,
+    pub content_strategy_readability: Arc<AtomicU64>,
+    pub content_strategy_selector: Arc<AtomicU64>,
+    pub content_strategy_body: Arc<AtomicU64>,
+    pub decode_mismatch: Arc<AtomicU64>,
+    pub non_html_skipped: Arc<AtomicU64>,
+    pub pages_dead_lettered: Arc<AtomicU64>,
+    pub images_produced: Arc<AtomicU64>,
+    pub pages_with_overflow: Arc<AtomicU64>,
+    pub summaries_produced: Arc<AtomicU64>,
+    pub sitemaps_processed: Arc<AtomicU64>,
+    pub discovered_urls_produced: Arc<AtomicU64>,
+    pub pages_routed_errors: Arc<AtomicU64>,
+    pub redirects_produced: Arc<AtomicU64>,
     pub last_message_time: Arc<tokio::sync::RwLock<Option<Instant>>>,
     pub start_time: Instant,
 }
@@ -30,12 +88,48 @@ impl Metrics {
     pub fn new() -> Self {
         Self {
             inflight_pages: Arc::new(AtomicU64::new(0)),
+            inflight_sends: Arc::new(AtomicU64::new(0)),
+            inflight_bytes: Arc::new(AtomicU64::new(0)),
+            breaker_state: Arc::new(AtomicU64::new(0)),
             pages_processed: Arc::new(AtomicU64::new(0)),
             pages_successful: Arc::new(AtomicU64::new(0)),
             pages_failed: Arc::new(AtomicU64::new(0)),
             kafka_successful: Arc::new(AtomicU64::new(0)),
             kafka_failed: Arc::new(AtomicU64::new(0)),
             kafka_errored: Arc::new(AtomicU64::new(0)),
+            pages_stripped: Arc::new(AtomicU64::new(0)),
+            s3_successful: Arc::new(AtomicU64::new(0)),
+            s3_errored: Arc::new(AtomicU64::new(0)),
+            pages_skipped_seen: Arc::new(AtomicU64::new(0)),
+            pages_skipped_status: Arc::new(AtomicU64::new(0)),
+            boilerplate_dropped: Arc::new(AtomicU64::new(0)),
+            pages_noindex: Arc::new(AtomicU64::new(0)),
+            link_farm_suspected: Arc::new(AtomicU64::new(0)),
+            pages_below_readability: Arc::new(AtomicU64::new(0)),
+            pages_title_too_short: Arc::new(AtomicU64::new(0)),
+            links_filtered_out: Arc::new(AtomicU64::new(0)),
+            schema_errors: Arc::new(AtomicU64::new(0)),
+            link_resolve_errors: Arc::new(AtomicU64::new(0)),
+            image_resolve_errors: Arc::new(AtomicU64::new(0)),
+            relative_urls_dropped: Arc::new(AtomicU64::new(0)),
+            readability_errors: Arc::new(AtomicU64::new(0)),
+            readability_recovered_after_sanitize: Arc::new(AtomicU64::new(0)),
+            readability_noop: Arc::new(AtomicU64::new(0)),
+            content_from_metadata: Arc::new(AtomicU64::new(0)),
+            content_language_mismatch: Arc::new(AtomicU64::new(0)),
+            content_strategy_readability: Arc::new(AtomicU64::new(0)),
+            content_strategy_selector: Arc::new(AtomicU64::new(0)),
+            content_strategy_body: Arc::new(AtomicU64::new(0)),
+            decode_mismatch: Arc::new(AtomicU64::new(0)),
+            non_html_skipped: Arc::new(AtomicU64::new(0)),
+            pages_dead_lettered: Arc::new(AtomicU64::new(0)),
+            images_produced: Arc::new(AtomicU64::new(0)),
+            pages_with_overflow: Arc::new(AtomicU64::new(0)),
+            summaries_produced: Arc::new(AtomicU64::new(0)),
+            sitemaps_processed: Arc::new(AtomicU64::new(0)),
+            discovered_urls_produced: Arc::new(AtomicU64::new(0)),
+            pages_routed_errors: Arc::new(AtomicU64::new(0)),
+            redirects_produced: Arc::new(AtomicU64::new(0)),
             last_message_time: Arc::new(tokio::sync::RwLock::new(None)),
             start_time: Instant::now(),
         }
@@ -49,6 +143,22 @@ impl Metrics {
         self.inflight_pages.fetch_sub(1, Ordering::Relaxed);
     }
 
+    pub fn inc_inflight_sends(&self) {
+        self.inflight_sends.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn dec_inflight_sends(&self) {
+        self.inflight_sends.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub fn add_inflight_bytes(&self, bytes: u64) {
+        self.inflight_bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    pub fn sub_inflight_bytes(&self, bytes: u64) {
+        self.inflight_bytes.fetch_sub(bytes, Ordering::Relaxed);
+    }
+
     pub fn inc_pages_processed(&self) {
         self.pages_processed.fetch_add(1, Ordering::Relaxed);
 
@@ -79,10 +189,169 @@ impl Metrics {
         self.kafka_errored.fetch_add(1, Ordering::Relaxed);
     }
 
+    pub fn inc_pages_stripped(&self) {
+        self.pages_stripped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_s3_successful(&self) {
+        self.s3_successful.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_s3_errored(&self) {
+        self.s3_errored.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_pages_skipped_seen(&self) {
+        self.pages_skipped_seen.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_pages_skipped_status(&self) {
+        self.pages_skipped_status.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_boilerplate_dropped(&self) {
+        self.boilerplate_dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_pages_noindex(&self) {
+        self.pages_noindex.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_link_farm_suspected(&self) {
+        self.link_farm_suspected.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_pages_below_readability(&self) {
+        self.pages_below_readability.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_pages_title_too_short(&self) {
+        self.pages_title_too_short.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn add_links_filtered_out(&self, links: u64) {
+        self.links_filtered_out.fetch_add(links, Ordering::Relaxed);
+    }
+
+    pub fn inc_schema_errors(&self) {
+        self.schema_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_link_resolve_errors(&self) {
+        self.link_resolve_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_image_resolve_errors(&self) {
+        self.image_resolve_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_relative_urls_dropped(&self) {
+        self.relative_urls_dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_readability_errors(&self) {
+        self.readability_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_readability_recovered_after_sanitize(&self) {
+        self.readability_recovered_after_sanitize
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_readability_noop(&self) {
+        self.readability_noop.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_content_from_metadata(&self) {
+        self.content_from_metadata.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_content_language_mismatch(&self) {
+        self.content_language_mismatch
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts the strategy (`readability`, `selector` or `body`) that produced
+    /// a page's main content.
+    pub fn inc_content_strategy(&self, strategy: &str) {
+        let counter = match strategy {
+            "readability" => &self.content_strategy_readability,
+            "selector" => &self.content_strategy_selector,
+            _ => &self.content_strategy_body,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_decode_mismatch(&self) {
+        self.decode_mismatch.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_non_html_skipped(&self) {
+        self.non_html_skipped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_pages_dead_lettered(&self) {
+        self.pages_dead_lettered.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_images_produced(&self) {
+        self.images_produced.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_pages_with_overflow(&self) {
+        self.pages_with_overflow.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_summaries_produced(&self) {
+        self.summaries_produced.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_sitemaps_processed(&self) {
+        self.sitemaps_processed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_discovered_urls_produced(&self) {
+        self.discovered_urls_produced
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_pages_routed_errors(&self) {
+        self.pages_routed_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_redirects_produced(&self) {
+        self.redirects_produced.fetch_add(1, Ordering::Relaxed);
+    }
+
     pub fn get_inflight_pages(&self) -> u64 {
         self.inflight_pages.load(Ordering::Relaxed)
     }
 
+    pub fn get_inflight_sends(&self) -> u64 {
+        self.inflight_sends.load(Ordering::Relaxed)
+    }
+
+    pub fn get_inflight_bytes(&self) -> u64 {
+        self.inflight_bytes.load(Ordering::Relaxed)
+    }
+
+    pub fn set_breaker_state(&self, state: BreakerState) {
+        let value = match state {
+            BreakerState::Closed => 0,
+            BreakerState::Open => 1,
+            BreakerState::HalfOpen => 2,
+        };
+        self.breaker_state.store(value, Ordering::Relaxed);
+    }
+
+    pub fn get_breaker_state(&self) -> BreakerState {
+        match self.breaker_state.load(Ordering::Relaxed) {
+            1 => BreakerState::Open,
+            2 => BreakerState::HalfOpen,
+            _ => BreakerState::Closed,
+        }
+    }
+
     pub fn get_pages_processed(&self) -> u64 {
         self.pages_processed.load(Ordering::Relaxed)
     }
@@ -107,6 +376,139 @@ impl Metrics {
         self.kafka_errored.load(Ordering::Relaxed)
     }
 
+    pub fn get_pages_stripped(&self) -> u64 {
+        self.pages_stripped.load(Ordering::Relaxed)
+    }
+
+    pub fn get_s3_successful(&self) -> u64 {
+        self.s3_successful.load(Ordering::Relaxed)
+    }
+
+    pub fn get_s3_errored(&self) -> u64 {
+        self.s3_errored.load(Ordering::Relaxed)
+    }
+
+    pub fn get_pages_skipped_seen(&self) -> u64 {
+        self.pages_skipped_seen.load(Ordering::Relaxed)
+    }
+
+    pub fn get_link_farm_suspected(&self) -> u64 {
+        self.link_farm_suspected.load(Ordering::Relaxed)
+    }
+
+    pub fn get_pages_below_readability(&self) -> u64 {
+        self.pages_below_readability.load(Ordering::Relaxed)
+    }
+
+    pub fn get_pages_title_too_short(&self) -> u64 {
+        self.pages_title_too_short.load(Ordering::Relaxed)
+    }
+
+    pub fn get_links_filtered_out(&self) -> u64 {
+        self.links_filtered_out.load(Ordering::Relaxed)
+    }
+
+    pub fn get_pages_skipped_status(&self) -> u64 {
+        self.pages_skipped_status.load(Ordering::Relaxed)
+    }
+
+    pub fn get_boilerplate_dropped(&self) -> u64 {
+        self.boilerplate_dropped.load(Ordering::Relaxed)
+    }
+
+    pub fn get_pages_noindex(&self) -> u64 {
+        self.pages_noindex.load(Ordering::Relaxed)
+    }
+
+    pub fn get_schema_errors(&self) -> u64 {
+        self.schema_errors.load(Ordering::Relaxed)
+    }
+
+    pub fn get_link_resolve_errors(&self) -> u64 {
+        self.link_resolve_errors.load(Ordering::Relaxed)
+    }
+
+    pub fn get_image_resolve_errors(&self) -> u64 {
+        self.image_resolve_errors.load(Ordering::Relaxed)
+    }
+
+    pub fn get_relative_urls_dropped(&self) -> u64 {
+        self.relative_urls_dropped.load(Ordering::Relaxed)
+    }
+
+    pub fn get_readability_errors(&self) -> u64 {
+        self.readability_errors.load(Ordering::Relaxed)
+    }
+
+    pub fn get_readability_recovered_after_sanitize(&self) -> u64 {
+        self.readability_recovered_after_sanitize
+            .load(Ordering::Relaxed)
+    }
+
+    pub fn get_readability_noop(&self) -> u64 {
+        self.readability_noop.load(Ordering::Relaxed)
+    }
+
+    pub fn get_content_from_metadata(&self) -> u64 {
+        self.content_from_metadata.load(Ordering::Relaxed)
+    }
+
+    pub fn get_content_language_mismatch(&self) -> u64 {
+        self.content_language_mismatch.load(Ordering::Relaxed)
+    }
+
+    pub fn get_content_strategy_readability(&self) -> u64 {
+        self.content_strategy_readability.load(Ordering::Relaxed)
+    }
+
+    pub fn get_content_strategy_selector(&self) -> u64 {
+        self.content_strategy_selector.load(Ordering::Relaxed)
+    }
+
+    pub fn get_content_strategy_body(&self) -> u64 {
+        self.content_strategy_body.load(Ordering::Relaxed)
+    }
+
+    pub fn get_decode_mismatch(&self) -> u64 {
+        self.decode_mismatch.load(Ordering::Relaxed)
+    }
+
+    pub fn get_non_html_skipped(&self) -> u64 {
+        self.non_html_skipped.load(Ordering::Relaxed)
+    }
+
+    pub fn get_pages_dead_lettered(&self) -> u64 {
+        self.pages_dead_lettered.load(Ordering::Relaxed)
+    }
+
+    pub fn get_images_produced(&self) -> u64 {
+        self.images_produced.load(Ordering::Relaxed)
+    }
+
+    pub fn get_pages_with_overflow(&self) -> u64 {
+        self.pages_with_overflow.load(Ordering::Relaxed)
+    }
+
+    pub fn get_summaries_produced(&self) -> u64 {
+        self.summaries_produced.load(Ordering::Relaxed)
+    }
+
+    pub fn get_sitemaps_processed(&self) -> u64 {
+        self.sitemaps_processed.load(Ordering::Relaxed)
+    }
+
+    pub fn get_discovered_urls_produced(&self) -> u64 {
+        self.discovered_urls_produced.load(Ordering::Relaxed)
+    }
+
+    pub fn get_pages_routed_errors(&self) -> u64 {
+        self.pages_routed_errors.load(Ordering::Relaxed)
+    }
+
+    pub fn get_redirects_produced(&self) -> u64 {
+        self.redirects_produced.load(Ordering::Relaxed)
+    }
+
     pub fn get_uptime(&self) -> u64 {
         self.start_time.elapsed().as_secs()
     }
@@ -128,13 +530,19 @@ struct HealthResponse {
     kafka_errored: u64,
     last_message_age_seconds: Option<u64>,
     kafka_connected: bool,
+    circuit_breaker: &'static str,
 }
 
 /// Health check endpoint.
+///
+/// During the first `startup_grace_secs` a disconnected consumer reports
+/// `starting` rather than `not_healthy`, so orchestrators don't kill the pod
+/// while Kafka is still bootstrapping.
 #[get("/health")]
 async fn health(
     metrics: web::Data<Arc<Metrics>>,
     kafka: web::Data<Arc<KafkaHandler>>,
+    config: web::Data<Arc<Config>>,
 ) -> impl Responder {
     let uptime = metrics.get_uptime();
     let inflight_pages = metrics.get_inflight_pages();
@@ -149,6 +557,8 @@ async fn health(
     let response = HealthResponse {
         status: if kafka_ok {
             "healthy".to_string()
+        } else if uptime < config.startup_grace_secs {
+            "starting".to_string()
         } else {
             "not_healthy".to_string()
         },
@@ -159,6 +569,7 @@ async fn health(
         kafka_errored,
         last_message_age_seconds: last_message_age,
         kafka_connected: kafka_ok,
+        circuit_breaker: metrics.get_breaker_state().as_str(),
     };
 
     HttpResponse::Ok().json(response)
@@ -188,6 +599,18 @@ async fn metrics_endpoint(metrics: web::Data<Arc<Metrics>>) -> impl Responder {
          # TYPE parser_inflight_pages gauge\n\
          parser_inflight_pages {}\n\
          \n\
+         # HELP parser_inflight_sends Parsed-page Kafka sends awaiting delivery\n\
+         # TYPE parser_inflight_sends gauge\n\
+         parser_inflight_sends {}\n\
+         \n\
+         # HELP parser_inflight_bytes Payload bytes of the pages in processing\n\
+         # TYPE parser_inflight_bytes gauge\n\
+         parser_inflight_bytes {}\n\
+         \n\
+         # HELP parser_circuit_breaker_state Produce circuit breaker: 0 closed, 1 open, 2 half-open\n\
+         # TYPE parser_circuit_breaker_state gauge\n\
+         parser_circuit_breaker_state {}\n\
+         \n\
          # HELP parser_pages_processed Total pages processed\n\
          # TYPE parser_pages_processed counter\n\
          parser_pages_processed {}\n\
@@ -212,6 +635,132 @@ async fn metrics_endpoint(metrics: web::Data<Arc<Metrics>>) -> impl Responder {
          # TYPE parser_kafka_errored counter\n\
          parser_kafka_errored {}\n\
          \n\
+         # HELP parser_pages_stripped Oversized pages sent after stripping optional fields\n\
+         # TYPE parser_pages_stripped counter\n\
+         parser_pages_stripped {}\n\
+         \n\
+         # HELP parser_s3_successful Pages written to the S3 sink\n\
+         # TYPE parser_s3_successful counter\n\
+         parser_s3_successful {}\n\
+         \n\
+         # HELP parser_s3_errored Failed S3 sink writes\n\
+         # TYPE parser_s3_errored counter\n\
+         parser_s3_errored {}\n\
+         \n\
+         # HELP parser_pages_skipped_seen Pages skipped because the URL was probably seen before\n\
+         # TYPE parser_pages_skipped_seen counter\n\
+         parser_pages_skipped_seen {}\n\
+         \n\
+         # HELP parser_pages_skipped_status Pages skipped because status_routes maps their crawl status to skip\n\
+         # TYPE parser_pages_skipped_status counter\n\
+         parser_pages_skipped_status {}\n\
+         \n\
+         # HELP parser_boilerplate_dropped Short pages dropped as matching a recent boilerplate cluster\n\
+         # TYPE parser_boilerplate_dropped counter\n\
+         parser_boilerplate_dropped {}\n\
+         \n\
+         # HELP parser_pages_noindex Pages kept out of the main topic by a robots noindex\n\
+         # TYPE parser_pages_noindex counter\n\
+         parser_pages_noindex {}\n\
+         \n\
+         # HELP parser_link_farm_suspected Pages with more outbound links than max_outbound_links\n\
+         # TYPE parser_link_farm_suspected counter\n\
+         parser_link_farm_suspected {}\n\
+         \n\
+         # HELP parser_pages_below_readability Pages with a readability_score below min_readability_score\n\
+         # TYPE parser_pages_below_readability counter\n\
+         parser_pages_below_readability {}\n\
+         \n\
+         # HELP parser_pages_title_too_short Pages whose title is shorter than min_title_len\n\
+         # TYPE parser_pages_title_too_short counter\n\
+         parser_pages_title_too_short {}\n\
+         \n\
+         # HELP parser_links_filtered_out Links dropped by outlink_host_filter\n\
+         # TYPE parser_links_filtered_out counter\n\
+         parser_links_filtered_out {}\n\
+         \n\
+         # HELP parser_schema_errors Schema.org JSON-LD blocks that failed to parse\n\
+         # TYPE parser_schema_errors counter\n\
+         parser_schema_errors {}\n\
+         \n\
+         # HELP parser_link_resolve_errors Links whose URL could not be resolved\n\
+         # TYPE parser_link_resolve_errors counter\n\
+         parser_link_resolve_errors {}\n\
+         \n\
+         # HELP parser_image_resolve_errors Images whose src could not be resolved\n\
+         # TYPE parser_image_resolve_errors counter\n\
+         parser_image_resolve_errors {}\n\
+         \n\
+         # HELP parser_relative_urls_dropped Relative links and images dropped for lack of a valid base URL\n\
+         # TYPE parser_relative_urls_dropped counter\n\
+         parser_relative_urls_dropped {}\n\
+         \n\
+         # HELP parser_readability_errors Pages where readability failed and body text was used\n\
+         # TYPE parser_readability_errors counter\n\
+         parser_readability_errors {}\n\
+         \n\
+         # HELP parser_readability_recovered_after_sanitize Pages where readability only succeeded on sanitized markup\n\
+         # TYPE parser_readability_recovered_after_sanitize counter\n\
+         parser_readability_recovered_after_sanitize {}\n\
+         \n\
+         # HELP parser_readability_noop Pages where readability kept (nearly) the whole body\n\
+         # TYPE parser_readability_noop counter\n\
+         parser_readability_noop {}\n\
+         \n\
+         # HELP parser_content_from_metadata Pages too short whose description was used as content\n\
+         # TYPE parser_content_from_metadata counter\n\
+         parser_content_from_metadata {}\n\
+         \n\
+         # HELP parser_content_language_mismatch Pages whose readability content was confidently not in the declared language\n\
+         # TYPE parser_content_language_mismatch counter\n\
+         parser_content_language_mismatch {}\n\
+         \n\
+         # HELP parser_content_strategy_total Pages by the strategy that produced their main content\n\
+         # TYPE parser_content_strategy_total counter\n\
+         parser_content_strategy_total{{strategy=\"readability\"}} {}\n\
+         parser_content_strategy_total{{strategy=\"selector\"}} {}\n\
+         parser_content_strategy_total{{strategy=\"body\"}} {}\n\
+         \n\
+         # HELP parser_decode_mismatch Pages whose decoded text was dominated by replacement characters\n\
+         # TYPE parser_decode_mismatch counter\n\
+         parser_decode_mismatch {}\n\
+         \n\
+         # HELP parser_non_html_skipped Payloads skipped because they start with a binary file signature\n\
+         # TYPE parser_non_html_skipped counter\n\
+         parser_non_html_skipped {}\n\
+         \n\
+         # HELP parser_pages_dead_lettered Payloads forwarded to the dead-letter topic\n\
+         # TYPE parser_pages_dead_lettered counter\n\
+         parser_pages_dead_lettered {}\n\
+         \n\
+         # HELP parser_images_produced Image records produced to the images topic\n\
+         # TYPE parser_images_produced counter\n\
+         parser_images_produced {}\n\
+         \n\
+         # HELP parser_pages_with_overflow Pages whose links or images exceeded the inline limits\n\
+         # TYPE parser_pages_with_overflow counter\n\
+         parser_pages_with_overflow {}\n\
+         \n\
+         # HELP parser_summaries_produced Summary records produced to the summary topic\n\
+         # TYPE parser_summaries_produced counter\n\
+         parser_summaries_produced {}\n\
+         \n\
+         # HELP parser_sitemaps_processed XML sitemaps routed to the discovered-URLs topic\n\
+         # TYPE parser_sitemaps_processed counter\n\
+         parser_sitemaps_processed {}\n\
+         \n\
+         # HELP parser_discovered_urls_produced Sitemap URLs produced to the discovered-URLs topic\n\
+         # TYPE parser_discovered_urls_produced counter\n\
+         parser_discovered_urls_produced {}\n\
+         \n\
+         # HELP parser_pages_routed_errors Pages forwarded to the errors topic by status_routes\n\
+         # TYPE parser_pages_routed_errors counter\n\
+         parser_pages_routed_errors {}\n\
+         \n\
+         # HELP parser_redirects_produced Redirect records produced by status_routes\n\
+         # TYPE parser_redirects_produced counter\n\
+         parser_redirects_produced {}\n\
+         \n\
          # HELP parser_last_message_age Last message age in seconds\n\
          # TYPE parser_last_message_age gauge\n\
          parser_last_message_age {}\n\
@@ -220,12 +769,48 @@ async fn metrics_endpoint(metrics: web::Data<Arc<Metrics>>) -> impl Responder {
          # TYPE parser_uptime_seconds gauge\n\
          parser_uptime_seconds {}\n",
         metrics.get_inflight_pages(),
+        metrics.get_inflight_sends(),
+        metrics.get_inflight_bytes(),
+        metrics.breaker_state.load(Ordering::Relaxed),
         metrics.get_pages_processed(),
         metrics.get_pages_successful(),
         metrics.get_pages_failed(),
         metrics.get_kafka_successful(),
         metrics.get_kafka_failed(),
         metrics.get_kafka_errored(),
+        metrics.get_pages_stripped(),
+        metrics.get_s3_successful(),
+        metrics.get_s3_errored(),
+        metrics.get_pages_skipped_seen(),
+        metrics.get_pages_skipped_status(),
+        metrics.get_boilerplate_dropped(),
+        metrics.get_pages_noindex(),
+        metrics.get_link_farm_suspected(),
+        metrics.get_pages_below_readability(),
+        metrics.get_pages_title_too_short(),
+        metrics.get_links_filtered_out(),
+        metrics.get_schema_errors(),
+        metrics.get_link_resolve_errors(),
+        metrics.get_image_resolve_errors(),
+        metrics.get_relative_urls_dropped(),
+        metrics.get_readability_errors(),
+        metrics.get_readability_recovered_after_sanitize(),
+        metrics.get_readability_noop(),
+        metrics.get_content_from_metadata(),
+        metrics.get_content_language_mismatch(),
+        metrics.get_content_strategy_readability(),
+        metrics.get_content_strategy_selector(),
+        metrics.get_content_strategy_body(),
+        metrics.get_decode_mismatch(),
+        metrics.get_non_html_skipped(),
+        metrics.get_pages_dead_lettered(),
+        metrics.get_images_produced(),
+        metrics.get_pages_with_overflow(),
+        metrics.get_summaries_produced(),
+        metrics.get_sitemaps_processed(),
+        metrics.get_discovered_urls_produced(),
+        metrics.get_pages_routed_errors(),
+        metrics.get_redirects_produced(),
         last_message_age,
         uptime,
     );
@@ -235,6 +820,54 @@ async fn metrics_endpoint(metrics: web::Data<Arc<Metrics>>) -> impl Responder {
         .body(metrics_text)
 }
 
+/// Effective configuration with secrets redacted (admin only).
+#[get("/config")]
+async fn config_endpoint(req: HttpRequest, config: web::Data<Arc<Config>>) -> impl Responder {
+    if !config.admin_enabled {
+        return HttpResponse::NotFound().finish();
+    }
+    if !is_admin(&req, &config) {
+        return HttpResponse::Unauthorized()
+            .insert_header(("WWW-Authenticate", "Basic realm=\"parser\""))
+            .finish();
+    }
+
+    HttpResponse::Ok().json(config.sanitized())
+}
+
+/// Checks the request's basic-auth credentials against `admin_username` / `admin_password`.
+///
+/// Both sides are hashed and the digests compared in constant time, so the
+/// response time doesn't reveal how much of the credentials matched.
+fn is_admin(req: &HttpRequest, config: &Config) -> bool {
+    let Some(password) = config.admin_password.as_deref() else {
+        return false;
+    };
+
+    let credentials = req
+        .headers()
+        .get("Authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Basic "))
+        .and_then(|encoded| {
+            base64::engine::general_purpose::STANDARD
+                .decode(encoded.trim())
+                .ok()
+        })
+        .and_then(|decoded| String::from_utf8(decoded).ok());
+
+    let Some(credentials) = credentials else {
+        return false;
+    };
+    let expected = Sha256::digest(format!("{}:{}", config.admin_username, password));
+    let given = Sha256::digest(credentials);
+    expected
+        .iter()
+        .zip(given.iter())
+        .fold(0, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
 #[get("/")]
 async fn index() -> impl Responder {
     HttpResponse::Ok().body("Parser monitor is running. See /health, /live, /metrics.")
@@ -242,14 +875,16 @@ async fn index() -> impl Responder {
 
 /// Start the monitor server, with metrics & kafka checker.
 pub async fn start_monitor_server(
-    port: u16,
+    config: Arc<Config>,
     metrics: Arc<Metrics>,
     kafka_handler: Arc<KafkaHandler>,
     mut shutdown_rx: watch::Receiver<bool>,
     shutdown_tx: watch::Sender<bool>,
 ) -> std::io::Result<()> {
+    let port = config.monitor_port;
     let metrics_data = web::Data::new(metrics);
     let kafka_data = web::Data::new(kafka_handler);
+    let config_data = web::Data::new(config);
 
     info!("Starting monitor server on port {}", port);
 
@@ -257,9 +892,11 @@ pub async fn start_monitor_server(
         App::new()
             .app_data(metrics_data.clone())
             .app_data(kafka_data.clone())
+            .app_data(config_data.clone())
             .service(health)
             .service(live)
             .service(metrics_endpoint)
+            .service(config_endpoint)
             .service(index)
     })
     .bind(("0.0.0.0", port))?
@@ -276,3 +913,36 @@ pub async fn start_monitor_server(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    fn request(user: &str, password: &str) -> HttpRequest {
+        let encoded =
+            base64::engine::general_purpose::STANDARD.encode(format!("{user}:{password}"));
+        TestRequest::default()
+            .insert_header(("Authorization", format!("Basic {encoded}")))
+            .to_http_request()
+    }
+
+    #[test]
+    fn admin_requires_matching_credentials() {
+        let config = Config {
+            admin_password: Some("hunter2".to_string()),
+            ..Config::default()
+        };
+        let user = config.admin_username.clone();
+
+        assert!(is_admin(&request(&user, "hunter2"), &config));
+        assert!(!is_admin(&request(&user, "hunter3"), &config));
+        assert!(!is_admin(&request("root", "hunter2"), &config));
+        assert!(!is_admin(
+            &TestRequest::default().to_http_request(),
+            &config
+        ));
+        // Without a password the admin endpoints stay closed.
+        assert!(!is_admin(&request(&user, ""), &Config::default()));
+    }
+}
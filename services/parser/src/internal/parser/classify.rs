@@ -0,0 +1,205 @@
+//! Coarse page-type classification.
+//!
+//! `classify_page_type` assigns one of a handful of types from signals the
+//! parser already has, checked in order with the first match winning:
+//!
+//! 1. A schema.org `@type` declared in JSON-LD (`Product`, `ItemList`,
+//!    `DiscussionForumPosting`, `Article`, ...).
+//! 2. A schema.org offer `price` → product.
+//! 3. At least `LISTING_MIN_CARDS` sibling "cards" (same tag and class, each
+//!    with a link and an image or heading at most `CARD_MAX_DEPTH` levels
+//!    down) outside `<nav>`, `<header>` and `<footer>` → listing. Skipped for
+//!    pages over `max_dom_depth`.
+//! 4. At least `FORUM_MIN_POSTS` comment or post elements → forum.
+//! 5. A single long text (`ARTICLE_MIN_WORDS` words in at least
+//!    `ARTICLE_MIN_PARAGRAPHS` paragraphs) → article.
+//!
+//! Everything else is `other`. The rules are deliberately simple; they are a
+//! ranking hint, not a ground truth.
+
+use once_cell::sync::Lazy;
+use scraper::{ElementRef, Html, Selector};
+use serde_json::Value;
+use std::collections::HashMap;
+
+use super::models::{PageType, ParsedPage};
+use super::schema::has_type;
+
+/// Sibling cards with the same tag and class needed for a listing.
+const LISTING_MIN_CARDS: usize = 8;
+
+/// Levels below a card searched for its link and image or heading.
+const CARD_MAX_DEPTH: usize = 4;
+
+/// Comment or post elements needed for a forum thread.
+const FORUM_MIN_POSTS: usize = 5;
+
+/// Words of main content needed for an article.
+const ARTICLE_MIN_WORDS: usize = 300;
+
+/// Paragraphs of main content needed for an article.
+const ARTICLE_MIN_PARAGRAPHS: usize = 3;
+
+/// JSON-LD types mapped to a page type, checked in this order.
+const SCHEMA_TYPES: &[(PageType, &[&str])] = &[
+    (PageType::Product, &["Product", "ProductGroup", "Offer"]),
+    (
+        PageType::Forum,
+        &["DiscussionForumPosting", "QAPage", "SocialMediaPosting"],
+    ),
+    (
+        PageType::Listing,
+        &["ItemList", "CollectionPage", "SearchResultsPage"],
+    ),
+    (
+        PageType::Article,
+        &[
+            "Article",
+            "NewsArticle",
+            "BlogPosting",
+            "TechArticle",
+            "ScholarlyArticle",
+            "Report",
+        ],
+    ),
+];
+
+/// Comments and posts of a discussion thread.
+static POST_SELECTOR: Lazy<Selector> = Lazy::new(|| {
+    Selector::parse(
+        "[itemprop='comment'], [itemtype$='/Comment'], [itemtype$='/DiscussionForumPosting'], \
+         .comment, .forum-post, .post-message",
+    )
+    .unwrap()
+});
+
+/// Classifies a parsed page as article, product, listing, forum or other.
+///
+/// `json_ld` are the page's flattened schema.org nodes.
+pub fn classify_page_type(parsed: &ParsedPage, document: &Html, json_ld: &[Value]) -> PageType {
+    let declared = SCHEMA_TYPES.iter().find_map(|(page_type, types)| {
+        json_ld
+            .iter()
+            .any(|node| has_type(node, types))
+            .then_some(*page_type)
+    });
+    if let Some(page_type) = declared {
+        return page_type;
+    }
+
+    if parsed.price.is_some() {
+        return PageType::Product;
+    }
+    if !parsed.dom_depth_exceeded && max_sibling_cards(document) >= LISTING_MIN_CARDS {
+        return PageType::Listing;
+    }
+    if document.select(&POST_SELECTOR).count() >= FORUM_MIN_POSTS {
+        return PageType::Forum;
+    }
+    if parsed.word_count >= ARTICLE_MIN_WORDS && parsed.paragraph_count >= ARTICLE_MIN_PARAGRAPHS {
+        return PageType::Article;
+    }
+
+    PageType::Other
+}
+
+/// The largest group of sibling cards sharing a tag and class.
+///
+/// Each element is visited once and its children grouped; navigation,
+/// headers and footers are not descended into.
+fn max_sibling_cards(document: &Html) -> usize {
+    let mut max = 0;
+    let mut stack = vec![document.root_element()];
+
+    while let Some(parent) = stack.pop() {
+        let mut groups: HashMap<(&str, &str), usize> = HashMap::new();
+        for child in parent.children().filter_map(ElementRef::wrap) {
+            if matches!(child.value().name(), "nav" | "header" | "footer") {
+                continue;
+            }
+            stack.push(child);
+            let Some(class) = child.value().attr("class") else {
+                continue;
+            };
+            if is_card(child) {
+                let count = groups
+                    .entry((child.value().name(), class.trim()))
+                    .or_default();
+                *count += 1;
+                max = max.max(*count);
+            }
+        }
+    }
+
+    max
+}
+
+/// Whether `card` holds a link and an image or heading within
+/// `CARD_MAX_DEPTH` levels.
+fn is_card(card: ElementRef<'_>) -> bool {
+    let (mut link, mut content) = (false, false);
+    let mut stack = vec![(card, 0)];
+
+    while let Some((element, depth)) = stack.pop() {
+        match element.value().name() {
+            "a" => link |= element.value().attr("href").is_some(),
+            "img" | "h2" | "h3" | "h4" => content = true,
+            _ => {}
+        }
+        if link && content {
+            return true;
+        }
+        if depth < CARD_MAX_DEPTH {
+            let children = element.children().filter_map(ElementRef::wrap);
+            stack.extend(children.map(|child| (child, depth + 1)));
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(card: &str) -> Html {
+        Html::parse_document(&format!(
+            "<html><body><ul>{}</ul></body></html>",
+            card.repeat(8)
+        ))
+    }
+
+    #[test]
+    fn sibling_cards_make_a_listing() {
+        let document = cards(r#"<li class="card"><a href="/p"><h3>Item</h3></a></li>"#);
+
+        assert_eq!(max_sibling_cards(&document), 8);
+        assert_eq!(
+            classify_page_type(&ParsedPage::default(), &document, &[]),
+            PageType::Listing
+        );
+    }
+
+    #[test]
+    fn cards_must_be_shallow_and_outside_chrome() {
+        let deep = r#"<li class="card"><a href="/p">x</a><div><div><div><div><img src="i"></div></div></div></div></li>"#;
+        assert_eq!(max_sibling_cards(&cards(deep)), 0);
+
+        let nav = Html::parse_document(&format!(
+            "<html><body><nav>{}</nav></body></html>",
+            r#"<a class="card" href="/p"><img src="i"></a>"#.repeat(8)
+        ));
+        assert_eq!(max_sibling_cards(&nav), 0);
+    }
+
+    #[test]
+    fn deep_pages_skip_the_card_scan() {
+        let document = cards(r#"<li class="card"><a href="/p"><h3>Item</h3></a></li>"#);
+        let parsed = ParsedPage {
+            dom_depth_exceeded: true,
+            ..ParsedPage::default()
+        };
+
+        assert_eq!(classify_page_type(&parsed, &document, &[]), PageType::Other);
+    }
+}
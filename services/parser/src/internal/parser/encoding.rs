@@ -0,0 +1,60 @@
+//! Payload decoding and decode-mismatch detection.
+//!
+//! Payloads are decoded as UTF-8 (lossily). When the page was actually in
+//! another charset the text fills up with U+FFFD replacement characters; this
+//! module measures that and re-decodes with the declared charset.
+
+use encoding_rs::Encoding;
+use once_cell::sync::Lazy;
+use regex::bytes::Regex;
+
+/// Matches `charset=...` in a `<meta charset>` or `<meta http-equiv>` tag.
+static META_CHARSET: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?i)<meta[^>]+charset\s*=\s*["']?\s*([a-z0-9_\-:.]+)"#)
+        .expect("Failed to compile meta charset regex")
+});
+
+/// How many leading bytes are scanned for a `<meta charset>` declaration.
+const CHARSET_SNIFF_BYTES: usize = 4096;
+
+/// Fraction of characters in `text` that are U+FFFD replacement characters.
+pub fn replacement_ratio(text: &str) -> f64 {
+    let (total, replaced) = text.chars().fold((0usize, 0usize), |(total, replaced), c| {
+        (
+            total + 1,
+            replaced + usize::from(c == char::REPLACEMENT_CHARACTER),
+        )
+    });
+
+    if total == 0 {
+        0.0
+    } else {
+        replaced as f64 / total as f64
+    }
+}
+
+/// Finds the charset declared by the `content-type` header or a `<meta>` tag.
+///
+/// The header wins over the markup. Returns `None` when neither declares a
+/// charset `encoding_rs` knows.
+pub fn declared_encoding(payload: &[u8], content_type: Option<&str>) -> Option<&'static Encoding> {
+    let from_header = content_type
+        .and_then(|ct| {
+            ct.split(';')
+                .filter_map(|param| param.trim().split_once('='))
+                .find(|(name, _)| name.trim().eq_ignore_ascii_case("charset"))
+        })
+        .and_then(|(_, value)| Encoding::for_label(value.trim().trim_matches('"').as_bytes()));
+
+    from_header.or_else(|| {
+        let head = &payload[..payload.len().min(CHARSET_SNIFF_BYTES)];
+        META_CHARSET
+            .captures(head)
+            .and_then(|caps| Encoding::for_label(&caps[1]))
+    })
+}
+
+/// Decodes `payload` with `encoding`, replacing malformed sequences.
+pub fn decode(payload: &[u8], encoding: &'static Encoding) -> String {
+    encoding.decode_without_bom_handling(payload).0.into_owned()
+}
@@ -5,12 +5,25 @@
 
 use once_cell::sync::Lazy;
 use readability::extractor;
-use scraper::{Html, Selector};
+use regex::Regex;
+use scraper::{ElementRef, Html, Node, Selector};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 use std::io::Cursor;
+use std::time::{Duration, Instant};
 use url::Url;
 
-use super::models::{Heading, ImageData, LinkData};
-use super::text_utils::clean_text;
+use crate::internal::config::DataAttrSpec;
+
+use super::language_detector::normalize_lang_code;
+use super::models::{
+    AlternateLink, CodeBlock, Faq, FormField, FormInfo, Heading, HreflangLink, ImageData,
+    ImageSource, LanguageSpan, LinkData, OpenGraphData, TwitterCardData,
+};
+use super::schema::{has_type, text_property};
+use super::text_utils::{clean_text, clean_text_preserving_paragraphs, content_hash};
+use super::PLACEHOLDER_TITLE;
+use crate::internal::monitor::Metrics;
 
 // Precompiled selectors for performance
 
@@ -21,156 +34,1124 @@ static HEADING_SELECTOR: Lazy<Selector> =
 /// Selector for "a href"
 static LINK_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse("a[href]").unwrap());
 
-/// Selector for "img src"
-static IMG_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse("img[src]").unwrap());
+/// Selector for images and `<picture>` elements
+static IMG_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse("img, picture").unwrap());
+
+/// Selector for `<img>` with an `alt`, for icon link text
+static IMG_ALT_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse("img[alt]").unwrap());
+
+/// Selector for `<img>` inside a `<picture>`
+static IMG_ONLY_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse("img").unwrap());
+
+/// Selector for `<source>` children of a `<picture>`
+static SOURCE_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse("source[srcset]").unwrap());
+
+/// Selector for code blocks
+static CODE_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse("pre, code").unwrap());
+
+/// Selector for paragraphs
+static PARAGRAPH_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse("p").unwrap());
+
+/// Selector for in-page fragment targets
+static ID_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse("[id]").unwrap());
 
 /// Selector for "body" fallback
 static BODY_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse("body").unwrap());
 
 /// Extracts and cleans all `<h1>`–`<h6>` headings from the document.
-pub fn extract_headings(document: &Html) -> Vec<Heading> {
+///
+/// Headings whose text is empty after `clean_text` (whitespace or `&nbsp;`
+/// only) are dropped unless `keep_empty` is set.
+pub fn extract_headings(document: &Html, keep_empty: bool) -> Vec<Heading> {
     document
         .select(&HEADING_SELECTOR)
         .filter_map(|element| {
             let tag_name = element.value().name(); // e.g. "h1"
             let level = tag_name.strip_prefix('h')?.parse::<u8>().ok()?;
             let text = clean_text(&element.text().collect::<String>());
-            if text.is_empty() {
+            if text.is_empty() && !keep_empty {
                 return None;
             }
-            Some(Heading { level, text })
+            Some(Heading {
+                level,
+                text,
+                raw_text: None,
+            })
         })
         .collect()
 }
 
 /// Extracts all `<a>` links, resolving relative URLs and marking external links.
 ///
-/// Skips links with `javascript:` or `mailto:` schemes or empty text.
+/// Skips links with `javascript:` or `mailto:` schemes, and links with empty
+/// text unless `keep_empty` is set.
 ///
 /// # Arguments
 /// - `document`: Parsed HTML document.
-/// - `base_url`: URL of the page, used to resolve relative links.
+/// - `base`: Resolution base from `resolution_base`; without one, relative links are dropped.
+/// - `keep_empty`: Keep links whose anchor text is empty after cleaning.
+/// - `text_fallback`: For links without visible text, use `aria-label`, then
+///   `title`, then the `alt` of a contained `<img>` as the text.
+/// - `metrics`: Counts links whose URL could not be resolved or was dropped.
 ///
 /// # Returns
 /// A vector of `LinkData`.
-pub fn extract_links(document: &Html, base_url: &str) -> Vec<LinkData> {
-    let base = Url::parse(base_url).ok();
-
+pub fn extract_links(
+    document: &Html,
+    base: Option<&Url>,
+    keep_empty: bool,
+    text_fallback: bool,
+    metrics: &Metrics,
+) -> Vec<LinkData> {
     document
         .select(&LINK_SELECTOR)
         .filter_map(|element| {
             let href = element.value().attr("href")?;
-            let text = clean_text(&element.text().collect::<String>());
-
-            if href.starts_with("javascript:") || href.starts_with("mailto:") || text.is_empty() {
-                return None;
+            let mut text = element.text().collect::<String>();
+            if text_fallback && clean_text(&text).is_empty() {
+                if let Some(fallback) = link_text_fallback(element) {
+                    text = fallback.to_string();
+                }
             }
+            build_link(href, &text, base, keep_empty, metrics)
+        })
+        .collect()
+}
 
-            let resolved_url = if let Some(base) = &base {
-                base.join(href)
-                    .map(|mut u| {
-                        u.set_fragment(None);
-                        u
-                    })
-                    .unwrap_or_else(|_| Url::parse(href).unwrap_or_else(|_| base.clone()))
-            } else {
-                Url::parse(href).unwrap_or_else(|_| Url::parse("about:blank").unwrap())
-            };
+/// Text for an icon link: its `aria-label`, `title` or first non-empty image `alt`.
+fn link_text_fallback<'a>(link: ElementRef<'a>) -> Option<&'a str> {
+    let non_empty = |value: &&str| !value.trim().is_empty();
+    link.value()
+        .attr("aria-label")
+        .filter(non_empty)
+        .or_else(|| link.value().attr("title").filter(non_empty))
+        .or_else(|| {
+            link.select(&IMG_ALT_SELECTOR)
+                .filter_map(|img| img.value().attr("alt"))
+                .find(non_empty)
+        })
+}
 
-            let resolved_url_str = resolved_url.to_string();
+/// Builds a `LinkData` from a raw `href` and anchor text.
+///
+/// Returns `None` for `javascript:`/`mailto:` links, links whose text is empty
+/// after `clean_text` (whitespace or `&nbsp;` only) unless `keep_empty` is set,
+/// and relative links when there is no base to resolve them against.
+pub fn build_link(
+    href: &str,
+    raw_text: &str,
+    base: Option<&Url>,
+    keep_empty: bool,
+    metrics: &Metrics,
+) -> Option<LinkData> {
+    let text = clean_text(raw_text);
 
-            let is_external =
-                if let (Some(base), Ok(link_url)) = (base.clone(), Url::parse(&resolved_url_str)) {
-                    base.domain() != link_url.domain()
-                } else {
-                    false
-                };
+    if href.starts_with("javascript:")
+        || href.starts_with("mailto:")
+        || (text.is_empty() && !keep_empty)
+    {
+        return None;
+    }
 
-            Some(LinkData {
-                url: resolved_url_str,
-                text,
-                is_external,
-            })
+    let resolved_url = match resolve_url(base, href) {
+        Some(Ok(url)) => url,
+        Some(Err(fallback)) => {
+            metrics.inc_link_resolve_errors();
+            fallback
+        }
+        None => {
+            metrics.inc_relative_urls_dropped();
+            return None;
+        }
+    };
+
+    let is_external = base.is_some_and(|base| base.domain() != resolved_url.domain());
+
+    Some(LinkData {
+        url: resolved_url.to_string(),
+        text,
+        is_external,
+    })
+}
+
+/// The registrable domain of `host` per the public suffix list
+/// (`news.bbc.co.uk` → `bbc.co.uk`). IP addresses, and hosts without a
+/// registrable domain (`localhost`), are returned as is.
+pub fn registrable_domain(host: &str) -> String {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    if host.parse::<std::net::IpAddr>().is_ok() || host.starts_with('[') {
+        return host;
+    }
+
+    psl::domain_str(&host).map_or(host.clone(), str::to_string)
+}
+
+/// Path prefixes of share/intent endpoints on social domains, which point at
+/// a "share this page" dialog rather than the site's profile.
+const SOCIAL_SHARE_PATHS: [&str; 3] = ["/share", "/intent", "/dialog"];
+
+/// Classifies links into `platform → URL` by their registrable domain, for the
+/// `(platform, domain)` pairs of `social_platforms`. The first link per
+/// platform wins; share and intent links are skipped.
+pub fn social_links(links: &[LinkData], platforms: &[(String, String)]) -> HashMap<String, String> {
+    let mut social: HashMap<String, String> = HashMap::new();
+    if platforms.is_empty() {
+        return social;
+    }
+
+    for link in links {
+        let Ok(url) = Url::parse(&link.url) else {
+            continue;
+        };
+        let Some(domain) = url.host_str().map(registrable_domain) else {
+            continue;
+        };
+        let path = url.path().to_ascii_lowercase();
+        if SOCIAL_SHARE_PATHS
+            .iter()
+            .any(|prefix| path.starts_with(prefix))
+        {
+            continue;
+        }
+        if let Some((platform, _)) = platforms.iter().find(|(_, d)| *d == domain) {
+            social
+                .entry(platform.clone())
+                .or_insert_with(|| link.url.clone());
+        }
+    }
+    social
+}
+
+/// Registrable domains of the external links, deduplicated in order of first
+/// appearance and capped at `max` (0 disables).
+pub fn outbound_domains(links: &[LinkData], max: usize) -> Vec<String> {
+    let mut domains: Vec<String> = Vec::new();
+    for host in links
+        .iter()
+        .filter(|link| link.is_external)
+        .filter_map(|link| {
+            Url::parse(&link.url)
+                .ok()?
+                .host_str()
+                .map(registrable_domain)
         })
-        .collect()
+    {
+        if domains.len() >= max {
+            break;
+        }
+        if !domains.contains(&host) {
+            domains.push(host);
+        }
+    }
+    domains
 }
 
 /// Extracts all `<img>` elements, resolving relative `src` attributes.
 ///
+/// An `<img>` inside a `<picture>` also records the picture's `<source srcset>`
+/// candidates in `sources`, and falls back to the first candidate when it has
+/// no `src`. A `<picture>` without an `<img>` is extracted from its first
+/// candidate alone.
+///
 /// # Arguments
 /// - `document`: Parsed HTML document.
-/// - `base_url`: URL of the page, used to resolve relative image URLs.
+/// - `base`: Resolution base from `resolution_base`; without one, relative images are dropped.
+/// - `metrics`: Counts images whose `src` could not be resolved or was dropped.
 ///
 /// # Returns
 /// A vector of `ImageData`.
-pub fn extract_images(document: &Html, base_url: &str) -> Vec<ImageData> {
-    let base = Url::parse(base_url).ok();
-
+pub fn extract_images(document: &Html, base: Option<&Url>, metrics: &Metrics) -> Vec<ImageData> {
     document
         .select(&IMG_SELECTOR)
         .filter_map(|element| {
-            let src = element.value().attr("src")?;
-            let alt = element.value().attr("alt").map(|s| s.to_string());
-            let title = element.value().attr("title").map(|s| s.to_string());
-
-            let resolved_src = if let Some(base) = &base {
-                base.join(src)
-                    .map(|mut u| {
-                        u.set_fragment(None);
-                        u
-                    })
-                    .unwrap_or_else(|_| Url::parse(src).unwrap_or_else(|_| base.clone()))
+            let (img, sources) = if element.value().name() == "picture" {
+                if element.select(&IMG_ONLY_SELECTOR).next().is_some() {
+                    // Handled when the nested <img> itself is visited.
+                    return None;
+                }
+                (None, picture_sources(element, base, metrics))
             } else {
-                Url::parse(src).unwrap_or_else(|_| Url::parse("about:blank").unwrap())
+                let sources = element
+                    .parent()
+                    .and_then(ElementRef::wrap)
+                    .filter(|parent| parent.value().name() == "picture")
+                    .map(|picture| picture_sources(picture, base, metrics))
+                    .unwrap_or_default();
+                (Some(element), sources)
+            };
+
+            let attr = |name| img.and_then(|img| img.value().attr(name));
+
+            let resolved_src = match attr("src").filter(|src| !src.trim().is_empty()) {
+                Some(src) => match resolve_url(base, src) {
+                    Some(Ok(url)) => url.to_string(),
+                    Some(Err(fallback)) => {
+                        metrics.inc_image_resolve_errors();
+                        fallback.to_string()
+                    }
+                    None => {
+                        metrics.inc_relative_urls_dropped();
+                        return None;
+                    }
+                },
+                None => sources.first()?.url.clone(),
             };
 
             Some(ImageData {
-                src: resolved_src.to_string(),
-                alt,
-                title,
+                src: resolved_src,
+                alt: attr("alt").map(|s| s.to_string()),
+                title: attr("title").map(|s| s.to_string()),
+                width: pixel_attr(attr("width")),
+                height: pixel_attr(attr("height")),
+                sources,
             })
         })
         .collect()
 }
 
+/// Collects the `srcset` candidates of a `<picture>`'s `<source>` children.
+///
+/// Candidates that can't be resolved are skipped and counted like images.
+fn picture_sources(picture: ElementRef, base: Option<&Url>, metrics: &Metrics) -> Vec<ImageSource> {
+    let mut sources = Vec::new();
+
+    for source in picture.select(&SOURCE_SELECTOR) {
+        let attr = |name| {
+            source
+                .value()
+                .attr(name)
+                .map(clean_text)
+                .filter(|value| !value.is_empty())
+        };
+        let media = attr("media");
+        let media_type = attr("type").map(|t| t.to_ascii_lowercase());
+
+        let srcset = source.value().attr("srcset").unwrap_or_default();
+        for candidate in srcset.split(',') {
+            let mut parts = candidate.split_whitespace();
+            let Some(href) = parts.next() else {
+                continue;
+            };
+
+            let url = match resolve_url(base, href) {
+                Some(Ok(url)) => url,
+                Some(Err(_)) => {
+                    metrics.inc_image_resolve_errors();
+                    continue;
+                }
+                None => {
+                    metrics.inc_relative_urls_dropped();
+                    continue;
+                }
+            };
+
+            sources.push(ImageSource {
+                url: url.to_string(),
+                descriptor: parts.next().map(str::to_string),
+                media: media.clone(),
+                media_type: media_type.clone(),
+            });
+        }
+    }
+
+    sources
+}
+
+/// Extracts `<link rel="alternate">` elements, split into hreflang translations
+/// and other formats.
+///
+/// Non-hreflang alternates are kept only when they declare a `type` or `media`,
+/// which is what distinguishes a PDF or print version from a bare duplicate.
+/// Relative URLs are resolved against `base`; alternates that can't be resolved
+/// are skipped.
+pub fn extract_alternates(
+    document: &Html,
+    base: Option<&Url>,
+) -> (Vec<HreflangLink>, Vec<AlternateLink>) {
+    static ALTERNATE_SELECTOR: Lazy<Selector> =
+        Lazy::new(|| Selector::parse("link[rel~='alternate'][href]").unwrap());
+
+    let attr = |element: &ElementRef, name| {
+        element
+            .value()
+            .attr(name)
+            .map(clean_text)
+            .filter(|value| !value.is_empty())
+    };
+
+    let mut hreflang = Vec::new();
+    let mut alternates = Vec::new();
+
+    for element in document.select(&ALTERNATE_SELECTOR) {
+        let href = element.value().attr("href").unwrap_or_default();
+        let Some(Ok(url)) = resolve_url(base, href.trim()) else {
+            continue;
+        };
+
+        if let Some(lang) = attr(&element, "hreflang") {
+            hreflang.push(HreflangLink {
+                lang,
+                url: url.to_string(),
+            });
+            continue;
+        }
+
+        let media_type = attr(&element, "type").map(|t| t.to_ascii_lowercase());
+        let media = attr(&element, "media");
+        if media_type.is_none() && media.is_none() {
+            continue;
+        }
+
+        alternates.push(AlternateLink {
+            url: url.to_string(),
+            media_type,
+            media,
+            title: attr(&element, "title"),
+        });
+    }
+
+    (hreflang, alternates)
+}
+
+/// Parses an `<img>` dimension attribute such as `"640"` or `"640px"`.
+fn pixel_attr(value: Option<&str>) -> Option<u32> {
+    let value = value?.trim();
+    value
+        .strip_suffix("px")
+        .unwrap_or(value)
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Picks the base URL relative links and images are resolved against.
+///
+/// The page URL (the Kafka key) wins; if it doesn't parse, an absolute
+/// `<base href>` is used, then an absolute canonical URL. `None` means relative
+/// URLs on the page can't be resolved.
+pub fn resolution_base(
+    page_url: &str,
+    base_href: Option<&str>,
+    canonical: Option<&str>,
+) -> Option<Url> {
+    Url::parse(page_url)
+        .ok()
+        .or_else(|| base_href.and_then(|href| Url::parse(href).ok()))
+        .or_else(|| canonical.and_then(|href| Url::parse(href).ok()))
+}
+
+/// Whether the canonical URL points somewhere other than the page itself.
+///
+/// Both URLs are compared after normalization: the canonical is resolved
+/// against the page URL, fragments are dropped and a trailing `/` on the path
+/// is ignored. A self-referential canonical, a missing one, or one that can't be
+/// parsed is not a mismatch.
+pub fn is_canonical_mismatch(page_url: &str, canonical: Option<&str>) -> bool {
+    let Some(canonical) = canonical else {
+        return false;
+    };
+    let Ok(page) = Url::parse(page_url) else {
+        return false;
+    };
+    let Ok(canonical) = page.join(canonical.trim()) else {
+        return false;
+    };
+
+    comparable_url(&page) != comparable_url(&canonical)
+}
+
+/// Whether `<link rel=canonical>` and `og:url` name different URLs.
+///
+/// Both are resolved against the page URL and normalized like for
+/// `is_canonical_mismatch`. Only a page declaring both, parseably, can mismatch.
+pub fn is_og_canonical_mismatch(
+    page_url: &str,
+    canonical: Option<&str>,
+    og_url: Option<&str>,
+) -> bool {
+    let (Some(canonical), Some(og_url)) = (canonical, og_url) else {
+        return false;
+    };
+    let Ok(page) = Url::parse(page_url) else {
+        return false;
+    };
+    let (Ok(canonical), Ok(og_url)) = (page.join(canonical.trim()), page.join(og_url.trim()))
+    else {
+        return false;
+    };
+
+    comparable_url(&canonical) != comparable_url(&og_url)
+}
+
+/// Returns the number of non-empty path segments of `page_url` and whether it
+/// is a homepage (depth 0).
+///
+/// Query strings, fragments and repeated or trailing slashes don't count, so
+/// `/a/b/`, `/a//b` and `/a/b?x=1` are all depth 2. An unparseable URL is
+/// depth 0 but not a homepage.
+pub fn url_depth(page_url: &str) -> (usize, bool) {
+    let Ok(url) = Url::parse(page_url) else {
+        return (0, false);
+    };
+    let depth = url
+        .path_segments()
+        .map_or(0, |segments| segments.filter(|s| !s.is_empty()).count());
+
+    (depth, depth == 0)
+}
+
+/// Builds the stable document id for a page.
+///
+/// The id is the lowercase hex SHA-256 of the normalized document URL: the
+/// canonical URL resolved against the page URL when `use_canonical` is set and
+/// one is present (else the page URL), with the fragment and trailing path
+/// slash removed and scheme and host lowercased by URL parsing. The query is
+/// kept. This definition is part of the output contract and must not change,
+/// or every stored id would change with it.
+pub fn doc_id(page_url: &str, canonical: Option<&str>, use_canonical: bool) -> String {
+    let page = Url::parse(page_url).ok();
+    let target = canonical
+        .filter(|_| use_canonical)
+        .and_then(|canonical| page.as_ref()?.join(canonical.trim()).ok())
+        .or(page);
+
+    let normalized = match target {
+        Some(url) => comparable_url(&url),
+        None => page_url.trim().to_string(),
+    };
+    content_hash(&normalized)
+}
+
+/// Serializes `url` without fragment and trailing path slash, for equality checks.
+fn comparable_url(url: &Url) -> String {
+    let mut url = url.clone();
+    url.set_fragment(None);
+    let path = url.path().trim_end_matches('/').to_string();
+    url.set_path(&path);
+    url.to_string()
+}
+
+/// Resolves `href` against `base`, dropping any fragment.
+///
+/// If joining fails, returns `Err` with the fallback URL the extractors still
+/// emit: `href` parsed as absolute, else the base itself. Returns `None` for a
+/// relative `href` with no base.
+fn resolve_url(base: Option<&Url>, href: &str) -> Option<Result<Url, Url>> {
+    let Some(base) = base else {
+        return Url::parse(href).ok().map(Ok);
+    };
+
+    match base.join(href) {
+        Ok(mut url) => {
+            url.set_fragment(None);
+            Some(Ok(url))
+        }
+        Err(_) => Some(Err(Url::parse(href).unwrap_or_else(|_| base.clone()))),
+    }
+}
+
+/// Extracts `<pre>` blocks and standalone `<code>` elements verbatim.
+///
+/// Unlike other extractors, the text is not passed through `clean_text`, so
+/// indentation and line breaks survive. `<code>` nested in a `<pre>` is part of
+/// that block rather than a block of its own.
+pub fn extract_code_blocks(document: &Html) -> Vec<CodeBlock> {
+    document
+        .select(&CODE_SELECTOR)
+        .filter(|element| {
+            element.value().name() == "pre"
+                || !element
+                    .ancestors()
+                    .filter_map(ElementRef::wrap)
+                    .any(|a| a.value().name() == "pre")
+        })
+        .filter_map(|element| {
+            let code = element.text().collect::<String>();
+            if code.trim().is_empty() {
+                return None;
+            }
+
+            let language = code_language(&element).or_else(|| {
+                element
+                    .descendants()
+                    .filter_map(ElementRef::wrap)
+                    .find_map(|child| code_language(&child))
+            });
+
+            Some(CodeBlock { code, language })
+        })
+        .collect()
+}
+
+/// Reads the language from a `language-*` or `lang-*` class.
+fn code_language(element: &ElementRef) -> Option<String> {
+    element.value().classes().find_map(|class| {
+        class
+            .strip_prefix("language-")
+            .or_else(|| class.strip_prefix("lang-"))
+            .filter(|lang| !lang.is_empty())
+            .map(|lang| lang.to_lowercase())
+    })
+}
+
+/// How the main content was obtained.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentStrategy {
+    /// Readability selected the content.
+    Readability,
+    /// A domain profile's `content_selector` matched.
+    Selector,
+    /// The whole body text was used.
+    Body,
+}
+
+impl ContentStrategy {
+    /// Label used for `parser_content_strategy_total`.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Readability => "readability",
+            Self::Selector => "selector",
+            Self::Body => "body",
+        }
+    }
+}
+
+/// The main content of a page: its cleaned text and the subtree it came from.
+pub struct MainContent {
+    /// Cleaned main content text.
+    pub text: String,
+
+    /// Cleaned text of the whole `<body>`, regardless of readability.
+    pub body_text: String,
+
+    /// Which strategy produced `text`.
+    pub strategy: ContentStrategy,
+
+    /// The content root as a standalone fragment, for extractors that only
+    /// look inside the main content.
+    pub root: Html,
+
+    /// Whether the body nests deeper than `max_dom_depth`.
+    pub depth_exceeded: bool,
+
+    /// Whether readability returned an error on the original markup.
+    pub readability_failed: bool,
+
+    /// Whether readability only produced content after sanitizing the markup.
+    pub recovered_after_sanitize: bool,
+
+    /// Time spent in readability, including the sanitized retry; `None` when
+    /// it did not run.
+    pub readability_duration: Option<Duration>,
+}
+
+impl MainContent {
+    fn empty() -> Self {
+        Self {
+            text: String::new(),
+            body_text: String::new(),
+            strategy: ContentStrategy::Body,
+            root: Html::new_fragment(),
+            depth_exceeded: false,
+            readability_failed: false,
+            recovered_after_sanitize: false,
+            readability_duration: None,
+        }
+    }
+}
+
+/// Settings for `extract_main_content`.
+pub struct ContentOptions<'a> {
+    /// Maximum element depth below `<body>` to descend into.
+    pub max_depth: usize,
+
+    /// Retry readability once on sanitized markup when it fails.
+    pub sanitize_retry: bool,
+
+    /// Main-content selector from a domain profile, tried before readability.
+    pub content_selector: Option<&'a Selector>,
+
+    /// In-content junk (`content_exclude_selectors`) removed from the content
+    /// root before its text is collected.
+    pub exclude_selector: Option<&'a Selector>,
+}
+
+/// Sanitizer applied to the markup before retrying readability.
+///
+/// Keeps ammonia's default tag whitelist plus `class`/`id`, which readability
+/// scores on, and drops scripts, styles and embedded objects with their content.
+static SANITIZER: Lazy<ammonia::Builder<'static>> = Lazy::new(|| {
+    let mut builder = ammonia::Builder::default();
+    builder
+        .add_generic_attributes(["class", "id"])
+        .clean_content_tags(HashSet::from([
+            "script", "style", "noscript", "template", "iframe", "object", "embed", "svg", "title",
+        ]));
+    builder
+});
+
 /// Extracts the main readable content from the page using `readability`.
 ///
-/// If readability fails, falls back to body text.
+/// When `content_selector` is set and its first match has text, that element
+/// is the content and readability is skipped. If readability fails (errors or
+/// finds no text) and `sanitize_retry` is set, it is retried once on sanitized
+/// markup; if that also fails, falls back to body text. Readability is also
+/// skipped when `base_url` does not parse.
+///
+/// Body text is collected no deeper than `max_depth` levels; when the body
+/// nests deeper, readability is skipped entirely and the truncated body text
+/// is used, bounding the work spent on pathological markup.
 ///
 /// # Arguments
 /// - `document`: Parsed HTML document.
 /// - `base_url`: The URL of the page, used by readability.
+/// - `options`: Depth limit and retry behaviour.
 ///
 /// # Returns
-/// Cleaned main content text, or empty string if extraction fails.
-pub fn extract_main_content(document: &Html, base_url: &str) -> String {
-    // Get the original HTML as a string
-    let html_str = document.root_element().html();
+/// The main content, with empty text if extraction fails.
+pub fn extract_main_content(
+    document: &Html,
+    base_url: &str,
+    options: &ContentOptions,
+) -> MainContent {
+    // Readability needs the base URL; without one only the fallbacks run.
+    let url = Url::parse(base_url).ok();
 
-    // Create a BufRead from the HTML string
-    let mut reader = Cursor::new(html_str);
+    let (body_text, depth_exceeded) = extract_body_text(document, options.max_depth);
 
-    // Parse the base URL
-    let url = match Url::parse(base_url) {
-        Ok(u) => u,
-        Err(_) => return String::new(),
+    let mut content = MainContent {
+        body_text,
+        depth_exceeded,
+        ..MainContent::empty()
     };
 
-    // Run readability
-    if let Ok(article) = extractor::extract(&mut reader, &url) {
-        let doc = Html::parse_fragment(&article.content);
-        let text = clean_text(&doc.root_element().text().collect::<String>());
-        if !text.is_empty() {
-            return text;
+    if let Some(selector) = options.content_selector {
+        if let Some(element) = document.select(selector).next() {
+            let root = exclude_junk(Html::parse_fragment(&element.inner_html()), options);
+            let text = clean_text(&root.root_element().text().collect::<Vec<_>>().join(" "));
+            if !text.is_empty() {
+                content.text = text;
+                content.root = root;
+                content.strategy = ContentStrategy::Selector;
+                return content;
+            }
+        }
+    }
+
+    if let Some(url) = url.as_ref().filter(|_| !depth_exceeded) {
+        let started = Instant::now();
+        // Run readability on the original markup
+        match run_readability(&document.root_element().html(), url, options) {
+            Ok(Some((text, root))) => {
+                content.text = text;
+                content.root = root;
+                content.strategy = ContentStrategy::Readability;
+                content.readability_duration = Some(started.elapsed());
+                return content;
+            }
+            Ok(None) => {}
+            Err(_) => content.readability_failed = true,
+        }
+
+        // Retry once on sanitized markup
+        if options.sanitize_retry {
+            if let Ok(Some((text, root))) = run_readability(&sanitize_html(document), url, options)
+            {
+                content.text = text;
+                content.root = root;
+                content.strategy = ContentStrategy::Readability;
+                content.recovered_after_sanitize = true;
+                content.readability_duration = Some(started.elapsed());
+                return content;
+            }
         }
+        content.readability_duration = Some(started.elapsed());
     }
 
     // Fallback to raw body text
-    if let Some(body) = document.select(&BODY_SELECTOR).next() {
-        return clean_text(&body.text().collect::<String>());
+    if document.select(&BODY_SELECTOR).next().is_some() {
+        content.text = content.body_text.clone();
+        content.root = body_root(document);
     }
 
-    String::new()
+    content
+}
+
+/// The `<body>` as a standalone fragment (empty without a body).
+pub fn body_root(document: &Html) -> Html {
+    document
+        .select(&BODY_SELECTOR)
+        .next()
+        .map(|body| Html::parse_fragment(&body.inner_html()))
+        .unwrap_or_else(Html::new_fragment)
+}
+
+/// Extracts the cleaned text of the whole `<body>`, descending at most `max_depth` levels.
+///
+/// Returns the text and whether the body nests deeper than `max_depth`.
+pub fn extract_body_text(document: &Html, max_depth: usize) -> (String, bool) {
+    document
+        .select(&BODY_SELECTOR)
+        .next()
+        .map(|body| {
+            let (text, exceeded) = collect_text_bounded(body, max_depth);
+            (clean_text(&text), exceeded)
+        })
+        .unwrap_or_default()
+}
+
+/// Runs readability, returning the cleaned text and content root if any text was found.
+fn run_readability(
+    html: &str,
+    url: &Url,
+    options: &ContentOptions,
+) -> Result<Option<(String, Html)>, readability::error::Error> {
+    let article = extractor::extract(&mut Cursor::new(html), url)?;
+    let root = exclude_junk(Html::parse_fragment(&article.content), options);
+    let text = clean_text(&root.root_element().text().collect::<String>());
+    Ok((!text.is_empty()).then_some((text, root)))
+}
+
+/// Removes `exclude_selector` matches from a content root.
+fn exclude_junk(mut root: Html, options: &ContentOptions) -> Html {
+    if let Some(exclude) = options.exclude_selector {
+        remove_elements(&mut root, exclude);
+    }
+    root
+}
+
+/// Re-serializes the document through [`SANITIZER`].
+///
+/// Serializing from a fresh parse also well-forms broken nesting, which is
+/// what most often trips readability up.
+fn sanitize_html(document: &Html) -> String {
+    SANITIZER.clean(&document.root_element().html()).to_string()
+}
+
+/// Detaches every element matching `selector` (and its subtree) from the document.
+pub fn remove_elements(document: &mut Html, selector: &Selector) {
+    let ids: Vec<_> = document
+        .select(selector)
+        .map(|element| element.id())
+        .collect();
+
+    for id in ids {
+        if let Some(mut node) = document.tree.get_mut(id) {
+            node.detach();
+        }
+    }
+}
+
+/// Removes the query string from each link URL.
+pub fn strip_link_queries(links: &mut [LinkData]) {
+    for link in links {
+        if let Ok(mut url) = Url::parse(&link.url) {
+            url.set_query(None);
+            link.url = url.to_string();
+        }
+    }
+}
+
+/// Collects the text below `element`, descending at most `max_depth` levels.
+///
+/// Returns the raw (uncleaned) text and whether any content was cut off.
+fn collect_text_bounded(element: ElementRef, max_depth: usize) -> (String, bool) {
+    let mut text = String::new();
+    let mut exceeded = false;
+    let mut stack = vec![(*element, 0)];
+
+    while let Some((node, depth)) = stack.pop() {
+        match node.value() {
+            Node::Text(t) => text.push_str(t),
+            Node::Element(_) => {
+                if depth >= max_depth {
+                    exceeded |= node.has_children();
+                    continue;
+                }
+                stack.extend(node.children().rev().map(|child| (child, depth + 1)));
+            }
+            _ => {}
+        }
+    }
+
+    (text, exceeded)
+}
+
+/// Elements that start a new line of text.
+const BLOCK_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "figcaption",
+    "figure",
+    "footer",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "td",
+    "th",
+    "tr",
+    "ul",
+];
+
+/// Returns the text of a content root with paragraph breaks between blocks
+/// (`preserve_paragraphs`).
+///
+/// Block elements are separated by a blank line and `<br>` by a newline; runs
+/// of empty paragraphs and `<br>`s from messy markup collapse to a single
+/// break (see `clean_text_preserving_paragraphs`).
+pub fn paragraph_text(root: &Html) -> String {
+    let mut text = String::new();
+    push_block_text(root.root_element(), &mut text);
+    clean_text_preserving_paragraphs(&text)
+}
+
+/// Appends `element`'s text to `text`, with newlines around blocks and at `<br>`.
+fn push_block_text(element: ElementRef, text: &mut String) {
+    for child in element.children() {
+        match child.value() {
+            Node::Text(t) => text.push_str(t),
+            Node::Element(e) if e.name() == "br" => text.push('\n'),
+            Node::Element(e) => {
+                let Some(child) = ElementRef::wrap(child) else {
+                    continue;
+                };
+                let block = BLOCK_ELEMENTS.contains(&e.name());
+                if block {
+                    text.push_str("\n\n");
+                }
+                push_block_text(child, text);
+                if block {
+                    text.push_str("\n\n");
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Returns the cleaned text of the first substantial paragraph (the lede),
+/// and the number of `<p>` elements that are non-empty after cleaning.
+///
+/// For the lede, paragraphs shorter than `min_len` characters, and paragraphs
+/// inside `<figure>`/`<figcaption>` (image captions), are skipped. Both come
+/// from a single pass over the paragraphs of `root`.
+pub fn extract_lead(root: &Html, min_len: usize) -> (Option<String>, usize) {
+    let mut lead = None;
+    let mut count = 0;
+
+    for paragraph in root.select(&PARAGRAPH_SELECTOR) {
+        let text = clean_text(&paragraph.text().collect::<String>());
+        if text.is_empty() {
+            continue;
+        }
+        count += 1;
+
+        if lead.is_none()
+            && text.chars().count() >= min_len
+            && !paragraph
+                .ancestors()
+                .filter_map(ElementRef::wrap)
+                .any(|e| matches!(e.value().name(), "figure" | "figcaption"))
+        {
+            lead = Some(text);
+        }
+    }
+
+    (lead, count)
+}
+
+/// Extracts FAQ-style `<details>` elements: the `<summary>` is the question and
+/// the rest of the element the answer.
+///
+/// A heuristic fallback for pages without `FAQPage` JSON-LD; disclosure widgets
+/// are also used for menus and spoilers, so it is noisier.
+pub fn extract_details_faqs(document: &Html) -> Vec<Faq> {
+    static DETAILS_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse("details").unwrap());
+
+    document
+        .select(&DETAILS_SELECTOR)
+        .filter_map(|details| {
+            let summary = details
+                .children()
+                .filter_map(ElementRef::wrap)
+                .find(|child| child.value().name() == "summary")?;
+            let question = clean_text(&summary.text().collect::<String>());
+
+            let answer = details
+                .children()
+                .filter(|child| child.id() != summary.id())
+                .flat_map(|child| {
+                    ElementRef::wrap(child)
+                        .map(|element| element.text().collect::<Vec<_>>())
+                        .or_else(|| child.value().as_text().map(|text| vec![&**text]))
+                        .unwrap_or_default()
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            let answer = clean_text(&answer);
+
+            (!question.is_empty() && !answer.is_empty()).then_some(Faq { question, answer })
+        })
+        .collect()
+}
+
+/// Extracts `<dl>` definition lists as `(term, definition)` pairs, cleaned.
+///
+/// Each `<dt>` is paired with the `<dd>` elements that follow it (joined with
+/// `"; "` when there are several); consecutive `<dt>`s share the definitions
+/// after them. `<dt>`/`<dd>` wrapped in a `<div>`, as HTML allows, are found too.
+pub fn extract_definition_lists(document: &Html) -> Vec<(String, String)> {
+    static DL_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse("dl").unwrap());
+
+    let mut pairs = Vec::new();
+    for list in document.select(&DL_SELECTOR) {
+        let items = list
+            .children()
+            .filter_map(ElementRef::wrap)
+            .flat_map(|child| {
+                if child.value().name() == "div" {
+                    child.children().filter_map(ElementRef::wrap).collect()
+                } else {
+                    vec![child]
+                }
+            });
+
+        let mut terms: Vec<String> = Vec::new();
+        let mut definitions: Vec<String> = Vec::new();
+        for item in items {
+            let text = clean_text(&item.text().collect::<Vec<_>>().join(" "));
+            match item.value().name() {
+                "dt" => {
+                    if !definitions.is_empty() {
+                        push_definitions(&mut pairs, &mut terms, &mut definitions);
+                    }
+                    if !text.is_empty() {
+                        terms.push(text);
+                    }
+                }
+                "dd" if !text.is_empty() => definitions.push(text),
+                _ => {}
+            }
+        }
+        push_definitions(&mut pairs, &mut terms, &mut definitions);
+    }
+    pairs
+}
+
+/// Pairs the pending terms with the pending definitions and clears both.
+fn push_definitions(
+    pairs: &mut Vec<(String, String)>,
+    terms: &mut Vec<String>,
+    definitions: &mut Vec<String>,
+) {
+    if !definitions.is_empty() {
+        let definition = definitions.join("; ");
+        pairs.extend(terms.drain(..).map(|term| (term, definition.clone())));
+    }
+    terms.clear();
+    definitions.clear();
+}
+
+/// Collects the `id` of every element in the content root as a `url#id` deep link.
+///
+/// Ids are deduplicated in document order and capped at `max`. Empty ids and
+/// pages whose URL can't be parsed yield nothing.
+pub fn extract_anchors(root: &Html, url: &str, max: usize) -> Vec<String> {
+    let Ok(mut target) = Url::parse(url) else {
+        return Vec::new();
+    };
+    let mut seen = HashSet::new();
+
+    root.select(&ID_SELECTOR)
+        .filter_map(|element| element.value().attr("id"))
+        .map(str::trim)
+        .filter(|id| !id.is_empty() && seen.insert(*id))
+        .take(max)
+        .map(|id| {
+            target.set_fragment(Some(id));
+            target.to_string()
+        })
+        .collect()
+}
+
+/// Finds paragraphs of the content root declared in a language other than the page's.
+///
+/// A paragraph's language is the `lang` of the paragraph or its nearest
+/// ancestor inside the content root. Offsets are character positions in `text`
+/// (the cleaned content), located by searching forward paragraph by paragraph.
+pub fn extract_language_spans(
+    root: &Html,
+    text: &str,
+    page_lang: Option<&str>,
+) -> Vec<LanguageSpan> {
+    let page_lang = page_lang.map(normalize_lang_code);
+    let mut spans = Vec::new();
+    let mut cursor = 0;
+
+    for paragraph in root.select(&PARAGRAPH_SELECTOR) {
+        let lang = std::iter::once(paragraph)
+            .chain(paragraph.ancestors().filter_map(ElementRef::wrap))
+            .find_map(|e| e.value().attr("lang"))
+            .map(str::trim)
+            .filter(|lang| !lang.is_empty());
+
+        let paragraph_text = clean_text(&paragraph.text().collect::<String>());
+        if paragraph_text.is_empty() {
+            continue;
+        }
+
+        let Some(found) = text[cursor..].find(&paragraph_text) else {
+            continue;
+        };
+        let start_byte = cursor + found;
+        cursor = start_byte + paragraph_text.len();
+
+        let Some(lang) = lang else {
+            continue;
+        };
+        if page_lang.as_deref() == Some(normalize_lang_code(lang).as_str()) {
+            continue;
+        }
+
+        let start = text[..start_byte].chars().count();
+        spans.push(LanguageSpan {
+            start,
+            end: start + paragraph_text.chars().count(),
+            lang: lang.to_string(),
+        });
+    }
+
+    spans
+}
+
+/// Extracts the declared `<html lang>` attribute.
+pub fn extract_html_lang(document: &Html) -> Option<String> {
+    document
+        .root_element()
+        .value()
+        .attr("lang")
+        .map(str::trim)
+        .filter(|lang| !lang.is_empty())
+        .map(str::to_string)
 }
 
 /// Extracts the `<title>` tag.
@@ -181,7 +1162,35 @@ pub fn extract_title(document: &Html) -> String {
         .select(&TITLE_SELECTOR)
         .next()
         .map(|e| clean_text(&e.inner_html()))
-        .unwrap_or_else(|| "No Title".to_string())
+        .unwrap_or_else(|| PLACEHOLDER_TITLE.to_string())
+}
+
+/// Title candidates for pages without a `<title>`: `og:title`, then
+/// `twitter:title`, then the first non-empty `<h1>`.
+pub fn extract_title_fallback(document: &Html) -> Option<String> {
+    static TWITTER_SELECTOR: Lazy<Selector> =
+        Lazy::new(|| Selector::parse("meta[name][content]").unwrap());
+    static H1_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse("h1").unwrap());
+
+    extract_meta_property(document, "og:title")
+        .or_else(|| {
+            document
+                .select(&TWITTER_SELECTOR)
+                .filter(|e| {
+                    e.value()
+                        .attr("name")
+                        .is_some_and(|name| name.trim().eq_ignore_ascii_case("twitter:title"))
+                })
+                .filter_map(|e| e.value().attr("content"))
+                .map(clean_text)
+                .find(|v| !v.is_empty())
+        })
+        .or_else(|| {
+            document
+                .select(&H1_SELECTOR)
+                .map(|e| clean_text(&e.text().collect::<String>()))
+                .find(|v| !v.is_empty())
+        })
 }
 
 /// Extracts `<meta name="description">`.
@@ -196,6 +1205,510 @@ pub fn extract_meta_description(document: &Html) -> Option<String> {
         .map(clean_text)
 }
 
+/// Extracts the content of the first non-empty `<meta property="...">` with the given property.
+pub fn extract_meta_property(document: &Html, property: &str) -> Option<String> {
+    static PROPERTY_SELECTOR: Lazy<Selector> =
+        Lazy::new(|| Selector::parse("meta[property][content]").unwrap());
+
+    document
+        .select(&PROPERTY_SELECTOR)
+        .filter(|e| e.value().attr("property") == Some(property))
+        .filter_map(|e| e.value().attr("content"))
+        .map(clean_text)
+        .find(|v| !v.is_empty())
+}
+
+/// Collects the page's `og:*` properties.
+///
+/// The first non-empty value of each property wins. `og:image` is resolved
+/// against `base` (and dropped when it can't be); properties without a named
+/// field go to `additional`. Returns `None` when the page has none.
+pub fn extract_og_tags(document: &Html, base: Option<&Url>) -> Option<OpenGraphData> {
+    static OG_SELECTOR: Lazy<Selector> =
+        Lazy::new(|| Selector::parse("meta[property^='og:'][content]").unwrap());
+
+    let mut og = OpenGraphData::default();
+    let mut found = false;
+    for element in document.select(&OG_SELECTOR) {
+        let (Some(property), Some(content)) = (
+            element.value().attr("property"),
+            element.value().attr("content"),
+        ) else {
+            continue;
+        };
+        let property = property.trim().to_ascii_lowercase();
+        let content = clean_text(content);
+        if content.is_empty() {
+            continue;
+        }
+
+        let field = match property.as_str() {
+            "og:title" => &mut og.title,
+            "og:description" => &mut og.description,
+            "og:type" => &mut og.og_type,
+            "og:url" => &mut og.url,
+            "og:image" => {
+                if og.image.is_none() {
+                    og.image = resolve_url(base, &content)
+                        .and_then(Result::ok)
+                        .map(String::from);
+                    found |= og.image.is_some();
+                }
+                continue;
+            }
+            _ => {
+                og.additional.entry(property).or_insert(content);
+                found = true;
+                continue;
+            }
+        };
+        if field.is_none() {
+            *field = Some(content);
+            found = true;
+        }
+    }
+
+    found.then_some(og)
+}
+
+/// Collects the page's `twitter:*` card properties.
+///
+/// Tags are read from `name` (the documented form) or `property`; the first
+/// non-empty value of each wins, and `twitter:image:src` counts as
+/// `twitter:image`. Missing `title`, `description` and `image` fall back to
+/// the page's Open Graph values, as Twitter itself does. Returns `None` when
+/// the page has no `twitter:*` properties.
+pub fn extract_twitter_cards(
+    document: &Html,
+    base: Option<&Url>,
+    og: Option<&OpenGraphData>,
+) -> Option<TwitterCardData> {
+    static TWITTER_SELECTOR: Lazy<Selector> = Lazy::new(|| {
+        Selector::parse("meta[name^='twitter:'][content], meta[property^='twitter:'][content]")
+            .unwrap()
+    });
+
+    let mut card = TwitterCardData::default();
+    let mut found = false;
+    for element in document.select(&TWITTER_SELECTOR) {
+        let value = element.value();
+        let (Some(name), Some(content)) = (
+            value.attr("name").or_else(|| value.attr("property")),
+            value.attr("content"),
+        ) else {
+            continue;
+        };
+        let name = name.trim().to_ascii_lowercase();
+        let content = clean_text(content);
+        if content.is_empty() {
+            continue;
+        }
+        found = true;
+
+        let field = match name.as_str() {
+            "twitter:card" => &mut card.card,
+            "twitter:title" => &mut card.title,
+            "twitter:description" => &mut card.description,
+            "twitter:creator" => &mut card.creator,
+            "twitter:image" | "twitter:image:src" => {
+                if card.image.is_none() {
+                    card.image = resolve_url(base, &content)
+                        .and_then(Result::ok)
+                        .map(String::from);
+                }
+                continue;
+            }
+            _ => {
+                card.additional.entry(name).or_insert(content);
+                continue;
+            }
+        };
+        if field.is_none() {
+            *field = Some(content);
+        }
+    }
+    if !found {
+        return None;
+    }
+
+    if let Some(og) = og {
+        for (field, fallback) in [
+            (&mut card.title, &og.title),
+            (&mut card.description, &og.description),
+            (&mut card.image, &og.image),
+        ] {
+            if field.is_none() {
+                field.clone_from(fallback);
+            }
+        }
+    }
+    Some(card)
+}
+
+/// Returns the `content` of every `<meta property>` equal to `property`, in
+/// document order, without duplicates.
+pub fn extract_meta_property_all(document: &Html, property: &str) -> Vec<String> {
+    static PROPERTY_SELECTOR: Lazy<Selector> =
+        Lazy::new(|| Selector::parse("meta[property][content]").unwrap());
+
+    let mut values: Vec<String> = Vec::new();
+    for value in document
+        .select(&PROPERTY_SELECTOR)
+        .filter(|e| e.value().attr("property") == Some(property))
+        .filter_map(|e| e.value().attr("content"))
+        .map(clean_text)
+    {
+        if !value.is_empty() && !values.contains(&value) {
+            values.push(value);
+        }
+    }
+    values
+}
+
+/// Extracts `<meta name="viewport">`, whitespace-collapsed.
+pub fn extract_viewport(document: &Html) -> Option<String> {
+    static VIEWPORT_SELECTOR: Lazy<Selector> =
+        Lazy::new(|| Selector::parse("meta[name][content]").unwrap());
+
+    document
+        .select(&VIEWPORT_SELECTOR)
+        .filter(|e| {
+            e.value()
+                .attr("name")
+                .is_some_and(|name| name.trim().eq_ignore_ascii_case("viewport"))
+        })
+        .filter_map(|e| e.value().attr("content"))
+        .map(clean_text)
+        .find(|v| !v.is_empty())
+}
+
+/// Self-declared ratings that mark mature content (compared lowercased).
+const ADULT_RATINGS: [&str; 4] = ["adult", "mature", "restricted", "explicit"];
+
+/// The RTA ("Restricted To Adults") label, used as a `rating` value.
+const RTA_LABEL: &str = "rta-5042-1996-1400-1577-rta";
+
+/// Extracts `<meta name="rating">` (or `<meta http-equiv="rating">`, as the RTA
+/// label is often published), whitespace-collapsed.
+pub fn extract_content_rating(document: &Html) -> Option<String> {
+    static RATING_SELECTOR: Lazy<Selector> =
+        Lazy::new(|| Selector::parse("meta[name][content], meta[http-equiv][content]").unwrap());
+
+    document
+        .select(&RATING_SELECTOR)
+        .filter(|e| {
+            let value = e.value();
+            value
+                .attr("name")
+                .or_else(|| value.attr("http-equiv"))
+                .is_some_and(|name| name.trim().eq_ignore_ascii_case("rating"))
+        })
+        .filter_map(|e| e.value().attr("content"))
+        .map(clean_text)
+        .find(|v| !v.is_empty())
+}
+
+/// Whether a `rating` value labels the page as adult content (`adult`,
+/// `mature`, `RTA-5042-1996-1400-1577-RTA`, ...).
+pub fn is_adult_rating(rating: Option<&str>) -> bool {
+    rating.is_some_and(|rating| {
+        let rating = rating.to_lowercase();
+        rating.contains(RTA_LABEL) || ADULT_RATINGS.contains(&rating.as_str())
+    })
+}
+
+/// Whether a viewport declaration sets `width=device-width`.
+///
+/// Directives may be separated by commas or semicolons; names and values are
+/// matched case-insensitively.
+pub fn is_mobile_friendly(viewport: Option<&str>) -> bool {
+    viewport.is_some_and(|viewport| {
+        viewport.split([',', ';']).any(|directive| {
+            directive.split_once('=').is_some_and(|(name, value)| {
+                name.trim().eq_ignore_ascii_case("width")
+                    && value.trim().eq_ignore_ascii_case("device-width")
+            })
+        })
+    })
+}
+
+/// Extracts `<meta name="robots">`, lowercased and whitespace-collapsed.
+pub fn extract_robots_meta(document: &Html) -> Option<String> {
+    static ROBOTS_SELECTOR: Lazy<Selector> =
+        Lazy::new(|| Selector::parse("meta[name][content]").unwrap());
+
+    document
+        .select(&ROBOTS_SELECTOR)
+        .filter(|e| {
+            e.value()
+                .attr("name")
+                .is_some_and(|name| name.trim().eq_ignore_ascii_case("robots"))
+        })
+        .filter_map(|e| e.value().attr("content"))
+        .map(|content| clean_text(content).to_ascii_lowercase())
+        .find(|v| !v.is_empty())
+}
+
+/// Extracts the content of `<meta name>` tags listed in `names` (lowercase),
+/// keyed by name. The first non-empty value of each name wins.
+pub fn extract_meta_names(document: &Html, names: &[String]) -> HashMap<String, String> {
+    static NAMED_META_SELECTOR: Lazy<Selector> =
+        Lazy::new(|| Selector::parse("meta[name][content]").unwrap());
+
+    let mut values = HashMap::new();
+    if names.is_empty() {
+        return values;
+    }
+
+    for element in document.select(&NAMED_META_SELECTOR) {
+        let name = element
+            .value()
+            .attr("name")
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        if !names.contains(&name) || values.contains_key(&name) {
+            continue;
+        }
+
+        let content = clean_text(element.value().attr("content").unwrap_or_default());
+        if !content.is_empty() {
+            values.insert(name, content);
+        }
+    }
+
+    values
+}
+
+/// CSS named colors (CSS Color Module Level 4), plus `transparent`.
+const CSS_NAMED_COLORS: &[&str] = &[
+    "aliceblue",
+    "antiquewhite",
+    "aqua",
+    "aquamarine",
+    "azure",
+    "beige",
+    "bisque",
+    "black",
+    "blanchedalmond",
+    "blue",
+    "blueviolet",
+    "brown",
+    "burlywood",
+    "cadetblue",
+    "chartreuse",
+    "chocolate",
+    "coral",
+    "cornflowerblue",
+    "cornsilk",
+    "crimson",
+    "cyan",
+    "darkblue",
+    "darkcyan",
+    "darkgoldenrod",
+    "darkgray",
+    "darkgreen",
+    "darkgrey",
+    "darkkhaki",
+    "darkmagenta",
+    "darkolivegreen",
+    "darkorange",
+    "darkorchid",
+    "darkred",
+    "darksalmon",
+    "darkseagreen",
+    "darkslateblue",
+    "darkslategray",
+    "darkslategrey",
+    "darkturquoise",
+    "darkviolet",
+    "deeppink",
+    "deepskyblue",
+    "dimgray",
+    "dimgrey",
+    "dodgerblue",
+    "firebrick",
+    "floralwhite",
+    "forestgreen",
+    "fuchsia",
+    "gainsboro",
+    "ghostwhite",
+    "gold",
+    "goldenrod",
+    "gray",
+    "green",
+    "greenyellow",
+    "grey",
+    "honeydew",
+    "hotpink",
+    "indianred",
+    "indigo",
+    "ivory",
+    "khaki",
+    "lavender",
+    "lavenderblush",
+    "lawngreen",
+    "lemonchiffon",
+    "lightblue",
+    "lightcoral",
+    "lightcyan",
+    "lightgoldenrodyellow",
+    "lightgray",
+    "lightgreen",
+    "lightgrey",
+    "lightpink",
+    "lightsalmon",
+    "lightseagreen",
+    "lightskyblue",
+    "lightslategray",
+    "lightslategrey",
+    "lightsteelblue",
+    "lightyellow",
+    "lime",
+    "limegreen",
+    "linen",
+    "magenta",
+    "maroon",
+    "mediumaquamarine",
+    "mediumblue",
+    "mediumorchid",
+    "mediumpurple",
+    "mediumseagreen",
+    "mediumslateblue",
+    "mediumspringgreen",
+    "mediumturquoise",
+    "mediumvioletred",
+    "midnightblue",
+    "mintcream",
+    "mistyrose",
+    "moccasin",
+    "navajowhite",
+    "navy",
+    "oldlace",
+    "olive",
+    "olivedrab",
+    "orange",
+    "orangered",
+    "orchid",
+    "palegoldenrod",
+    "palegreen",
+    "paleturquoise",
+    "palevioletred",
+    "papayawhip",
+    "peachpuff",
+    "peru",
+    "pink",
+    "plum",
+    "powderblue",
+    "purple",
+    "rebeccapurple",
+    "red",
+    "rosybrown",
+    "royalblue",
+    "saddlebrown",
+    "salmon",
+    "sandybrown",
+    "seagreen",
+    "seashell",
+    "sienna",
+    "silver",
+    "skyblue",
+    "slateblue",
+    "slategray",
+    "slategrey",
+    "snow",
+    "springgreen",
+    "steelblue",
+    "tan",
+    "teal",
+    "thistle",
+    "tomato",
+    "transparent",
+    "turquoise",
+    "violet",
+    "wheat",
+    "white",
+    "whitesmoke",
+    "yellow",
+    "yellowgreen",
+];
+
+/// Extracts `<meta name="theme-color">`, split into a valid color and an invalid raw value.
+///
+/// A `theme-color` without a `media` attribute is preferred over one scoped to
+/// a color scheme. Valid values are hex (`#rgb`, `#rgba`, `#rrggbb`,
+/// `#rrggbbaa`, lowercased) or CSS named colors; anything else is returned as
+/// the second element so it can be kept in `additional_metadata`.
+pub fn extract_theme_color(document: &Html) -> (Option<String>, Option<String>) {
+    static THEME_COLOR_SELECTOR: Lazy<Selector> =
+        Lazy::new(|| Selector::parse("meta[name][content]").unwrap());
+
+    let mut candidates: Vec<_> = document
+        .select(&THEME_COLOR_SELECTOR)
+        .filter(|e| {
+            e.value()
+                .attr("name")
+                .is_some_and(|name| name.trim().eq_ignore_ascii_case("theme-color"))
+        })
+        .filter_map(|e| {
+            let content = clean_text(e.value().attr("content")?);
+            (!content.is_empty()).then_some((e.value().attr("media").is_some(), content))
+        })
+        .collect();
+    candidates.sort_by_key(|(scoped, _)| *scoped);
+
+    let Some((_, raw)) = candidates.into_iter().next() else {
+        return (None, None);
+    };
+
+    let color = raw.to_ascii_lowercase();
+    let is_hex = color.strip_prefix('#').is_some_and(|hex| {
+        matches!(hex.len(), 3 | 4 | 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit())
+    });
+    if is_hex || CSS_NAMED_COLORS.contains(&color.as_str()) {
+        (Some(color), None)
+    } else {
+        (None, Some(raw))
+    }
+}
+
+/// Extracts the declared favicon (`<link rel="icon">`, including `shortcut icon`),
+/// falling back to `apple-touch-icon`, resolved against `base`.
+pub fn extract_favicon(document: &Html, base: Option<&Url>) -> Option<String> {
+    static ICON_SELECTOR: Lazy<Selector> = Lazy::new(|| {
+        Selector::parse("link[rel~='icon'][href], link[rel~='apple-touch-icon'][href]").unwrap()
+    });
+
+    let mut icons: Vec<_> = document
+        .select(&ICON_SELECTOR)
+        .filter_map(|e| {
+            let touch = !e
+                .value()
+                .attr("rel")?
+                .split_ascii_whitespace()
+                .any(|rel| rel.eq_ignore_ascii_case("icon"));
+            let href = e.value().attr("href")?.trim();
+            match resolve_url(base, href)? {
+                Ok(url) => Some((touch, url.to_string())),
+                Err(_) => None,
+            }
+        })
+        .collect();
+    icons.sort_by_key(|(touch, _)| *touch);
+
+    icons.into_iter().next().map(|(_, url)| url)
+}
+
+/// Whether robots directives such as `"noindex, follow"` forbid indexing.
+pub fn is_noindex(robots: Option<&str>) -> bool {
+    robots.is_some_and(|robots| {
+        robots
+            .split(',')
+            .map(str::trim)
+            .any(|directive| directive == "noindex" || directive == "none")
+    })
+}
+
 /// Extracts `<meta name="keywords">`.
 pub fn extract_meta_keywords(document: &Html) -> Option<String> {
     static KEYWORDS_SELECTOR: Lazy<Selector> =
@@ -208,6 +1721,17 @@ pub fn extract_meta_keywords(document: &Html) -> Option<String> {
         .map(clean_text)
 }
 
+/// Extracts the `href` of the first `<base>` element.
+pub fn extract_base_href(document: &Html) -> Option<String> {
+    static BASE_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse("base[href]").unwrap());
+
+    document
+        .select(&BASE_SELECTOR)
+        .next()
+        .and_then(|e| e.value().attr("href"))
+        .map(|href| href.to_string())
+}
+
 /// Extracts `<link rel="canonical">`.
 pub fn extract_canonical_url(document: &Html) -> Option<String> {
     static CANONICAL_SELECTOR: Lazy<Selector> =
@@ -219,3 +1743,384 @@ pub fn extract_canonical_url(document: &Html) -> Option<String> {
         .and_then(|e| e.value().attr("href"))
         .map(|href| href.to_string())
 }
+
+/// A compiled `outlink_host_filter`: host suffixes, or a regex with `regex:`.
+pub enum HostFilter {
+    Suffixes(Vec<String>),
+    Pattern(Regex),
+}
+
+impl HostFilter {
+    /// Compiles a config value, returning `None` if it is empty or the regex
+    /// is invalid.
+    pub fn compile(spec: &str) -> Option<Self> {
+        if let Some(pattern) = spec.strip_prefix("regex:") {
+            return Regex::new(pattern).ok().map(Self::Pattern);
+        }
+        let suffixes: Vec<String> = spec
+            .split(',')
+            .map(|suffix| suffix.trim().trim_start_matches('.').to_ascii_lowercase())
+            .filter(|suffix| !suffix.is_empty())
+            .collect();
+        (!suffixes.is_empty()).then_some(Self::Suffixes(suffixes))
+    }
+
+    /// Whether `url`'s host matches: equals a suffix or ends with `.suffix`
+    /// (so `gov` matches `www.usa.gov`, and `example.com` also matches its
+    /// subdomains), or matches the regex.
+    pub fn matches(&self, url: &str) -> bool {
+        let Some(host) = Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+        else {
+            return false;
+        };
+
+        match self {
+            Self::Suffixes(suffixes) => suffixes.iter().any(|suffix| {
+                host.strip_suffix(suffix.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.ends_with('.'))
+            }),
+            Self::Pattern(pattern) => pattern.is_match(&host),
+        }
+    }
+}
+
+/// A compiled `data_attr_selectors` entry.
+#[derive(Clone)]
+pub struct DataAttrSelector {
+    key: String,
+    selector: Selector,
+    attribute: String,
+}
+
+impl DataAttrSelector {
+    /// Compiles a config spec, returning `None` if the selector is invalid.
+    pub fn compile(spec: &DataAttrSpec) -> Option<Self> {
+        Some(Self {
+            key: spec.key.clone(),
+            selector: Selector::parse(&spec.selector).ok()?,
+            attribute: spec.attribute.clone(),
+        })
+    }
+}
+
+/// Extracts configured `data-*` attribute values, keyed by their output key.
+///
+/// Uses the first matching element that carries the attribute with a
+/// non-empty value.
+pub fn extract_data_attributes(
+    document: &Html,
+    selectors: &[DataAttrSelector],
+) -> HashMap<String, String> {
+    selectors
+        .iter()
+        .filter_map(|spec| {
+            let value = document
+                .select(&spec.selector)
+                .filter_map(|e| e.value().attr(&spec.attribute))
+                .map(clean_text)
+                .find(|v| !v.is_empty())?;
+            Some((spec.key.clone(), value))
+        })
+        .collect()
+}
+
+/// Whether `url`'s path contains one of `patterns` (lowercased, e.g. `/login`)
+/// ending at a path, extension or query boundary, so `/login` matches
+/// `/account/login/` and `/login.php` but not `/loginhelp`.
+pub fn matches_url_pattern(url: &str, patterns: &[String]) -> bool {
+    let Ok(url) = Url::parse(url) else {
+        return false;
+    };
+    let path = url.path().to_ascii_lowercase();
+
+    patterns.iter().any(|pattern| {
+        path.match_indices(pattern.as_str()).any(|(start, _)| {
+            path[start + pattern.len()..]
+                .chars()
+                .next()
+                .is_none_or(|c| matches!(c, '/' | '.' | '-' | '_'))
+        })
+    })
+}
+
+/// Whether the page has a `<form>` with a password field outside the site
+/// chrome (`header`, `nav`, `footer`, `aside`), i.e. a sign-in form as the
+/// page's purpose rather than a login box in the menu.
+pub fn has_password_form(document: &Html) -> bool {
+    static PASSWORD_SELECTOR: Lazy<Selector> =
+        Lazy::new(|| Selector::parse("form input[type='password']").unwrap());
+
+    document.select(&PASSWORD_SELECTOR).any(|input| {
+        !input
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .any(|e| matches!(e.value().name(), "header" | "nav" | "footer" | "aside"))
+    })
+}
+
+/// Extracts each `<form>`'s resolved action, method and field names/types.
+///
+/// Field values (including defaults and hidden tokens) are never read.
+pub fn extract_forms(document: &Html, base: Option<&Url>) -> Vec<FormInfo> {
+    static FORM_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse("form").unwrap());
+    static FIELD_SELECTOR: Lazy<Selector> =
+        Lazy::new(|| Selector::parse("input, select, textarea").unwrap());
+
+    document
+        .select(&FORM_SELECTOR)
+        .map(|form| {
+            let action = form
+                .value()
+                .attr("action")
+                .map(str::trim)
+                .filter(|action| !action.is_empty())
+                .and_then(|action| resolve_url(base, action)?.ok())
+                .filter(|url| matches!(url.scheme(), "http" | "https"))
+                .map(String::from);
+            let method = form
+                .value()
+                .attr("method")
+                .map(str::trim)
+                .filter(|method| !method.is_empty())
+                .unwrap_or("get")
+                .to_ascii_uppercase();
+            let fields = form
+                .select(&FIELD_SELECTOR)
+                .map(|field| {
+                    let element = field.value();
+                    let field_type = match element.name() {
+                        "input" => element
+                            .attr("type")
+                            .map(|t| t.trim().to_ascii_lowercase())
+                            .filter(|t| !t.is_empty())
+                            .unwrap_or_else(|| "text".to_string()),
+                        other => other.to_string(),
+                    };
+                    FormField {
+                        name: element
+                            .attr("name")
+                            .map(str::trim)
+                            .filter(|name| !name.is_empty())
+                            .map(str::to_string),
+                        field_type,
+                    }
+                })
+                .collect();
+
+            FormInfo {
+                action,
+                method,
+                fields,
+            }
+        })
+        .collect()
+}
+
+/// Breadcrumb containers, most specific first.
+static BREADCRUMB_SELECTORS: Lazy<Vec<Selector>> = Lazy::new(|| {
+    [
+        "[itemtype$='BreadcrumbList'] [itemprop='itemListElement'] [itemprop='name']",
+        "nav[aria-label='breadcrumb'] li, nav[aria-label='Breadcrumb'] li",
+        ".breadcrumb li, .breadcrumbs li",
+    ]
+    .iter()
+    .map(|s| Selector::parse(s).unwrap())
+    .collect()
+});
+
+/// Extracts breadcrumb labels from microdata or a breadcrumb `<nav>`/list.
+///
+/// Used when the page has no `BreadcrumbList` JSON-LD. The first selector in
+/// `BREADCRUMB_SELECTORS` with any non-empty crumbs wins.
+pub fn extract_dom_breadcrumbs(document: &Html) -> Vec<String> {
+    BREADCRUMB_SELECTORS
+        .iter()
+        .map(|selector| {
+            document
+                .select(selector)
+                .map(|e| clean_text(&e.text().collect::<Vec<_>>().join(" ")))
+                .filter(|crumb| !crumb.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .find(|crumbs| !crumbs.is_empty())
+        .unwrap_or_default()
+}
+
+/// Labels treated as the site root crumb.
+const ROOT_CRUMBS: &[&str] = &["home", "homepage", "home page", "start", "index"];
+
+/// Joins breadcrumb labels into a category path such as `Electronics > Phones`.
+///
+/// A leading root crumb (`Home` and the like) is dropped. Returns `None` when
+/// no crumb is left.
+pub fn category_path(breadcrumbs: &[String], separator: &str) -> Option<String> {
+    let crumbs = match breadcrumbs.split_first() {
+        Some((first, rest)) if ROOT_CRUMBS.contains(&first.to_lowercase().as_str()) => rest,
+        _ => breadcrumbs,
+    };
+    (!crumbs.is_empty()).then(|| crumbs.join(separator))
+}
+
+/// Schema.org types that carry an `articleSection`.
+const ARTICLE_TYPES: &[&str] = &[
+    "Article",
+    "NewsArticle",
+    "BlogPosting",
+    "TechArticle",
+    "ScholarlyArticle",
+    "Report",
+];
+
+/// Picks a best-guess category for the page from publisher metadata.
+///
+/// Candidates, in order of precedence:
+/// 1. schema.org `articleSection` from a JSON-LD article node,
+/// 2. `<meta property="article:section">`,
+/// 3. `<meta name="category">`.
+///
+/// Returns the chosen category and every candidate found, keyed by source.
+pub fn extract_category(
+    document: &Html,
+    json_ld: &[Value],
+) -> (Option<String>, Vec<(&'static str, String)>) {
+    static SECTION_SELECTOR: Lazy<Selector> =
+        Lazy::new(|| Selector::parse("meta[property='article:section']").unwrap());
+    static CATEGORY_SELECTOR: Lazy<Selector> =
+        Lazy::new(|| Selector::parse("meta[name='category']").unwrap());
+
+    let meta_content = |selector: &Selector| {
+        document
+            .select(selector)
+            .filter_map(|e| e.value().attr("content"))
+            .map(clean_text)
+            .find(|v| !v.is_empty())
+    };
+
+    let schema_section = json_ld
+        .iter()
+        .filter(|node| has_type(node, ARTICLE_TYPES))
+        .find_map(|node| text_property(node, "articleSection"))
+        .map(|section| clean_text(&section));
+
+    let candidates: Vec<(&'static str, String)> = [
+        ("category_schema_article_section", schema_section),
+        (
+            "category_og_article_section",
+            meta_content(&SECTION_SELECTOR),
+        ),
+        ("category_meta", meta_content(&CATEGORY_SELECTOR)),
+    ]
+    .into_iter()
+    .filter_map(|(key, value)| Some((key, value?)))
+    .collect();
+
+    let category = candidates.first().map(|(_, value)| value.clone());
+    (category, candidates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitize_html_drops_scripts_and_keeps_scoring_attributes() {
+        let document = Html::parse_document(
+            r#"<html><body><script>track()</script><div id="content" class="post"><p>Hello</p></div></body></html>"#,
+        );
+        let sanitized = sanitize_html(&document);
+        assert!(!sanitized.contains("track()"));
+        assert!(sanitized.contains(r#"id="content""#));
+        assert!(sanitized.contains(r#"class="post""#));
+        assert!(sanitized.contains("<p>Hello</p>"));
+    }
+
+    #[test]
+    fn main_content_falls_back_to_body_text_for_an_invalid_url() {
+        let document = Html::parse_document("<html><body><p>Plain body text</p></body></html>");
+        let options = ContentOptions {
+            max_depth: 64,
+            sanitize_retry: true,
+            content_selector: None,
+            exclude_selector: None,
+        };
+
+        let content = extract_main_content(&document, "not a url", &options);
+
+        assert_eq!(content.text, "Plain body text");
+        assert_eq!(content.strategy, ContentStrategy::Body);
+        assert!(content.readability_duration.is_none());
+    }
+
+    const BLANK_ANCHORS: &str = "<html><body>\
+        <h1>Title</h1><h2>&nbsp;</h2><h3>   </h3>\
+        <a href=\"/a\">Real</a><a href=\"/b\">&nbsp;&nbsp;</a><a href=\"/c\"> \n </a>\
+        </body></html>";
+
+    #[test]
+    fn drops_blank_headings_and_links_by_default() {
+        let document = Html::parse_document(BLANK_ANCHORS);
+        let base = Url::parse("https://example.com/").unwrap();
+
+        let headings = extract_headings(&document, false);
+        let links = extract_links(&document, Some(&base), false, false, &Metrics::new());
+
+        assert_eq!(headings.len(), 1);
+        assert_eq!(headings[0].text, "Title");
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].text, "Real");
+    }
+
+    #[test]
+    fn keeps_blank_headings_and_links_when_enabled() {
+        let document = Html::parse_document(BLANK_ANCHORS);
+        let base = Url::parse("https://example.com/").unwrap();
+
+        let headings = extract_headings(&document, true);
+        let links = extract_links(&document, Some(&base), true, false, &Metrics::new());
+
+        assert_eq!(headings.len(), 3);
+        assert!(headings[1..].iter().all(|heading| heading.text.is_empty()));
+        assert_eq!(links.len(), 3);
+        assert_eq!(links[1].url, "https://example.com/b");
+        assert!(links[1..].iter().all(|link| link.text.is_empty()));
+    }
+
+    #[test]
+    fn paragraph_text_collapses_br_runs_and_empty_paragraphs() {
+        let root = Html::parse_fragment(
+            "<p>First line<br>second line</p>\
+             <p></p><p>&nbsp;</p><p> </p>\
+             <p>After<br><br><br><br>gap</p><br><br>",
+        );
+
+        assert_eq!(
+            paragraph_text(&root),
+            "First line\nsecond line\n\nAfter\n\ngap"
+        );
+    }
+
+    #[test]
+    fn registrable_domain_uses_the_public_suffix_list() {
+        assert_eq!(registrable_domain("news.bbc.co.uk"), "bbc.co.uk");
+        assert_eq!(registrable_domain("www.example.com."), "example.com");
+        assert_eq!(registrable_domain("a.b.example.com.au"), "example.com.au");
+        assert_eq!(registrable_domain("city.kawasaki.jp"), "city.kawasaki.jp");
+        assert_eq!(registrable_domain("localhost"), "localhost");
+        assert_eq!(registrable_domain("192.168.0.1"), "192.168.0.1");
+    }
+
+    #[test]
+    fn icon_links_fall_back_to_image_alt() {
+        let document = Html::parse_document(
+            r#"<html><body><a href="/home"><img src="/logo.png" alt="Home"></a></body></html>"#,
+        );
+        let base = Url::parse("https://example.com/").unwrap();
+
+        let links = extract_links(&document, Some(&base), false, true, &Metrics::new());
+
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].text, "Home");
+    }
+}
@@ -34,6 +34,161 @@ pub fn detect_language(text: &str) -> Option<String> {
     }
 }
 
+/// Like `detect_language`, but only for detections with at least `min_confidence`.
+pub fn detect_language_confident(text: &str, min_confidence: f64) -> Option<String> {
+    let text = text.trim();
+    if text.len() < 20 {
+        return None;
+    }
+
+    let info = detect(text)?;
+    (info.confidence() >= min_confidence).then(|| info.lang().code().to_string())
+}
+
+/// Normalizes a language code to its ISO 639-1 primary subtag where known.
+///
+/// Accepts BCP 47 tags (`fr-CA`, `en_US`) and the ISO 639-3 codes returned by
+/// `detect_language` (`eng`), so declared and detected languages can be compared.
+///
+/// # Example
+/// ```
+/// assert_eq!(normalize_lang_code("en-US"), "en");
+/// assert_eq!(normalize_lang_code("eng"), "en");
+/// ```
+pub fn normalize_lang_code(code: &str) -> String {
+    let primary = code
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+
+    let short = match primary.as_str() {
+        "afr" => "af",
+        "aka" => "ak",
+        "amh" => "am",
+        "ara" => "ar",
+        "aze" => "az",
+        "bel" => "be",
+        "ben" => "bn",
+        "bul" => "bg",
+        "cat" => "ca",
+        "ces" => "cs",
+        "cmn" => "zh",
+        "dan" => "da",
+        "deu" => "de",
+        "ell" => "el",
+        "eng" => "en",
+        "epo" => "eo",
+        "est" => "et",
+        "fin" => "fi",
+        "fra" => "fr",
+        "guj" => "gu",
+        "heb" => "he",
+        "hin" => "hi",
+        "hrv" => "hr",
+        "hun" => "hu",
+        "hye" => "hy",
+        "ind" => "id",
+        "ita" => "it",
+        "jav" => "jv",
+        "jpn" => "ja",
+        "kan" => "kn",
+        "kat" => "ka",
+        "khm" => "km",
+        "kor" => "ko",
+        "lat" => "la",
+        "lav" => "lv",
+        "lit" => "lt",
+        "mal" => "ml",
+        "mar" => "mr",
+        "mkd" => "mk",
+        "mya" => "my",
+        "nep" => "ne",
+        "nld" => "nl",
+        "nob" => "nb",
+        "ori" => "or",
+        "pan" => "pa",
+        "pes" => "fa",
+        "pol" => "pl",
+        "por" => "pt",
+        "ron" => "ro",
+        "rus" => "ru",
+        "sin" => "si",
+        "slk" => "sk",
+        "slv" => "sl",
+        "sna" => "sn",
+        "spa" => "es",
+        "srp" => "sr",
+        "swe" => "sv",
+        "tam" => "ta",
+        "tel" => "te",
+        "tgl" => "tl",
+        "tha" => "th",
+        "tuk" => "tk",
+        "tur" => "tr",
+        "ukr" => "uk",
+        "urd" => "ur",
+        "uzb" => "uz",
+        "vie" => "vi",
+        "yid" => "yi",
+        "zul" => "zu",
+        _ => return primary,
+    };
+
+    short.to_string()
+}
+
+/// Picks the language a page declares for itself, as an ISO 639-1 code.
+///
+/// Used when `detect_language` gives up (short or ambiguous text). The
+/// `<html lang>` attribute wins over `og:locale` (`en_US`); values that don't
+/// start with a 2–3 letter primary subtag are ignored.
+///
+/// # Example
+/// ```
+/// assert_eq!(declared_language(None, Some("fr_FR")).as_deref(), Some("fr"));
+/// ```
+pub fn declared_language(html_lang: Option<&str>, og_locale: Option<&str>) -> Option<String> {
+    [html_lang, og_locale]
+        .into_iter()
+        .flatten()
+        .map(normalize_lang_code)
+        .find(|code| (2..=3).contains(&code.len()) && code.bytes().all(|b| b.is_ascii_lowercase()))
+}
+
+/// Normalizes an Open Graph locale (`en_US`) to a BCP 47 tag (`en-US`).
+///
+/// The language subtag is lowercased and a 2-letter region uppercased; other
+/// subtags (scripts, numeric regions) are kept as given. Returns `None` unless
+/// the value starts with a 2–3 letter language subtag.
+///
+/// # Example
+/// ```
+/// assert_eq!(normalize_locale("en_US").as_deref(), Some("en-US"));
+/// assert_eq!(normalize_locale("zh_hant_tw").as_deref(), Some("zh-hant-TW"));
+/// assert_eq!(normalize_locale("pt-br").as_deref(), Some("pt-BR"));
+/// assert_eq!(normalize_locale("english"), None);
+/// ```
+pub fn normalize_locale(raw: &str) -> Option<String> {
+    let mut subtags = raw.trim().split(['_', '-']);
+    let language = subtags.next()?.to_ascii_lowercase();
+    if !(2..=3).contains(&language.len()) || !language.bytes().all(|b| b.is_ascii_lowercase()) {
+        return None;
+    }
+
+    let mut locale = language;
+    for subtag in subtags.filter(|subtag| !subtag.is_empty()) {
+        locale.push('-');
+        if subtag.len() == 2 && subtag.bytes().all(|b| b.is_ascii_alphabetic()) {
+            locale.push_str(&subtag.to_ascii_uppercase());
+        } else {
+            locale.push_str(subtag);
+        }
+    }
+    Some(locale)
+}
+
 /// Maps ISO 639-1 or ISO 639-2 language codes to PostgreSQL FTS configurations.
 ///
 /// Falls back to `"simple"` if no specific configuration exists.
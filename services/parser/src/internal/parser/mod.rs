@@ -4,87 +4,1239 @@
 //! including title, meta tags, main content, links, images, headings, etc.
 
 use anyhow::Result;
-use scraper::Html;
+use scraper::{Html, Selector};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::debug;
 
+mod classify;
+mod encoding;
 mod extractors;
 mod language_detector;
 pub mod models;
+mod schema;
+mod sitemap;
+mod sniff;
+mod streaming;
 mod text_utils;
+pub mod timestamp;
 
+use classify::classify_page_type;
+use encoding::{declared_encoding, decode, replacement_ratio};
 use extractors::{
-    extract_canonical_url, extract_headings, extract_images, extract_links, extract_main_content,
-    extract_meta_description, extract_meta_keywords, extract_title,
+    body_root, build_link, category_path, doc_id, extract_alternates, extract_anchors,
+    extract_base_href, extract_canonical_url, extract_category, extract_code_blocks,
+    extract_content_rating, extract_data_attributes, extract_definition_lists,
+    extract_details_faqs, extract_dom_breadcrumbs, extract_favicon, extract_forms,
+    extract_headings, extract_html_lang, extract_images, extract_language_spans, extract_lead,
+    extract_links, extract_main_content, extract_meta_description, extract_meta_keywords,
+    extract_meta_names, extract_meta_property, extract_meta_property_all, extract_og_tags,
+    extract_robots_meta, extract_theme_color, extract_title, extract_title_fallback,
+    extract_twitter_cards, extract_viewport, has_password_form, is_adult_rating,
+    is_canonical_mismatch, is_mobile_friendly, is_noindex, is_og_canonical_mismatch,
+    matches_url_pattern, outbound_domains, paragraph_text, remove_elements, resolution_base,
+    social_links, strip_link_queries, url_depth, ContentOptions, ContentStrategy, DataAttrSelector,
+    HostFilter, MainContent,
 };
-use language_detector::{detect_language, map_lang_to_pg};
-use models::ParsedPage;
+use language_detector::{
+    declared_language, detect_language, detect_language_confident, map_lang_to_pg,
+    normalize_lang_code, normalize_locale,
+};
+use models::{Heading, LinkData, PageType, ParsedPage, ProcessingMeta, SitemapEntry};
+use schema::{
+    extract_breadcrumbs, extract_business, extract_events, extract_faqs, extract_json_ld,
+    extract_price, extract_rating, extract_video,
+};
+use sitemap::{extract_sitemap, is_sitemap};
+use sniff::sniff_binary;
+use streaming::extract_streaming;
+use text_utils::{
+    clean_text, content_hash, count_words, make_preview, normalize_key, readability_score,
+    strip_site_suffix, token_counts,
+};
+
+use crate::internal::config::{Config, DomainProfile, LengthGate};
+use crate::internal::monitor::Metrics;
 
-use crate::internal::config::Config;
+/// Parse failures that should be routed to the dead-letter topic rather than dropped.
+#[derive(Debug, thiserror::Error)]
+pub enum DeadLetterError {
+    /// The decoded text is dominated by U+FFFD replacement characters.
+    #[error("Decode mismatch: {:.1}% replacement characters", ratio * 100.0)]
+    DecodeMismatch { ratio: f64 },
+
+    /// The payload starts with the signature of a binary file format.
+    #[error("Non-HTML payload: looks like {kind}")]
+    NonHtml { kind: &'static str },
+
+    /// `strict_extraction` is set and extraction produced warnings.
+    #[error("Strict extraction: {}", warnings.join(", "))]
+    StrictExtraction { warnings: Vec<String> },
+}
+
+impl DeadLetterError {
+    /// Short machine-readable reason, sent as the `x-dead-letter-reason` header.
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Self::DecodeMismatch { .. } => "decode_mismatch",
+            Self::NonHtml { .. } => "non_html",
+            Self::StrictExtraction { .. } => "strict_extraction",
+        }
+    }
+}
+
+/// A `DomainProfile` with its selectors compiled.
+struct CompiledProfile {
+    profile: DomainProfile,
+    content_selector: Option<Selector>,
+    remove_selector: Option<Selector>,
+}
+
+impl CompiledProfile {
+    fn compile(profile: DomainProfile) -> Self {
+        let compile = |selector: &Option<String>| {
+            selector
+                .as_deref()
+                .and_then(|selector| Selector::parse(selector).ok())
+        };
+
+        Self {
+            content_selector: compile(&profile.content_selector),
+            remove_selector: compile(&profile.remove_selectors),
+            profile,
+        }
+    }
+
+    fn strip_query(&self) -> bool {
+        self.profile.strip_query.unwrap_or(false)
+    }
+}
+
+/// Pages with a password form count as sign-in pages only up to this many
+/// words; longer pages are content that happens to include a login box.
+const AUTH_FORM_MAX_WORDS: usize = 200;
+
+/// Title extractors emit when a page has none.
+pub(crate) const PLACEHOLDER_TITLE: &str = "No Title";
 
 /// HTML parser that extracts structured data from a page.
 ///
-/// Initialized with a `Config` to enforce content limits & settings.
-#[derive(Clone)]
+/// Initialized with a `Config` to enforce content limits & settings, and the
+/// shared `Metrics` to record extraction errors. Parsing only needs `&self`,
+/// so one instance (behind an `Arc`) serves every worker.
 pub struct HtmlParser {
     config: Config,
+    metrics: Arc<Metrics>,
+    data_attr_selectors: Vec<DataAttrSelector>,
+    capture_meta_names: Vec<String>,
+    auth_url_patterns: Vec<String>,
+    content_exclude: Option<Selector>,
+    outlink_filter: Option<HostFilter>,
+    social_platforms: Vec<(String, String)>,
+    length_gates: HashMap<String, LengthGate>,
+    domain_profiles: HashMap<String, CompiledProfile>,
 }
 
 impl HtmlParser {
     /// Creates a new `HtmlParser`.
-    pub fn new(config: &Config) -> Self {
+    pub fn new(config: &Config, metrics: Arc<Metrics>) -> Self {
+        let data_attr_selectors = config
+            .data_attr_specs()
+            .iter()
+            .filter_map(DataAttrSelector::compile)
+            .collect();
+
         Self {
             config: config.clone(),
+            metrics,
+            data_attr_selectors,
+            capture_meta_names: config.capture_meta_names(),
+            auth_url_patterns: config.auth_url_patterns(),
+            // Validated at startup.
+            content_exclude: config
+                .content_exclude_selectors
+                .as_deref()
+                .and_then(|selector| Selector::parse(selector).ok()),
+            outlink_filter: config
+                .outlink_host_filter
+                .as_deref()
+                .and_then(HostFilter::compile),
+            social_platforms: config.social_platforms(),
+            length_gates: config.length_gates(),
+            // Validated at startup; a load failure here just means no profiles.
+            domain_profiles: config
+                .domain_profiles()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(host, profile)| (host, CompiledProfile::compile(profile)))
+                .collect(),
+        }
+    }
+
+    /// Returns the entries of an XML sitemap payload, or `None` when the
+    /// payload is not a sitemap.
+    ///
+    /// Sitemaps are recognized by their `<urlset>` or `<sitemapindex>` root
+    /// element, more leniently when `content_type` (resolved like for pages)
+    /// is an XML type. Relative `<loc>` values are resolved against `url`.
+    pub fn parse_sitemap(
+        &self,
+        payload: &[u8],
+        url: &str,
+        content_type: Option<&str>,
+    ) -> Option<Vec<SitemapEntry>> {
+        let content_type = self.resolve_content_type(content_type);
+        if !is_sitemap(payload, &content_type) {
+            return None;
         }
+
+        let base = url::Url::parse(url).ok();
+        Some(extract_sitemap(
+            &String::from_utf8_lossy(payload),
+            base.as_ref(),
+        ))
+    }
+
+    /// Decodes a raw payload and parses it.
+    ///
+    /// With `sniff_binary_payloads`, payloads starting with a PNG, JPEG, GIF, WebP,
+    /// PDF, ZIP or gzip signature are rejected with `DeadLetterError::NonHtml`
+    /// before any decoding.
+    ///
+    /// The payload is decoded as UTF-8. With `decode_validation_enabled`, a
+    /// `cleaned_text` whose share of replacement characters exceeds
+    /// `max_replacement_ratio` counts as a `decode_mismatch`: the payload is
+    /// re-decoded once with the charset declared by the `content-type` header or
+    /// a `<meta charset>` tag, and fails with `DeadLetterError::DecodeMismatch`
+    /// if that doesn't help.
+    pub fn parse_payload(
+        &self,
+        payload: &[u8],
+        url: &str,
+        content_type: Option<&str>,
+    ) -> Result<ParsedPage> {
+        if self.config.sniff_binary_payloads {
+            if let Some(kind) = sniff_binary(payload) {
+                self.metrics.inc_non_html_skipped();
+                return Err(DeadLetterError::NonHtml { kind }.into());
+            }
+        }
+
+        let html = String::from_utf8_lossy(payload);
+        let parsed = self.parse_html(&html, url, content_type)?;
+
+        let max_ratio = self.config.max_replacement_ratio;
+        let ratio = replacement_ratio(&parsed.cleaned_text);
+        if !self.config.decode_validation_enabled || ratio <= max_ratio {
+            return Ok(parsed);
+        }
+
+        self.metrics.inc_decode_mismatch();
+
+        let declared = declared_encoding(payload, content_type)
+            .filter(|encoding| *encoding != encoding_rs::UTF_8);
+        if let Some(encoding) = declared {
+            debug!("Re-decoding {} as {}", url, encoding.name());
+            let mut reparsed = self.parse_html(&decode(payload, encoding), url, content_type)?;
+            if replacement_ratio(&reparsed.cleaned_text) <= max_ratio {
+                reparsed.encoding = encoding.name().to_ascii_lowercase();
+                return Ok(reparsed);
+            }
+        }
+
+        Err(DeadLetterError::DecodeMismatch { ratio }.into())
     }
 
     /// Parses HTML and returns a `ParsedPage` result.
     ///
     /// Validates content size, extracts all fields, and ensures minimum content length.
-    pub fn parse_html(&self, html: &str, url: &str) -> Result<ParsedPage> {
+    /// `content_type` is the content type the crawler reported for the payload, if any;
+    /// `content_length_overrides` can set different limits per content type.
+    ///
+    /// `extraction_mode` selects the path: `full` builds a DOM and runs every
+    /// extractor, while `body` and `metadata_only` use the streaming tokenizer.
+    ///
+    /// A `domain_profiles` entry for the URL's host (or a parent domain) can
+    /// override the mode and length limits, and add a content selector,
+    /// boilerplate removal and query stripping for links.
+    pub fn parse_html(
+        &self,
+        html: &str,
+        url: &str,
+        content_type: Option<&str>,
+    ) -> Result<ParsedPage> {
+        let content_type = self.resolve_content_type(content_type);
+        let profile = self.profile_for(url);
+        let mut gate = self.length_gate(&content_type);
+        if let Some(profile) = profile {
+            gate = profile.profile.length_gate(gate);
+        }
+
         // Enforce max content length
-        if html.len() > self.config.max_content_length {
+        if html.len() > gate.max {
             return Err(anyhow::anyhow!("Content too large: {} bytes", html.len()));
         }
 
-        let document = Html::parse_document(html);
+        let mode = profile
+            .and_then(|profile| profile.profile.extraction_mode.as_deref())
+            .unwrap_or(&self.config.extraction_mode);
+        let started = Instant::now();
+        let mut parsed = match mode {
+            "body" => self.parse_streaming(html, url, content_type, gate, true, profile),
+            "metadata_only" => self.parse_streaming(html, url, content_type, gate, false, profile),
+            _ => self.parse_dom(html, url, content_type, gate, profile),
+        }?;
+
+        if self.config.emit_processing_meta {
+            let meta = parsed.processing_meta.get_or_insert_with(Default::default);
+            meta.parse_duration_ms = duration_ms(started.elapsed());
+            meta.input_bytes = html.len();
+        }
+        Ok(parsed)
+    }
+
+    /// Full extraction over a materialized DOM.
+    fn parse_dom(
+        &self,
+        html: &str,
+        url: &str,
+        content_type: String,
+        gate: LengthGate,
+        profile: Option<&CompiledProfile>,
+    ) -> Result<ParsedPage> {
+        let mut document = Html::parse_document(html);
+        if let Some(selector) = profile.and_then(|p| p.remove_selector.as_ref()) {
+            remove_elements(&mut document, selector);
+        }
 
-        let title = extract_title(&document);
+        let mut title = extract_title(&document);
+        let mut raw_title = None;
+        if self.config.strip_site_suffix_from_title {
+            let site_name = extract_meta_property(&document, "og:site_name");
+            if let Some(stripped) = strip_site_suffix(&title, site_name.as_deref()) {
+                raw_title = Some(std::mem::replace(&mut title, stripped));
+            }
+        }
+        let title_too_short =
+            self.is_title_too_short(&mut title, || extract_title_fallback(&document));
         let description = extract_meta_description(&document);
         let meta_keywords = extract_meta_keywords(&document);
+        let robots_meta = extract_robots_meta(&document);
         let canonical_url = extract_canonical_url(&document);
 
-        let cleaned_text = extract_main_content(&document, url);
+        let html_lang = extract_html_lang(&document);
+
+        let MainContent {
+            text: mut cleaned_text,
+            body_text,
+            mut strategy,
+            root: mut content_root,
+            depth_exceeded: dom_depth_exceeded,
+            readability_failed,
+            recovered_after_sanitize,
+            readability_duration,
+        } = extract_main_content(&document, url, &self.content_options(profile));
+        if strategy == ContentStrategy::Readability
+            && self.config.language_mismatch_retry
+            && self.is_language_mismatch(&cleaned_text, &body_text, html_lang.as_deref())
+        {
+            debug!(
+                "Readability content not in the declared language, using body: {}",
+                url
+            );
+            cleaned_text = body_text.clone();
+            content_root = body_root(&document);
+            strategy = ContentStrategy::Body;
+        }
+        self.metrics.inc_content_strategy(strategy.label());
+        // Rebuild the text with paragraph breaks; the depth-bounded text is
+        // kept for pathologically deep markup.
+        let cleaned_text = if self.config.preserve_paragraphs && !dom_depth_exceeded {
+            paragraph_text(&content_root)
+        } else {
+            cleaned_text
+        };
+        if readability_failed {
+            self.metrics.inc_readability_errors();
+        }
+        if recovered_after_sanitize {
+            self.metrics.inc_readability_recovered_after_sanitize();
+        }
+        if strategy == ContentStrategy::Readability
+            && is_readability_noop(
+                &cleaned_text,
+                &body_text,
+                self.config.readability_noop_ratio,
+            )
+        {
+            debug!("Readability kept almost the whole body: {}", url);
+            self.metrics.inc_readability_noop();
+        }
 
         // Validate minimum content length
-        if cleaned_text.len() < self.config.min_content_length {
-            return Err(anyhow::anyhow!(
-                "Content too short: {} characters",
-                cleaned_text.len()
-            ));
+        let (cleaned_text, content_from_metadata) =
+            self.content_or_metadata(cleaned_text, gate.min, || {
+                longest_text([
+                    extract_meta_property(&document, "og:description"),
+                    extract_meta_description(&document),
+                ])
+            })?;
+
+        let mut headings = extract_headings(&document, self.config.keep_empty_headings);
+        if self.config.normalize_headings {
+            self.normalize_headings(&mut headings);
+        }
+        let base = resolution_base(
+            url,
+            extract_base_href(&document).as_deref(),
+            canonical_url.as_deref(),
+        );
+        let mut links = extract_links(
+            &document,
+            base.as_ref(),
+            self.config.keep_empty_anchors,
+            self.config.link_text_fallback,
+            &self.metrics,
+        );
+        if profile.is_some_and(CompiledProfile::strip_query) {
+            strip_link_queries(&mut links);
+        }
+        let link_stats = LinkStats::compute(&links, &body_text);
+        let outbound_domains = outbound_domains(&links, self.config.max_outbound_domains);
+        let social_links = social_links(&links, &self.social_platforms);
+        let links_filtered_out = self.filter_outlinks(&mut links);
+        let (hreflang_alternates, alternates) = extract_alternates(&document, base.as_ref());
+        let mut images = extract_images(&document, base.as_ref(), &self.metrics);
+        let images_total = images.len();
+        let images_with_alt = images.iter().filter(|image| image.alt.is_some()).count();
+        let unique_image_count = images
+            .iter()
+            .map(|image| image.src.as_str())
+            .collect::<HashSet<_>>()
+            .len();
+        if self.config.dedup_images {
+            let mut seen_srcs = HashSet::new();
+            images.retain(|image| seen_srcs.insert(image.src.clone()));
+        }
+        let mut code_blocks = extract_code_blocks(&document);
+        code_blocks.truncate(self.config.max_code_blocks);
+        let (json_ld, invalid_json_ld) = extract_json_ld(&document, &self.metrics);
+        let warnings = extraction_warnings(
+            url,
+            canonical_url.as_deref(),
+            base.as_ref(),
+            invalid_json_ld,
+        );
+        self.check_strict(&warnings)?;
+        let (category, category_candidates) = extract_category(&document, &json_ld);
+        let mut breadcrumbs = extract_breadcrumbs(&json_ld);
+        if breadcrumbs.is_empty() {
+            breadcrumbs = extract_dom_breadcrumbs(&document);
+        }
+        let business = extract_business(&json_ld);
+        let rating = extract_rating(&json_ld);
+        let price = extract_price(&json_ld);
+        let video = self
+            .config
+            .video_extraction_enabled
+            .then(|| extract_video(&json_ld, base.as_ref()))
+            .flatten();
+        let mut events = extract_events(&json_ld, self.config.extract_all_events);
+        let event = if self.config.extract_all_events {
+            events.first().cloned()
+        } else {
+            events.pop()
+        };
+        let mut faqs = extract_faqs(&json_ld);
+        if faqs.is_empty() && self.config.faq_dom_fallback {
+            faqs = extract_details_faqs(&document);
         }
 
-        let headings = extract_headings(&document);
-        let links = extract_links(&document, url);
-        let images = extract_images(&document, url);
+        let mut additional_metadata = extract_data_attributes(&document, &self.data_attr_selectors);
+        additional_metadata.extend(extract_meta_names(&document, &self.capture_meta_names));
+        let (theme_color, invalid_theme_color) = extract_theme_color(&document);
+        let viewport = extract_viewport(&document);
+        let mobile_friendly = is_mobile_friendly(viewport.as_deref());
+        let content_rating = extract_content_rating(&document);
+        if let Some(viewport) = viewport {
+            additional_metadata.insert("viewport".to_string(), viewport);
+        }
+        if let Some(invalid) = invalid_theme_color {
+            additional_metadata.insert("theme_color".to_string(), invalid);
+        }
+        if let Some(raw_title) = raw_title {
+            additional_metadata.insert("raw_title".to_string(), raw_title);
+        }
+        additional_metadata.extend(
+            category_candidates
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value)),
+        );
 
         let word_count = cleaned_text.split_whitespace().count();
-        let language = detect_language(&cleaned_text);
+        let char_count = cleaned_text.chars().count();
+        let title_word_count = count_words(&title);
+        let heading_word_count = headings.iter().map(|h| count_words(&h.text)).sum();
+        let og_url = extract_meta_property(&document, "og:url");
+        let locale = extract_meta_property(&document, "og:locale")
+            .as_deref()
+            .and_then(normalize_locale);
+        let alternate_locales: Vec<String> =
+            extract_meta_property_all(&document, "og:locale:alternate")
+                .iter()
+                .filter_map(|raw| normalize_locale(raw))
+                .filter(|alternate| locale.as_ref() != Some(alternate))
+                .collect();
+        let language = self.page_language(&cleaned_text, html_lang.as_deref(), locale.as_deref());
+        let page_lang = html_lang.or_else(|| language.clone());
+        let language_spans =
+            extract_language_spans(&content_root, &cleaned_text, page_lang.as_deref());
+        let (lead, paragraph_count) = extract_lead(&content_root, self.config.lead_min_length);
+        let anchors = extract_anchors(&content_root, url, self.config.max_anchors);
         let pg_lang = language.as_deref().map(map_lang_to_pg).unwrap_or("simple");
+        let token_counts = self
+            .config
+            .include_token_counts
+            .then(|| token_counts(&cleaned_text, pg_lang, self.config.max_token_counts));
 
-        Ok(ParsedPage {
+        let readability = readability_score(&cleaned_text, pg_lang);
+        let content_ratio = content_ratio(&cleaned_text, &body_text);
+        let og_tags = extract_og_tags(&document, base.as_ref());
+        let (depth, is_homepage) = url_depth(url);
+        let mut page = ParsedPage {
             url: url.to_string(),
+            doc_id: doc_id(
+                url,
+                canonical_url.as_deref(),
+                self.config.doc_id_use_canonical,
+            ),
+            url_depth: depth,
+            is_homepage,
             title,
             description,
+            lead,
+            paragraph_count,
+            title_is_duplicate: false,
+            description_is_duplicate: false,
+            preview: make_preview(&cleaned_text, self.config.preview_length),
+            content_hash: content_hash(&cleaned_text),
             cleaned_text,
+            body_word_count: self
+                .config
+                .include_body_text
+                .then(|| body_text.split_whitespace().count()),
+            body_char_count: self
+                .config
+                .include_body_text
+                .then(|| body_text.chars().count()),
+            body_text: self.config.include_body_text.then_some(body_text),
             headings,
+            anchors,
             links,
+            internal_link_count: link_stats.internal,
+            external_link_count: link_stats.external,
+            outbound_domains,
+            social_links,
+            link_farm_suspected: self.is_link_farm(link_stats.external),
+            readability_score: readability,
+            low_quality: self.is_low_quality(readability) || title_too_short,
+            title_too_short,
+            link_text_ratio: link_stats.text_ratio,
+            content_ratio,
+            content_from_metadata,
+            links_filtered_out,
+            auth_page: self.is_auth_url(url)
+                || (word_count <= AUTH_FORM_MAX_WORDS && has_password_form(&document)),
+            forms: if self.config.extract_forms {
+                extract_forms(&document, base.as_ref())
+            } else {
+                Vec::new()
+            },
+            theme_color,
+            mobile_friendly,
+            adult: is_adult_rating(content_rating.as_deref()),
+            content_rating,
+            favicon_url: extract_favicon(&document, base.as_ref()),
+            hreflang_alternates,
+            alternates,
+            images_total,
+            unique_image_count,
+            images_with_alt,
+            images_missing_alt: images_total - images_with_alt,
             images,
+            code_blocks,
+            canonical_mismatch: is_canonical_mismatch(url, canonical_url.as_deref()),
+            og_canonical_mismatch: is_og_canonical_mismatch(
+                url,
+                canonical_url.as_deref(),
+                og_url.as_deref(),
+            ),
+            og_url,
+            twitter_cards: extract_twitter_cards(&document, base.as_ref(), og_tags.as_ref()),
+            og_tags,
             canonical_url,
             language: Some(pg_lang.to_string()),
+            token_counts,
+            locale,
+            alternate_locales,
+            language_spans,
             word_count,
+            char_count,
+            title_word_count,
+            heading_word_count,
             meta_keywords,
+            noindex: is_noindex(robots_meta.as_deref()),
+            robots_meta,
+            category,
+            category_path: category_path(&breadcrumbs, &self.config.category_path_separator),
+            breadcrumbs,
+            business,
+            rating,
+            price,
+            video,
+            event,
+            events,
+            faqs,
+            definitions: extract_definition_lists(&document),
             timestamp: chrono::Utc::now(),
-            content_type: "text/html".to_string(),
+            content_type,
             encoding: "utf-8".to_string(),
+            additional_metadata,
+            warnings,
+            dom_depth_exceeded,
+            has_overflow: false,
+            processing_meta: self.config.emit_processing_meta.then(|| ProcessingMeta {
+                readability_duration_ms: readability_duration.map(duration_ms),
+                ..Default::default()
+            }),
+            page_type: PageType::Other,
+        };
+        page.page_type = classify_page_type(&page, &document, &json_ld);
+        Ok(page)
+    }
+
+    /// Cheap extraction in a single tokenizer pass, without a DOM.
+    ///
+    /// Fills title, description, keywords, canonical URL and links; with
+    /// `with_text` the whole body text becomes `cleaned_text` (no readability)
+    /// and the minimum content length applies. DOM-only fields stay empty.
+    fn parse_streaming(
+        &self,
+        html: &str,
+        url: &str,
+        content_type: String,
+        gate: LengthGate,
+        with_text: bool,
+        profile: Option<&CompiledProfile>,
+    ) -> Result<ParsedPage> {
+        let streamed = extract_streaming(html, with_text);
+
+        let streamed_body = clean_text(&streamed.text);
+        let mut cleaned_text = streamed_body.clone();
+        let mut content_from_metadata = false;
+        if with_text {
+            (cleaned_text, content_from_metadata) =
+                self.content_or_metadata(cleaned_text, gate.min, || {
+                    longest_text([streamed.description.as_deref().map(clean_text)])
+                })?;
+        }
+
+        let mut title = streamed
+            .title
+            .map(|t| clean_text(&t))
+            .unwrap_or_else(|| PLACEHOLDER_TITLE.to_string());
+        let mut additional_metadata = HashMap::new();
+        let viewport = streamed.viewport.as_deref().map(clean_text);
+        let mobile_friendly = is_mobile_friendly(viewport.as_deref());
+        let content_rating = streamed
+            .rating
+            .as_deref()
+            .map(clean_text)
+            .filter(|rating| !rating.is_empty());
+        if let Some(viewport) = viewport.filter(|v| !v.is_empty()) {
+            additional_metadata.insert("viewport".to_string(), viewport);
+        }
+        if self.config.strip_site_suffix_from_title {
+            if let Some(stripped) = strip_site_suffix(&title, None) {
+                let raw_title = std::mem::replace(&mut title, stripped);
+                additional_metadata.insert("raw_title".to_string(), raw_title);
+            }
+        }
+        let title_too_short = self.is_title_too_short(&mut title, || None);
+
+        let base = resolution_base(
+            url,
+            streamed.base_href.as_deref(),
+            streamed.canonical_url.as_deref(),
+        );
+        let warnings =
+            extraction_warnings(url, streamed.canonical_url.as_deref(), base.as_ref(), 0);
+        self.check_strict(&warnings)?;
+        let mut links: Vec<_> = streamed
+            .links
+            .iter()
+            .filter_map(|(href, text)| {
+                build_link(
+                    href,
+                    text,
+                    base.as_ref(),
+                    self.config.keep_empty_anchors,
+                    &self.metrics,
+                )
+            })
+            .collect();
+        if profile.is_some_and(CompiledProfile::strip_query) {
+            strip_link_queries(&mut links);
+        }
+        let link_stats = LinkStats::compute(&links, &cleaned_text);
+        let outbound_domains = outbound_domains(&links, self.config.max_outbound_domains);
+        let social_links = social_links(&links, &self.social_platforms);
+        let links_filtered_out = self.filter_outlinks(&mut links);
+        let robots_meta = streamed
+            .robots
+            .map(|robots| clean_text(&robots).to_ascii_lowercase())
+            .filter(|robots| !robots.is_empty());
+
+        let word_count = cleaned_text.split_whitespace().count();
+        let char_count = cleaned_text.chars().count();
+        let locale = streamed.og_locale.as_deref().and_then(normalize_locale);
+        let og_url = streamed
+            .og_url
+            .as_deref()
+            .map(clean_text)
+            .filter(|og_url| !og_url.is_empty());
+        let language = self.page_language(
+            &cleaned_text,
+            streamed.html_lang.as_deref(),
+            locale.as_deref(),
+        );
+        let pg_lang = language.as_deref().map(map_lang_to_pg).unwrap_or("simple");
+        let token_counts = self
+            .config
+            .include_token_counts
+            .then(|| token_counts(&cleaned_text, pg_lang, self.config.max_token_counts));
+        let body_text = (with_text && self.config.include_body_text).then(|| cleaned_text.clone());
+        let title_word_count = count_words(&title);
+
+        let readability = readability_score(&cleaned_text, pg_lang);
+        let content_ratio = content_ratio(&cleaned_text, &streamed_body);
+        let (depth, is_homepage) = url_depth(url);
+        Ok(ParsedPage {
+            url: url.to_string(),
+            doc_id: doc_id(
+                url,
+                streamed.canonical_url.as_deref(),
+                self.config.doc_id_use_canonical,
+            ),
+            url_depth: depth,
+            is_homepage,
+            title,
+            description: streamed.description.map(|d| clean_text(&d)),
+            preview: make_preview(&cleaned_text, self.config.preview_length),
+            content_hash: content_hash(&cleaned_text),
+            cleaned_text,
+            body_word_count: body_text
+                .as_ref()
+                .map(|text| text.split_whitespace().count()),
+            body_char_count: body_text.as_ref().map(|text| text.chars().count()),
+            body_text,
+            links,
+            internal_link_count: link_stats.internal,
+            external_link_count: link_stats.external,
+            outbound_domains,
+            social_links,
+            link_farm_suspected: self.is_link_farm(link_stats.external),
+            readability_score: readability,
+            low_quality: self.is_low_quality(readability) || title_too_short,
+            title_too_short,
+            link_text_ratio: link_stats.text_ratio,
+            content_ratio,
+            content_from_metadata,
+            links_filtered_out,
+            auth_page: self.is_auth_url(url),
+            mobile_friendly,
+            adult: is_adult_rating(content_rating.as_deref()),
+            content_rating,
+            canonical_mismatch: is_canonical_mismatch(url, streamed.canonical_url.as_deref()),
+            og_canonical_mismatch: is_og_canonical_mismatch(
+                url,
+                streamed.canonical_url.as_deref(),
+                og_url.as_deref(),
+            ),
+            og_url,
+            canonical_url: streamed.canonical_url,
+            language: Some(pg_lang.to_string()),
+            token_counts,
+            locale,
+            word_count,
+            char_count,
+            title_word_count,
+            meta_keywords: streamed.keywords.map(|k| clean_text(&k)),
+            noindex: is_noindex(robots_meta.as_deref()),
+            robots_meta,
+            timestamp: chrono::Utc::now(),
+            content_type,
+            encoding: "utf-8".to_string(),
+            additional_metadata,
+            warnings,
+            ..Default::default()
         })
     }
+
+    /// The page's language: detected from `text`, else declared.
+    ///
+    /// With `language_detection_enabled` off, detection is skipped and only
+    /// `<html lang>` counts.
+    fn page_language(
+        &self,
+        text: &str,
+        html_lang: Option<&str>,
+        og_locale: Option<&str>,
+    ) -> Option<String> {
+        if !self.config.language_detection_enabled {
+            return declared_language(html_lang, None);
+        }
+        detect_language(text).or_else(|| self.declared_language_fallback(html_lang, og_locale))
+    }
+
+    /// The page's declared language, if `declared_language_fallback` is enabled.
+    fn declared_language_fallback(
+        &self,
+        html_lang: Option<&str>,
+        og_locale: Option<&str>,
+    ) -> Option<String> {
+        if !self.config.declared_language_fallback {
+            return None;
+        }
+        declared_language(html_lang, og_locale)
+    }
+
+    /// Replaces heading text with its normalized key, keeping the original in `raw_text`.
+    fn normalize_headings(&self, headings: &mut [Heading]) {
+        let strip_punctuation = self.config.normalize_headings_strip_punctuation;
+        for heading in headings {
+            let normalized = normalize_key(&heading.text, strip_punctuation);
+            heading.raw_text = Some(std::mem::replace(&mut heading.text, normalized));
+        }
+    }
+
+    fn content_options<'a>(&'a self, profile: Option<&'a CompiledProfile>) -> ContentOptions<'a> {
+        ContentOptions {
+            max_depth: self.config.max_dom_depth,
+            sanitize_retry: self.config.readability_sanitize_retry,
+            content_selector: profile.and_then(|p| p.content_selector.as_ref()),
+            exclude_selector: self.content_exclude.as_ref(),
+        }
+    }
+
+    /// The domain profile for `url`'s host, trying parent domains in turn
+    /// (`www.blog.example.com`, `blog.example.com`, `example.com`).
+    fn profile_for(&self, url: &str) -> Option<&CompiledProfile> {
+        if self.domain_profiles.is_empty() {
+            return None;
+        }
+
+        let host = url::Url::parse(url).ok()?.host_str()?.to_ascii_lowercase();
+        let mut candidate = host.as_str();
+        loop {
+            if let Some(profile) = self.domain_profiles.get(candidate) {
+                return Some(profile);
+            }
+            candidate = candidate.split_once('.')?.1;
+        }
+    }
+
+    /// Drops links whose host doesn't match `outlink_host_filter`, returning
+    /// how many were dropped.
+    fn filter_outlinks(&self, links: &mut Vec<LinkData>) -> usize {
+        let Some(filter) = &self.outlink_filter else {
+            return 0;
+        };
+        let before = links.len();
+        links.retain(|link| filter.matches(&link.url));
+        let dropped = before - links.len();
+        self.metrics.add_links_filtered_out(dropped as u64);
+        dropped
+    }
+
+    /// Enforces the minimum content length on `text`.
+    ///
+    /// With `metadata_content_fallback`, text that is too short (an SPA shell
+    /// rendered client-side) is replaced by the `metadata` text when that is
+    /// longer; the `bool` reports the swap. Otherwise short text fails with
+    /// "Content too short".
+    fn content_or_metadata(
+        &self,
+        text: String,
+        min: usize,
+        metadata: impl FnOnce() -> Option<String>,
+    ) -> Result<(String, bool)> {
+        if text.len() >= min {
+            return Ok((text, false));
+        }
+        if self.config.metadata_content_fallback {
+            if let Some(metadata) = metadata().filter(|metadata| metadata.len() > text.len()) {
+                self.metrics.inc_content_from_metadata();
+                return Ok((metadata, true));
+            }
+        }
+        Err(anyhow::anyhow!(
+            "Content too short: {} characters",
+            text.len()
+        ))
+    }
+
+    /// Whether a page with `external` outbound links trips `max_outbound_links`.
+    fn is_link_farm(&self, external: usize) -> bool {
+        self.config.max_outbound_links > 0 && external > self.config.max_outbound_links
+    }
+
+    /// Whether a page with `score` trips `min_readability_score`. Pages too
+    /// short to score pass.
+    fn is_low_quality(&self, score: Option<f64>) -> bool {
+        self.config.min_readability_score > 0.0
+            && score.is_some_and(|score| score < self.config.min_readability_score)
+    }
+
+    /// Whether the effective title is shorter than `min_title_len` characters.
+    ///
+    /// A missing title is first replaced by `fallback` (`og:title`,
+    /// `twitter:title`, `<h1>`), so only pages without any usable title fail.
+    /// With the gate disabled the title is left untouched.
+    fn is_title_too_short(
+        &self,
+        title: &mut String,
+        fallback: impl FnOnce() -> Option<String>,
+    ) -> bool {
+        if self.config.min_title_len == 0 {
+            return false;
+        }
+        if title.is_empty() || title == PLACEHOLDER_TITLE {
+            match fallback() {
+                Some(fallback) => *title = fallback,
+                None => return true,
+            }
+        }
+        title.chars().count() < self.config.min_title_len
+    }
+
+    /// Whether readability's `content` is confidently in another language than
+    /// the declared `<html lang>` while the body text is in the declared one.
+    ///
+    /// Counts the mismatch (`content_language_mismatch`) whenever the content
+    /// disagrees with the declaration, whether or not the body is a better fit.
+    fn is_language_mismatch(&self, content: &str, body: &str, html_lang: Option<&str>) -> bool {
+        if !self.config.language_detection_enabled {
+            return false;
+        }
+        let Some(declared) = html_lang.map(normalize_lang_code) else {
+            return false;
+        };
+        let min_confidence = self.config.language_mismatch_min_confidence;
+        let Some(detected) = detect_language_confident(content, min_confidence) else {
+            return false;
+        };
+        if normalize_lang_code(&detected) == declared {
+            return false;
+        }
+
+        self.metrics.inc_content_language_mismatch();
+        detect_language(body).is_some_and(|body_lang| normalize_lang_code(&body_lang) == declared)
+    }
+
+    /// With `strict_extraction`, turns any extraction warning into a
+    /// dead-letter failure instead of a partial page.
+    fn check_strict(&self, warnings: &[String]) -> Result<()> {
+        if self.config.strict_extraction && !warnings.is_empty() {
+            return Err(DeadLetterError::StrictExtraction {
+                warnings: warnings.to_vec(),
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Whether `url`'s path matches one of `auth_url_patterns`.
+    fn is_auth_url(&self, url: &str) -> bool {
+        matches_url_pattern(url, &self.auth_url_patterns)
+    }
+
+    /// Length limits for `content_type`, falling back to the global limits.
+    fn length_gate(&self, content_type: &str) -> LengthGate {
+        self.length_gates
+            .get(content_type)
+            .copied()
+            .unwrap_or_else(|| self.config.default_length_gate())
+    }
+
+    /// Resolves the content type to report on the page.
+    ///
+    /// `content_type_override` wins; otherwise the reported type is used with any
+    /// parameters (e.g. `; charset=utf-8`) stripped, defaulting to `text/html`.
+    fn resolve_content_type(&self, reported: Option<&str>) -> String {
+        if let Some(forced) = &self.config.content_type_override {
+            return forced.clone();
+        }
+
+        reported
+            .and_then(|ct| ct.split(';').next())
+            .map(|ct| ct.trim().to_ascii_lowercase())
+            .filter(|ct| !ct.is_empty())
+            .unwrap_or_else(|| "text/html".to_string())
+    }
+}
+
+/// Non-fatal extraction problems, as short machine-readable codes:
+///
+/// - `invalid_canonical`: the canonical URL can't be resolved;
+/// - `unresolvable_base_url`: no base URL, so relative links and images are dropped;
+/// - `invalid_json_ld`: JSON-LD blocks that failed to parse were skipped.
+fn extraction_warnings(
+    url: &str,
+    canonical: Option<&str>,
+    base: Option<&url::Url>,
+    invalid_json_ld: usize,
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if canonical.is_some_and(|canonical| {
+        url::Url::parse(url)
+            .and_then(|page| page.join(canonical.trim()))
+            .is_err()
+            && url::Url::parse(canonical.trim()).is_err()
+    }) {
+        warnings.push("invalid_canonical".to_string());
+    }
+    if base.is_none() {
+        warnings.push("unresolvable_base_url".to_string());
+    }
+    if invalid_json_ld > 0 {
+        warnings.push("invalid_json_ld".to_string());
+    }
+    warnings
+}
+
+/// Characters of `content` over characters of `body`, capped at 1.0
+/// (0.0 for an empty body).
+fn content_ratio(content: &str, body: &str) -> f64 {
+    let body_chars = body.chars().count();
+    if body_chars == 0 {
+        return 0.0;
+    }
+    (content.chars().count() as f64 / body_chars as f64).min(1.0)
+}
+
+/// Out-degree summary of a page's links.
+struct LinkStats {
+    internal: usize,
+    external: usize,
+    /// Characters of link text over characters of `total_text`, capped at 1.0.
+    text_ratio: f64,
+}
+
+impl LinkStats {
+    fn compute(links: &[LinkData], total_text: &str) -> Self {
+        let external = links.iter().filter(|link| link.is_external).count();
+        let link_chars: usize = links.iter().map(|link| link.text.chars().count()).sum();
+        let total_chars = total_text.chars().count();
+
+        let text_ratio = if total_chars == 0 {
+            0.0
+        } else {
+            (link_chars as f64 / total_chars as f64).min(1.0)
+        };
+
+        Self {
+            internal: links.len() - external,
+            external,
+            text_ratio,
+        }
+    }
+}
+
+/// The longest non-empty candidate text.
+fn longest_text<const N: usize>(candidates: [Option<String>; N]) -> Option<String> {
+    candidates
+        .into_iter()
+        .flatten()
+        .filter(|text| !text.is_empty())
+        .max_by_key(String::len)
+}
+
+/// A duration in fractional milliseconds.
+fn duration_ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Whether readability's output is (nearly) the whole body text.
+///
+/// True when the content is at least `ratio` of the body text's length, which
+/// means content extraction removed little or nothing.
+fn is_readability_noop(content: &str, body_text: &str, ratio: f64) -> bool {
+    !body_text.is_empty() && content.len() as f64 >= body_text.len() as f64 * ratio
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Body text long enough to clear the default `min_content_length`.
+    const FILLER: &str = "This paragraph carries enough ordinary prose to make the page count \
+        as real content rather than a stub that the length gate would reject outright.";
+
+    fn parse_with(config: Config, html: &str) -> ParsedPage {
+        let parser = HtmlParser::new(&config, Arc::new(Metrics::new()));
+        parser
+            .parse_html(html, "https://example.com/post", None)
+            .unwrap()
+    }
+
+    fn parse(html: &str) -> ParsedPage {
+        parse_with(Config::default(), html)
+    }
+
+    #[test]
+    fn counts_internal_and_external_links() {
+        let page = parse(&format!(
+            r#"<html><head><title>Links</title></head><body>
+            <p>{FILLER}</p>
+            <a href="/about">About</a>
+            <a href="https://example.com/contact">Contact</a>
+            <a href="https://other.org/">Other</a>
+            </body></html>"#
+        ));
+
+        assert_eq!(page.internal_link_count, 2);
+        assert_eq!(page.external_link_count, 1);
+        // Link text over the whole body text, not just the main content.
+        let body_chars = format!("{FILLER} About Contact Other").chars().count();
+        let expected = "AboutContactOther".len() as f64 / body_chars as f64;
+        assert!((page.link_text_ratio - expected).abs() < 0.01);
+    }
+
+    #[test]
+    fn counts_words_in_cjk_title_and_headings() {
+        let page = parse(&format!(
+            "<html><head><title>東京タワー</title></head><body>\
+             <h1>東京タワー</h1><h2>展望台</h2><p>{FILLER}</p></body></html>"
+        ));
+
+        assert_eq!(page.title_word_count, 3);
+        assert_eq!(page.heading_word_count, 6);
+    }
+
+    #[test]
+    fn normalizes_og_locale_and_alternates() {
+        let page = parse(&format!(
+            r#"<html><head><title>Locale</title>
+            <meta property="og:locale" content="en_US">
+            <meta property="og:locale:alternate" content="fr_FR">
+            <meta property="og:locale:alternate" content="pt_br">
+            </head><body><p>{FILLER}</p></body></html>"#
+        ));
+
+        assert_eq!(page.locale.as_deref(), Some("en-US"));
+        assert_eq!(page.alternate_locales, ["fr-FR", "pt-BR"]);
+    }
+
+    #[test]
+    fn counts_images_with_and_without_alt() {
+        let page = parse(&format!(
+            r#"<html><head><title>Images</title></head><body><p>{FILLER}</p>
+            <img src="/a.png" alt="A chart">
+            <img src="/divider.png" alt="">
+            <img src="/b.png">
+            <img src="/c.png">
+            <img src="/a.png" alt="A chart">
+            </body></html>"#
+        ));
+
+        assert_eq!(page.images_total, 5);
+        assert_eq!(page.unique_image_count, 4);
+        // The empty alt marks a decorative image and counts as present.
+        assert_eq!(page.images_with_alt, 3);
+        assert_eq!(page.images_missing_alt, 2);
+    }
+
+    #[test]
+    fn counts_paragraphs_of_an_article_and_a_blurb() {
+        let paragraphs = (0..5)
+            .map(|_| format!("<p>{FILLER}</p>"))
+            .collect::<String>();
+        let article = parse(&format!(
+            "<html><head><title>Article</title></head><body>\
+             <article><h1>Article</h1>{paragraphs}<p> </p></article></body></html>"
+        ));
+        let blurb = parse(&format!(
+            "<html><head><title>Blurb</title></head><body><p>{FILLER}</p></body></html>"
+        ));
+
+        assert_eq!(article.paragraph_count, 5);
+        assert_eq!(blurb.paragraph_count, 1);
+    }
+
+    #[test]
+    fn content_ratio_separates_articles_from_nav_heavy_pages() {
+        let paragraphs = (0..6)
+            .map(|_| format!("<p>{FILLER}</p>"))
+            .collect::<String>();
+        let article = parse(&format!(
+            "<html><head><title>Article</title></head><body>\
+             <nav><a href=\"/\">Home</a></nav><article>{paragraphs}</article></body></html>"
+        ));
+
+        let nav_links = (0..80)
+            .map(|i| format!("<li><a href=\"/section/{i}\">Section number {i}</a></li>"))
+            .collect::<String>();
+        let nav_heavy = parse(&format!(
+            "<html><head><title>Portal</title></head><body>\
+             <nav><ul>{nav_links}</ul></nav><article><p>{FILLER}</p></article>\
+             <footer><ul>{nav_links}</ul></footer></body></html>"
+        ));
+
+        assert!(article.content_ratio > 0.9, "{}", article.content_ratio);
+        assert!(nav_heavy.content_ratio < 0.3, "{}", nav_heavy.content_ratio);
+    }
+
+    #[test]
+    fn content_ratio_is_one_on_the_streaming_path() {
+        let config = Config {
+            extraction_mode: "body".to_string(),
+            ..Config::default()
+        };
+        let page = parse_with(
+            config,
+            &format!("<html><head><title>Stream</title></head><body><p>{FILLER}</p></body></html>"),
+        );
+
+        assert_eq!(page.content_ratio, 1.0);
+    }
+
+    #[test]
+    fn language_detection_disabled_uses_only_html_lang() {
+        let page = |lang: &str, detect: bool| {
+            let config = Config {
+                language_detection_enabled: detect,
+                ..Config::default()
+            };
+            parse_with(
+                config,
+                &format!(
+                    "<html {lang}><head><title>Lang</title></head><body><p>{FILLER}</p></body></html>"
+                ),
+            )
+        };
+
+        // The English text is only recognized when detection runs.
+        assert_eq!(page("", true).language.as_deref(), Some("english"));
+        assert_eq!(page("", false).language.as_deref(), Some("simple"));
+        assert_eq!(
+            page(r#"lang="en-GB""#, false).language.as_deref(),
+            Some("english")
+        );
+        assert_eq!(
+            page(r#"lang="fr""#, false).language.as_deref(),
+            Some("simple")
+        );
+    }
 }
@@ -6,6 +6,33 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A `<link rel="alternate" hreflang>` pointing to a translation of the page.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HreflangLink {
+    /// The `hreflang` value as declared (e.g. `fr-CA`, `x-default`).
+    pub lang: String,
+
+    /// The resolved URL of the translated page.
+    pub url: String,
+}
+
+/// A non-hreflang `<link rel="alternate">`, such as a PDF, print or feed version.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AlternateLink {
+    /// The resolved URL of the alternate version.
+    pub url: String,
+
+    /// The `type` attribute (e.g. `application/pdf`), if present.
+    pub media_type: Option<String>,
+
+    /// The `media` attribute (e.g. `print`), if present.
+    pub media: Option<String>,
+
+    /// The `title` attribute, if present.
+    pub title: Option<String>,
+}
 
 /// Represents an image (`<img>`) found on the page.
 #[derive(Debug, Serialize, Deserialize)]
@@ -18,6 +45,136 @@ pub struct ImageData {
 
     /// The `title` attribute of the image, if present.
     pub title: Option<String>,
+
+    /// The `width` attribute in pixels, if present and numeric.
+    pub width: Option<u32>,
+
+    /// The `height` attribute in pixels, if present and numeric.
+    pub height: Option<u32>,
+
+    /// Responsive candidates from the enclosing `<picture>`'s `<source>` elements.
+    pub sources: Vec<ImageSource>,
+}
+
+/// A responsive image candidate from a `<picture>` `<source srcset>`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImageSource {
+    /// The resolved candidate URL.
+    pub url: String,
+
+    /// The `srcset` descriptor (e.g. `2x`, `800w`), if present.
+    pub descriptor: Option<String>,
+
+    /// The `media` query of the `<source>`, if present.
+    pub media: Option<String>,
+
+    /// The `type` of the `<source>` (e.g. `image/webp`), if present.
+    pub media_type: Option<String>,
+}
+
+/// A standalone image record, produced to the images topic for each image on a page.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImageRecord<'a> {
+    /// URL of the page the image was found on.
+    pub page_url: &'a str,
+
+    /// The resolved image URL.
+    pub src: &'a str,
+
+    /// The `alt` attribute of the image, if present.
+    pub alt: Option<&'a str>,
+
+    /// The `title` attribute of the image, if present.
+    pub title: Option<&'a str>,
+
+    /// The `width` attribute in pixels, if present and numeric.
+    pub width: Option<u32>,
+
+    /// The `height` attribute in pixels, if present and numeric.
+    pub height: Option<u32>,
+}
+
+/// A compact preview of a page, produced to the summary topic.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SummaryRecord<'a> {
+    /// URL of the page.
+    pub url: &'a str,
+
+    /// The page title.
+    pub title: &'a str,
+
+    /// The meta description, or the content `preview` when there is none.
+    pub snippet: &'a str,
+
+    /// The PostgreSQL text search configuration of the page language.
+    pub language: Option<&'a str>,
+
+    /// The first image on the page, if any.
+    pub primary_image: Option<&'a str>,
+
+    /// Fingerprint of `cleaned_text`, matching the full page's.
+    pub content_hash: &'a str,
+}
+
+/// A URL listed in an XML sitemap.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SitemapEntry {
+    /// The resolved `<loc>` URL.
+    pub loc: String,
+
+    /// The `<lastmod>` value as written in the sitemap, if present.
+    pub lastmod: Option<String>,
+
+    /// The `<priority>` value, if present and within `[0, 1]`.
+    pub priority: Option<f32>,
+
+    /// Whether the entry is a child sitemap of a `<sitemapindex>`.
+    pub is_sitemap: bool,
+}
+
+/// A URL discovered in a sitemap, produced to the discovered-URLs topic.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiscoveredUrlRecord<'a> {
+    /// The discovered URL.
+    pub url: &'a str,
+
+    /// URL of the sitemap it was listed in.
+    pub sitemap_url: &'a str,
+
+    /// The `<lastmod>` value, if present.
+    pub lastmod: Option<&'a str>,
+
+    /// The `<priority>` value, if present.
+    pub priority: Option<f32>,
+
+    /// Whether the URL is itself a sitemap (from a `<sitemapindex>`).
+    pub is_sitemap: bool,
+}
+
+/// Links or images beyond the inline limit, produced to an overflow topic.
+#[derive(Debug, Serialize)]
+pub struct OverflowRecord<'a, T> {
+    /// URL of the page the items belong to.
+    pub url: &'a str,
+
+    /// Position of the first item in the page's full list (the inline limit).
+    pub offset: usize,
+
+    /// The items that did not fit inline.
+    pub items: &'a [T],
+}
+
+/// A redirect reported by the crawler, produced to the redirects topic.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RedirectRecord<'a> {
+    /// URL that was fetched.
+    pub url: &'a str,
+
+    /// The `Location` target, resolved against `url`.
+    pub target: &'a str,
+
+    /// The HTTP status of the fetch (e.g. 301).
+    pub status: u16,
 }
 
 /// Represents a hyperlink (`<a>`) found on the page.
@@ -39,47 +196,517 @@ pub struct Heading {
     /// Heading level (e.g., 1 for `<h1>`)
     pub level: u8,
 
-    /// The text content of the heading.
+    /// The text content of the heading (normalized when `normalize_headings` is set).
     pub text: String,
+
+    /// The original heading text, present only when `text` was normalized.
+    pub raw_text: Option<String>,
 }
 
-/// Represents a fully-parsed HTML page and its extracted data.
+/// Represents a code block (`<pre>` or standalone `<code>`) found on the page.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CodeBlock {
+    /// The code, verbatim (whitespace is preserved).
+    pub code: String,
+
+    /// Language hinted by a `language-*`/`lang-*` class, if present.
+    pub language: Option<String>,
+}
+
+/// A range of `cleaned_text` whose element declares a language other than the page's.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LanguageSpan {
+    /// Start offset in `cleaned_text`, in characters.
+    pub start: usize,
+
+    /// End offset (exclusive) in `cleaned_text`, in characters.
+    pub end: usize,
+
+    /// The element's `lang` attribute, as declared.
+    pub lang: String,
+}
+
+/// A question and its answer, from schema.org `FAQPage` JSON-LD or a
+/// `<details>`/`<summary>` pair.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Faq {
+    /// The question text.
+    pub question: String,
+
+    /// The answer as cleaned plain text (markup removed).
+    pub answer: String,
+}
+
+/// A `<form>` on the page: where and how it submits, and which fields it has.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FormInfo {
+    /// The resolved `action`; `None` when missing or empty (the form submits
+    /// to the page itself) or not an http(s) URL.
+    pub action: Option<String>,
+
+    /// The submission method, uppercased (`GET` when missing).
+    pub method: String,
+
+    /// The form's `<input>`, `<select>` and `<textarea>` fields, in order.
+    pub fields: Vec<FormField>,
+}
+
+/// A form field's name and type; values are never captured.
 #[derive(Debug, Serialize, Deserialize)]
+pub struct FormField {
+    /// The `name` attribute, if any.
+    pub name: Option<String>,
+
+    /// The input `type` (lowercased, `text` when missing), or `select` / `textarea`.
+    pub field_type: String,
+}
+
+/// Contact and location details of a business, from schema.org `LocalBusiness`
+/// or `Organization` JSON-LD.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BusinessInfo {
+    /// The business `name`, if present.
+    pub name: Option<String>,
+
+    /// The `telephone`, reduced to digits with an optional leading `+`.
+    pub telephone: Option<String>,
+
+    /// The `address`, whether given as a string or a `PostalAddress`.
+    pub address: Option<PostalAddress>,
+
+    /// The `geo` coordinates, if both latitude and longitude are present.
+    pub geo: Option<GeoCoordinates>,
+}
+
+/// A postal address. Address strings only fill `formatted`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PostalAddress {
+    pub street_address: Option<String>,
+    pub locality: Option<String>,
+    pub region: Option<String>,
+    pub postal_code: Option<String>,
+    pub country: Option<String>,
+
+    /// The whole address on one line: the original string, or the structured
+    /// parts joined with `, `.
+    pub formatted: String,
+}
+
+/// A latitude/longitude pair.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GeoCoordinates {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// A schema.org `AggregateRating` (star rating) of the page's subject.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AggregateRating {
+    /// The `ratingValue`.
+    pub rating_value: f32,
+
+    /// The `reviewCount` (or `ratingCount` when no review count is given).
+    pub review_count: Option<u32>,
+
+    /// The `bestRating` of the scale, if declared (schema.org assumes 5).
+    pub best_rating: Option<f32>,
+}
+
+/// A product price from a schema.org `Offer`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PriceInfo {
+    /// The `price` (or an `AggregateOffer`'s `lowPrice`).
+    pub amount: f64,
+
+    /// ISO 4217 code from `priceCurrency`, or from a currency symbol.
+    pub currency: String,
+
+    /// The `availability` without its schema.org prefix (e.g. `InStock`).
+    pub availability: Option<String>,
+}
+
+/// Video details from a schema.org `VideoObject`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct VideoInfo {
+    /// The video `name`, if present.
+    pub name: Option<String>,
+
+    /// The resolved `thumbnailUrl` (the first one when several are given).
+    pub thumbnail_url: Option<String>,
+
+    /// The ISO 8601 `duration` in seconds; `None` when missing or malformed.
+    pub duration_secs: Option<u64>,
+
+    /// `uploadDate` in UTC, read like `EventInfo::start_date`.
+    #[serde(with = "super::timestamp::option", default)]
+    pub upload_date: Option<DateTime<Utc>>,
+}
+
+/// Event details from a schema.org `Event` (or subtype).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventInfo {
+    /// The event `name`.
+    pub name: String,
+
+    /// `startDate` in UTC; date-only and offset-less values are read as UTC.
+    #[serde(with = "super::timestamp::option", default)]
+    pub start_date: Option<DateTime<Utc>>,
+
+    /// `endDate`, read like `start_date`.
+    #[serde(with = "super::timestamp::option", default)]
+    pub end_date: Option<DateTime<Utc>>,
+
+    /// The venue: a `Place` name or address, a plain string, or an online URL.
+    pub location: Option<String>,
+
+    /// The first offer's `price`.
+    pub price: Option<f64>,
+
+    /// The first offer's `priceCurrency` (e.g. `USD`).
+    pub price_currency: Option<String>,
+}
+
+/// The page's Open Graph (`og:*`) properties.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct OpenGraphData {
+    /// `og:title`.
+    pub title: Option<String>,
+
+    /// `og:description`.
+    pub description: Option<String>,
+
+    /// `og:image`, resolved against the page URL.
+    pub image: Option<String>,
+
+    /// `og:type` (e.g. `article`, `website`).
+    #[serde(rename = "type")]
+    pub og_type: Option<String>,
+
+    /// `og:url`.
+    pub url: Option<String>,
+
+    /// Every other `og:*` property by its full name (`og:site_name`).
+    pub additional: HashMap<String, String>,
+}
+
+/// The page's Twitter Card (`twitter:*`) properties.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TwitterCardData {
+    /// `twitter:card` (e.g. `summary_large_image`).
+    pub card: Option<String>,
+
+    /// `twitter:title`, else `og:title`.
+    pub title: Option<String>,
+
+    /// `twitter:description`, else `og:description`.
+    pub description: Option<String>,
+
+    /// `twitter:image`, else `og:image`; resolved against the page URL.
+    pub image: Option<String>,
+
+    /// `twitter:creator` (the author's handle).
+    pub creator: Option<String>,
+
+    /// Every other `twitter:*` property by its full name (`twitter:site`).
+    pub additional: HashMap<String, String>,
+}
+
+/// Coarse kind of page, from `classify_page_type`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PageType {
+    /// A single long piece of text (news, blog post, documentation).
+    Article,
+    /// A product detail page.
+    Product,
+    /// A list of items linking elsewhere (category, search results, index).
+    Listing,
+    /// A discussion thread.
+    Forum,
+    /// None of the above, or not classified (streaming extraction).
+    #[default]
+    Other,
+}
+
+/// Per-page processing costs, emitted with `emit_processing_meta`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ProcessingMeta {
+    /// Wall time of parsing the HTML into a `ParsedPage`, in milliseconds.
+    pub parse_duration_ms: f64,
+
+    /// Part of `parse_duration_ms` spent in readability; `None` when it did
+    /// not run (streaming modes, content selectors, deep DOMs).
+    pub readability_duration_ms: Option<f64>,
+
+    /// Size of the decoded HTML in bytes.
+    pub input_bytes: usize,
+}
+
+/// Represents a fully-parsed HTML page and its extracted data.
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct ParsedPage {
     /// The URL of the page.
     pub url: String,
 
+    /// Stable id of the logical document: hex SHA-256 of the normalized
+    /// canonical URL (or page URL); see `doc_id_use_canonical`.
+    pub doc_id: String,
+
+    /// Number of non-empty path segments in `url` (`/a/b/` and `/a/b` are both 2).
+    pub url_depth: usize,
+
+    /// Whether `url` has an empty or `/` path, ignoring the query string.
+    pub is_homepage: bool,
+
     /// The page's `<title>`.
     pub title: String,
 
     /// The page's meta description, if present.
     pub description: Option<String>,
 
+    /// First substantial paragraph of the main content (the lede), if any.
+    pub lead: Option<String>,
+
+    /// Non-empty `<p>` elements in the main content; 0 on the streaming paths.
+    pub paragraph_count: usize,
+
+    /// Whether this title was seen more than `duplicate_threshold` times in this run.
+    pub title_is_duplicate: bool,
+
+    /// Whether this description was seen more than `duplicate_threshold` times in this run.
+    pub description_is_duplicate: bool,
+
     /// Cleaned and normalized text content.
     pub cleaned_text: String,
 
+    /// Hex SHA-256 of `cleaned_text`; also sent as a Kafka header (`content_hash_header`).
+    pub content_hash: String,
+
+    /// Snippet of `cleaned_text`: at most `preview_length` characters, cut at a
+    /// word boundary and ending in `…` when truncated.
+    pub preview: String,
+
+    /// Cleaned text of the whole `<body>`, when `include_body_text` is set.
+    pub body_text: Option<String>,
+
     /// A list of headings (`<h1>`, `<h2>`, etc.) found on the page.
     pub headings: Vec<Heading>,
 
-    /// All hyperlinks (`<a>`) found on the page.
+    /// `url#id` deep links to elements with an `id` in the main content,
+    /// capped by `max_anchors`.
+    pub anchors: Vec<String>,
+
+    /// All hyperlinks (`<a>`) found on the page, or the first
+    /// `inline_links_limit` of them (see `has_overflow`).
     pub links: Vec<LinkData>,
 
-    /// All images (`<img>`) found on the page.
+    /// Number of links pointing to the page's own domain.
+    pub internal_link_count: usize,
+
+    /// Number of links pointing to other domains.
+    pub external_link_count: usize,
+
+    /// Links dropped from `links` by `outlink_host_filter`. Link counts and
+    /// `outbound_domains` still cover every link on the page.
+    pub links_filtered_out: usize,
+
+    /// Registrable domains of the external links (`bbc.co.uk`, `example.com`),
+    /// deduplicated; at most `max_outbound_domains`.
+    pub outbound_domains: Vec<String>,
+
+    /// Links to the site's social profiles by platform (`github` → URL), per
+    /// `social_platforms`; the first link per platform is kept.
+    pub social_links: HashMap<String, String>,
+
+    /// Whether `external_link_count` exceeds `max_outbound_links` (a link-farm signal).
+    pub link_farm_suspected: bool,
+
+    /// Flesch reading ease of `cleaned_text`; `None` when the page isn't
+    /// English or the text is too short to score.
+    pub readability_score: Option<f64>,
+
+    /// Whether `readability_score` is below `min_readability_score` or the
+    /// title is `title_too_short`.
+    pub low_quality: bool,
+
+    /// Whether the title (after the `og:title`/`twitter:title`/`<h1>`
+    /// fallback) is shorter than `min_title_len`.
+    pub title_too_short: bool,
+
+    /// The page's `theme-color` (lowercased hex or CSS named color). Invalid
+    /// values are kept in `additional_metadata.theme_color` instead.
+    pub theme_color: Option<String>,
+
+    /// Whether `<meta name="viewport">` sets `width=device-width`. The raw
+    /// viewport is kept in `additional_metadata.viewport`.
+    pub mobile_friendly: bool,
+
+    /// The page's self-declared `<meta name="rating">` (e.g. `adult` or the
+    /// RTA label).
+    pub content_rating: Option<String>,
+
+    /// Whether `content_rating` marks the page as adult content, for SafeSearch.
+    pub adult: bool,
+
+    /// The resolved favicon URL declared with `<link rel="icon">`, if any.
+    pub favicon_url: Option<String>,
+
+    /// Share of the page's body text that sits inside links (0.0–1.0).
+    pub link_text_ratio: f64,
+
+    /// Length of `cleaned_text` over the length of the whole body text
+    /// (0.0–1.0); low values suggest thin content or failed extraction. On the
+    /// streaming paths the content is the body, so this is 1.0 whenever there
+    /// is body text.
+    pub content_ratio: f64,
+
+    /// Whether the body was too short and `cleaned_text` is the page's
+    /// description instead (`metadata_content_fallback`).
+    pub content_from_metadata: bool,
+
+    /// Translations of the page declared with `hreflang`.
+    pub hreflang_alternates: Vec<HreflangLink>,
+
+    /// Other alternate formats (PDF, print, feeds) declared with `rel="alternate"`.
+    pub alternates: Vec<AlternateLink>,
+
+    /// All images (`<img>`) found on the page, or the first
+    /// `inline_images_limit` of them (see `has_overflow`).
     pub images: Vec<ImageData>,
 
+    /// Number of images on the page, before `dedup_images`.
+    pub images_total: usize,
+
+    /// Number of distinct resolved image `src` values on the page.
+    pub unique_image_count: usize,
+
+    /// Images with an `alt` attribute (an empty `alt` marks a decorative image
+    /// and counts as present), before `dedup_images`.
+    pub images_with_alt: usize,
+
+    /// Images without an `alt` attribute.
+    pub images_missing_alt: usize,
+
+    /// Code blocks found on the page, capped by `max_code_blocks`.
+    pub code_blocks: Vec<CodeBlock>,
+
     /// The canonical URL of the page, if specified.
     pub canonical_url: Option<String>,
 
+    /// Whether `canonical_url` points to a different URL than the page (duplicate
+    /// or syndicated content). `false` for self-referential or missing canonicals.
+    pub canonical_mismatch: bool,
+
+    /// The page's `og:url`, if specified.
+    pub og_url: Option<String>,
+
+    /// Whether `canonical_url` and `og_url` are both set and, once resolved and
+    /// normalized, disagree.
+    pub og_canonical_mismatch: bool,
+
+    /// All Open Graph properties; `None` when the page declares none.
+    pub og_tags: Option<OpenGraphData>,
+
+    /// Twitter Card properties, with Open Graph fallbacks; `None` when the
+    /// page declares no `twitter:*` properties.
+    pub twitter_cards: Option<TwitterCardData>,
+
     /// Detected language of the page, if determined.
     pub language: Option<String>,
 
+    /// The `max_token_counts` most frequent lowercased words of `cleaned_text`
+    /// (stopwords removed for English), when `include_token_counts` is set.
+    pub token_counts: Option<HashMap<String, u32>>,
+
+    /// The `og:locale` as a BCP 47 tag (`en_US` becomes `en-US`).
+    pub locale: Option<String>,
+
+    /// `og:locale:alternate` values, normalized like `locale`.
+    pub alternate_locales: Vec<String>,
+
+    /// Paragraphs of `cleaned_text` declared in a language other than the page's.
+    pub language_spans: Vec<LanguageSpan>,
+
     /// Word count of the `cleaned_text`.
     pub word_count: usize,
 
+    /// Character count of the `cleaned_text`.
+    pub char_count: usize,
+
+    /// Word count of the whole `<body>` text, set with `include_body_text`.
+    /// Far above `word_count` means readability trimmed a lot; a tiny
+    /// `word_count` next to it points at a failed extraction.
+    pub body_word_count: Option<usize>,
+
+    /// Character count of the whole `<body>` text, set with `include_body_text`.
+    pub body_char_count: Option<usize>,
+
+    /// Words in the title, counted on Unicode word boundaries (CJK-aware).
+    pub title_word_count: usize,
+
+    /// Words across all headings, counted like `title_word_count`.
+    pub heading_word_count: usize,
+
     /// The page's meta keywords, if present.
     pub meta_keywords: Option<String>,
 
-    /// Timestamp when this page was parsed.
+    /// The lowercased `<meta name="robots">` directives, if present.
+    pub robots_meta: Option<String>,
+
+    /// Whether `robots_meta` contains `noindex` (or `none`).
+    pub noindex: bool,
+
+    /// Best-guess category from schema.org `articleSection`, `article:section`
+    /// or `<meta name="category">`, in that order of precedence.
+    pub category: Option<String>,
+
+    /// Breadcrumb labels from schema.org `BreadcrumbList`, microdata or a
+    /// breadcrumb `<nav>`/list, root first.
+    pub breadcrumbs: Vec<String>,
+
+    /// `breadcrumbs` joined with `category_path_separator`, without the leading
+    /// `Home` crumb (e.g. `Electronics > Phones`).
+    pub category_path: Option<String>,
+
+    /// Business contact details from schema.org `LocalBusiness`/`Organization`.
+    pub business: Option<BusinessInfo>,
+
+    /// Aggregate star rating from schema.org `AggregateRating`.
+    pub rating: Option<AggregateRating>,
+
+    /// Price of the first schema.org `Offer` with an amount and a currency.
+    pub price: Option<PriceInfo>,
+
+    /// Heuristic page type (article, product, listing, forum or other),
+    /// DOM extraction only.
+    pub page_type: PageType,
+
+    /// Whether this looks like a login, registration or password-reset page:
+    /// the URL path matches `auth_url_patterns`, or a short page carries a
+    /// password form.
+    pub auth_page: bool,
+
+    /// Every `<form>` on the page, when `extract_forms` is enabled.
+    pub forms: Vec<FormInfo>,
+
+    /// Video details from schema.org `VideoObject`, when `video_extraction_enabled`.
+    pub video: Option<VideoInfo>,
+
+    /// The first schema.org `Event` on the page.
+    pub event: Option<EventInfo>,
+
+    /// Every `Event` on the page, when `extract_all_events` is set.
+    pub events: Vec<EventInfo>,
+
+    /// Question/answer pairs from schema.org `FAQPage`, or from `<details>`
+    /// elements when `faq_dom_fallback` is enabled.
+    pub faqs: Vec<Faq>,
+
+    /// `(term, definition)` pairs from `<dl>` definition lists.
+    pub definitions: Vec<(String, String)>,
+
+    /// Timestamp when this page was parsed, written per `timestamp_format`.
+    #[serde(with = "super::timestamp")]
     pub timestamp: DateTime<Utc>,
 
     /// Content type of the page.
@@ -87,4 +714,22 @@ pub struct ParsedPage {
 
     /// Character encoding of the page.
     pub encoding: String,
+
+    /// Free-form extra metadata (e.g. configured `data-*` attributes).
+    pub additional_metadata: HashMap<String, String>,
+
+    /// Non-fatal extraction problems (`invalid_canonical`,
+    /// `unresolvable_base_url`, `invalid_json_ld`); with `strict_extraction`
+    /// any of them fails the page instead.
+    pub warnings: Vec<String>,
+
+    /// Whether the DOM nested deeper than `max_dom_depth`, truncating the text.
+    pub dom_depth_exceeded: bool,
+
+    /// Whether links or images beyond the inline limits were moved to the
+    /// overflow topics.
+    pub has_overflow: bool,
+
+    /// Parse and readability timings, when `emit_processing_meta` is set.
+    pub processing_meta: Option<ProcessingMeta>,
 }
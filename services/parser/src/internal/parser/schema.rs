@@ -0,0 +1,661 @@
+//! Schema.org JSON-LD extraction.
+//!
+//! Parses `<script type="application/ld+json">` blocks into individual
+//! schema.org nodes and provides helpers to query them by `@type`.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use once_cell::sync::Lazy;
+use scraper::{Html, Selector};
+use serde_json::Value;
+use url::Url;
+
+use super::models::{
+    AggregateRating, BusinessInfo, EventInfo, Faq, GeoCoordinates, PostalAddress, PriceInfo,
+    VideoInfo,
+};
+use super::text_utils::clean_text;
+use crate::internal::monitor::Metrics;
+
+/// `LocalBusiness` and its common subtypes.
+const LOCAL_BUSINESS_TYPES: &[&str] = &[
+    "LocalBusiness",
+    "Restaurant",
+    "FoodEstablishment",
+    "Store",
+    "Hotel",
+    "LodgingBusiness",
+    "MedicalBusiness",
+    "Dentist",
+    "ProfessionalService",
+    "LegalService",
+    "AutomotiveBusiness",
+    "HealthAndBeautyBusiness",
+    "FinancialService",
+    "RealEstateAgent",
+];
+
+/// Selector for JSON-LD script blocks
+static JSON_LD_SELECTOR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse("script[type='application/ld+json']").unwrap());
+
+/// Extracts all schema.org nodes from the page's JSON-LD blocks.
+///
+/// Top-level arrays and `@graph` containers are flattened so every returned
+/// value is a single node. Blocks that fail to parse are skipped and counted
+/// in `schema_errors`; their number is returned alongside the nodes.
+pub fn extract_json_ld(document: &Html, metrics: &Metrics) -> (Vec<Value>, usize) {
+    let mut nodes = Vec::new();
+    let mut invalid = 0;
+
+    for script in document.select(&JSON_LD_SELECTOR) {
+        let raw = script.text().collect::<String>();
+        if raw.trim().is_empty() {
+            continue;
+        }
+
+        match serde_json::from_str::<Value>(raw.trim()) {
+            Ok(value) => flatten_into(value, &mut nodes),
+            Err(_) => {
+                metrics.inc_schema_errors();
+                invalid += 1;
+            }
+        }
+    }
+
+    (nodes, invalid)
+}
+
+/// Pushes `value`'s nodes into `nodes`, unwrapping arrays and `@graph`.
+fn flatten_into(value: Value, nodes: &mut Vec<Value>) {
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                flatten_into(item, nodes);
+            }
+        }
+        Value::Object(mut map) => {
+            if let Some(graph) = map.remove("@graph") {
+                flatten_into(graph, nodes);
+            }
+            if !map.is_empty() {
+                nodes.push(Value::Object(map));
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Returns whether `node`'s `@type` (a string or an array) includes any of `types`.
+pub fn has_type(node: &Value, types: &[&str]) -> bool {
+    match node.get("@type") {
+        Some(Value::String(t)) => types.contains(&t.as_str()),
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .any(|t| types.contains(&t)),
+        _ => false,
+    }
+}
+
+/// Reads a property as text, taking the first entry when it is an array.
+pub fn text_property(node: &Value, key: &str) -> Option<String> {
+    let value = match node.get(key)? {
+        Value::Array(items) => items.first()?,
+        other => other,
+    };
+
+    let text = match value {
+        Value::String(s) => s.trim().to_string(),
+        Value::Number(n) => n.to_string(),
+        _ => return None,
+    };
+
+    (!text.is_empty()).then_some(text)
+}
+
+/// Extracts business contact details from the page's JSON-LD nodes.
+///
+/// A `LocalBusiness` (or subtype) node is preferred over an `Organization`.
+/// Returns `None` if the chosen node has no telephone, address or coordinates.
+pub fn extract_business(json_ld: &[Value]) -> Option<BusinessInfo> {
+    let node = json_ld
+        .iter()
+        .find(|node| has_type(node, LOCAL_BUSINESS_TYPES))
+        .or_else(|| {
+            json_ld
+                .iter()
+                .find(|node| has_type(node, &["Organization"]))
+        })?;
+
+    let business = BusinessInfo {
+        name: text_property(node, "name").map(|name| clean_text(&name)),
+        telephone: text_property(node, "telephone").and_then(|phone| normalize_phone(&phone)),
+        address: node.get("address").and_then(postal_address),
+        geo: node.get("geo").and_then(geo_coordinates),
+    };
+
+    (business.telephone.is_some() || business.address.is_some() || business.geo.is_some())
+        .then_some(business)
+}
+
+/// Extracts question/answer pairs from `FAQPage` nodes.
+///
+/// Questions are read from `mainEntity` (a single `Question` or an array); the
+/// answer is the first `acceptedAnswer`'s `text`, with any markup removed.
+/// Questions without a name or answer are skipped.
+pub fn extract_faqs(json_ld: &[Value]) -> Vec<Faq> {
+    json_ld
+        .iter()
+        .filter(|node| has_type(node, &["FAQPage"]))
+        .filter_map(|node| node.get("mainEntity"))
+        .flat_map(|entity| match entity {
+            Value::Array(items) => items.iter().collect(),
+            other => vec![other],
+        })
+        .filter(|question| has_type(question, &["Question"]))
+        .filter_map(|question| {
+            let name = clean_text(&text_property(question, "name")?);
+            let answer = match question.get("acceptedAnswer")? {
+                Value::Array(items) => items.first()?,
+                other => other,
+            };
+            let answer = strip_markup(&text_property(answer, "text")?);
+
+            (!name.is_empty() && !answer.is_empty()).then_some(Faq {
+                question: name,
+                answer,
+            })
+        })
+        .collect()
+}
+
+/// Extracts the crumb labels of the first `BreadcrumbList`, ordered by `position`.
+///
+/// A crumb's name is read from the `ListItem` itself or from its `item`.
+/// Crumbs without a name are skipped.
+pub fn extract_breadcrumbs(json_ld: &[Value]) -> Vec<String> {
+    let Some(Value::Array(items)) = json_ld
+        .iter()
+        .find(|node| has_type(node, &["BreadcrumbList"]))
+        .and_then(|node| node.get("itemListElement"))
+    else {
+        return Vec::new();
+    };
+
+    let mut crumbs: Vec<(f64, String)> = items
+        .iter()
+        .enumerate()
+        .filter_map(|(index, item)| {
+            let name = text_property(item, "name")
+                .or_else(|| text_property(item.get("item")?, "name"))
+                .map(|name| clean_text(&name))
+                .filter(|name| !name.is_empty())?;
+            let position = text_property(item, "position")
+                .and_then(|p| p.parse::<f64>().ok())
+                .unwrap_or(index as f64);
+            Some((position, name))
+        })
+        .collect();
+    crumbs.sort_by(|a, b| a.0.total_cmp(&b.0));
+    crumbs.into_iter().map(|(_, name)| name).collect()
+}
+
+/// Turns an HTML snippet (answers often carry `<p>`/`<a>` markup) into cleaned text.
+fn strip_markup(html: &str) -> String {
+    let fragment = Html::parse_fragment(html);
+    clean_text(&fragment.root_element().text().collect::<Vec<_>>().join(" "))
+}
+
+/// Extracts the first `AggregateRating`: a node's `aggregateRating` property
+/// (e.g. on a `Product`) or a standalone `AggregateRating` node.
+///
+/// Values may be numbers or numeric strings (`"4,5"` is read as `4.5`).
+/// Returns `None` without a usable `ratingValue`.
+pub fn extract_rating(json_ld: &[Value]) -> Option<AggregateRating> {
+    json_ld
+        .iter()
+        .filter_map(|node| {
+            node.get("aggregateRating")
+                .or_else(|| has_type(node, &["AggregateRating"]).then_some(node))
+        })
+        .find_map(|rating| {
+            let number = |key| {
+                text_property(rating, key)?
+                    .replace(',', ".")
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|n| n.is_finite() && *n >= 0.0)
+            };
+            let count = |key| number(key).map(|n| n.round() as u32);
+
+            Some(AggregateRating {
+                rating_value: number("ratingValue")?,
+                review_count: count("reviewCount").or_else(|| count("ratingCount")),
+                best_rating: number("bestRating"),
+            })
+        })
+}
+
+/// Currency symbols read as ISO 4217 codes when no code is given.
+const CURRENCY_SYMBOLS: &[(&str, &str)] = &[
+    ("US$", "USD"),
+    ("C$", "CAD"),
+    ("A$", "AUD"),
+    ("$", "USD"),
+    ("€", "EUR"),
+    ("£", "GBP"),
+    ("¥", "JPY"),
+    ("₹", "INR"),
+    ("₩", "KRW"),
+    ("₽", "RUB"),
+];
+
+/// Extracts the first offer's price: an `offers` property (e.g. on a
+/// `Product`; the first entry of an array) or a standalone `Offer` /
+/// `AggregateOffer` node.
+///
+/// `price` may be a number or a string with a currency symbol and thousands
+/// separators (`"$1,299.00"`, `"1.299,00 €"`); an `AggregateOffer` without a
+/// `price` uses `lowPrice`. The currency is `priceCurrency` when it is a
+/// three-letter code, else the symbol in `priceCurrency` or the price text.
+/// Returns `None` without a usable amount and currency.
+pub fn extract_price(json_ld: &[Value]) -> Option<PriceInfo> {
+    json_ld
+        .iter()
+        .filter_map(|node| match node.get("offers") {
+            Some(Value::Array(items)) => items.first(),
+            Some(offers) => Some(offers),
+            None => has_type(node, &["Offer", "AggregateOffer"]).then_some(node),
+        })
+        .find_map(|offer| {
+            let raw = text_property(offer, "price").or_else(|| text_property(offer, "lowPrice"))?;
+            let amount = parse_price_amount(&raw)?;
+
+            let declared = text_property(offer, "priceCurrency");
+            let currency = declared
+                .as_deref()
+                .map(str::trim)
+                .filter(|code| code.len() == 3 && code.chars().all(|c| c.is_ascii_alphabetic()))
+                .map(str::to_ascii_uppercase)
+                .or_else(|| declared.as_deref().and_then(currency_from_symbol))
+                .or_else(|| currency_from_symbol(&raw))?;
+
+            Some(PriceInfo {
+                amount,
+                currency,
+                availability: text_property(offer, "availability").map(|availability| {
+                    availability
+                        .rsplit('/')
+                        .next()
+                        .unwrap_or(&availability)
+                        .to_string()
+                }),
+            })
+        })
+}
+
+/// The ISO 4217 code of the first currency symbol in `text`.
+fn currency_from_symbol(text: &str) -> Option<String> {
+    CURRENCY_SYMBOLS
+        .iter()
+        .find(|(symbol, _)| text.contains(symbol))
+        .map(|(_, code)| code.to_string())
+}
+
+/// Parses a price such as `1299`, `"1,299.99"`, `"1.299,99"` or `"12,50 €"`.
+///
+/// Everything but digits and `.`/`,` is ignored. When both separators occur
+/// the last one is the decimal point; a lone `,` is a thousands separator
+/// only when it repeats or exactly three digits follow it. Negative and
+/// non-finite amounts are rejected.
+fn parse_price_amount(raw: &str) -> Option<f64> {
+    let number: String = raw
+        .chars()
+        .filter(|c| c.is_ascii_digit() || matches!(c, '.' | ','))
+        .collect();
+
+    let normalized = match (number.rfind('.'), number.rfind(',')) {
+        (Some(dot), Some(comma)) if comma > dot => number.replace('.', "").replace(',', "."),
+        (Some(_), Some(_)) => number.replace(',', ""),
+        (None, Some(comma)) => {
+            let thousands = number.matches(',').count() > 1 || number.len() - comma - 1 == 3;
+            if thousands {
+                number.replace(',', "")
+            } else {
+                number.replace(',', ".")
+            }
+        }
+        (Some(_), None) if number.matches('.').count() > 1 => number.replace('.', ""),
+        _ => number,
+    };
+
+    normalized
+        .parse::<f64>()
+        .ok()
+        .filter(|amount| amount.is_finite() && *amount >= 0.0)
+}
+
+/// Extracts the first `VideoObject`'s name, thumbnail, duration and upload date.
+///
+/// `thumbnailUrl` may be a string, an `ImageObject` or an array of either; a
+/// relative one is resolved against `base`. Returns `None` when the node
+/// carries none of these fields.
+pub fn extract_video(json_ld: &[Value], base: Option<&Url>) -> Option<VideoInfo> {
+    let node = json_ld
+        .iter()
+        .find(|node| has_type(node, &["VideoObject"]))?;
+
+    let thumbnail = node.get("thumbnailUrl").and_then(|thumbnail| {
+        let thumbnail = match thumbnail {
+            Value::Array(items) => items.first()?,
+            other => other,
+        };
+        match thumbnail {
+            Value::String(url) => Some(url.trim().to_string()),
+            other => text_property(other, "url").or_else(|| text_property(other, "contentUrl")),
+        }
+    });
+    let thumbnail_url = thumbnail.and_then(|href| match base {
+        Some(base) => base.join(&href).ok().map(String::from),
+        None => Url::parse(&href).ok().map(String::from),
+    });
+
+    let video = VideoInfo {
+        name: text_property(node, "name").map(|name| clean_text(&name)),
+        thumbnail_url,
+        duration_secs: text_property(node, "duration").and_then(|d| parse_iso8601_duration(&d)),
+        upload_date: text_property(node, "uploadDate").and_then(|d| parse_schema_date(&d)),
+    };
+
+    (video.name.is_some()
+        || video.thumbnail_url.is_some()
+        || video.duration_secs.is_some()
+        || video.upload_date.is_some())
+    .then_some(video)
+}
+
+/// `Event` and its common subtypes.
+const EVENT_TYPES: &[&str] = &[
+    "Event",
+    "BusinessEvent",
+    "ChildrensEvent",
+    "ComedyEvent",
+    "CourseInstance",
+    "DanceEvent",
+    "EducationEvent",
+    "ExhibitionEvent",
+    "Festival",
+    "FoodEvent",
+    "LiteraryEvent",
+    "MusicEvent",
+    "SaleEvent",
+    "ScreeningEvent",
+    "SocialEvent",
+    "SportsEvent",
+    "TheaterEvent",
+    "VisualArtsEvent",
+];
+
+/// Extracts the page's `Event` nodes in document order (at most one unless `all`).
+///
+/// Events without a `name` are skipped. `location` may be a `Place` (its name,
+/// else its address), a `VirtualLocation` (its URL) or a string; `offers` may
+/// be a single `Offer` or an array, of which the first is used.
+pub fn extract_events(json_ld: &[Value], all: bool) -> Vec<EventInfo> {
+    let events = json_ld
+        .iter()
+        .filter(|node| has_type(node, EVENT_TYPES))
+        .filter_map(|node| {
+            let name = clean_text(&text_property(node, "name")?);
+            if name.is_empty() {
+                return None;
+            }
+
+            let location = node.get("location").and_then(|location| {
+                let location = match location {
+                    Value::Array(items) => items.first()?,
+                    other => other,
+                };
+                match location {
+                    Value::String(place) => Some(clean_text(place)),
+                    other => text_property(other, "name")
+                        .map(|name| clean_text(&name))
+                        .or_else(|| postal_address(other.get("address")?).map(|a| a.formatted))
+                        .or_else(|| text_property(other, "url")),
+                }
+            });
+
+            let offer = node.get("offers").and_then(|offers| match offers {
+                Value::Array(items) => items.first(),
+                other => Some(other),
+            });
+            let price = offer
+                .and_then(|offer| text_property(offer, "price"))
+                .and_then(|price| parse_price_amount(&price));
+
+            Some(EventInfo {
+                name,
+                start_date: text_property(node, "startDate").and_then(|d| parse_schema_date(&d)),
+                end_date: text_property(node, "endDate").and_then(|d| parse_schema_date(&d)),
+                location: location.filter(|location| !location.is_empty()),
+                price,
+                price_currency: offer.and_then(|offer| text_property(offer, "priceCurrency")),
+            })
+        });
+
+    if all {
+        events.collect()
+    } else {
+        events.take(1).collect()
+    }
+}
+
+/// Parses a schema.org date or date-time into UTC.
+///
+/// Accepts RFC 3339 date-times, date-times without an offset (read as UTC,
+/// with or without seconds) and plain dates (midnight UTC).
+fn parse_schema_date(raw: &str) -> Option<DateTime<Utc>> {
+    let raw = raw.trim();
+    if let Ok(date) = DateTime::parse_from_rfc3339(raw) {
+        return Some(date.with_timezone(&Utc));
+    }
+    ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(raw, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+        .map(|date| date.and_utc())
+}
+
+/// Parses an ISO 8601 duration such as `PT1H2M3S` or `P1DT30M` into seconds.
+///
+/// Weeks, days, hours, minutes and (fractional, truncated) seconds are
+/// supported, each at most once and in that order. Years and months have no
+/// fixed length and are rejected, as is anything malformed.
+fn parse_iso8601_duration(raw: &str) -> Option<u64> {
+    let rest = raw.trim().to_ascii_uppercase();
+    let rest = rest.strip_prefix('P')?;
+    if rest.is_empty() {
+        return None;
+    }
+
+    let mut seconds = 0f64;
+    let mut in_time = false;
+    // Components read in the current (date or time) part; `P`/`T` must be followed by one.
+    let mut components = 0;
+    // Position of the last unit in `W D H M S`, so units can't repeat or go backwards.
+    let mut last_unit = None;
+    let mut number = String::new();
+
+    for c in rest.chars() {
+        match c {
+            'T' if !in_time && number.is_empty() => {
+                in_time = true;
+                components = 0;
+            }
+            '0'..='9' | '.' | ',' => number.push(if c == ',' { '.' } else { c }),
+            unit => {
+                let value: f64 = number.parse().ok()?;
+                number.clear();
+                let (position, factor) = match (in_time, unit) {
+                    (false, 'W') => (0, 604_800.0),
+                    (false, 'D') => (1, 86_400.0),
+                    (true, 'H') => (2, 3_600.0),
+                    (true, 'M') => (3, 60.0),
+                    (true, 'S') => (4, 1.0),
+                    _ => return None,
+                };
+                if last_unit.is_some_and(|last| position <= last) {
+                    return None;
+                }
+                last_unit = Some(position);
+                seconds += value * factor;
+                components += 1;
+            }
+        }
+    }
+
+    (number.is_empty() && components > 0 && seconds.is_finite()).then_some(seconds as u64)
+}
+
+/// Keeps the digits of a phone number and a leading `+`; `None` if too short to be one.
+fn normalize_phone(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+    let raw = raw.strip_prefix("tel:").unwrap_or(raw);
+    let digits: String = raw.chars().filter(char::is_ascii_digit).collect();
+    if digits.len() < 5 {
+        return None;
+    }
+
+    Some(if raw.starts_with('+') {
+        format!("+{digits}")
+    } else {
+        digits
+    })
+}
+
+/// Normalizes an `address` given as a string, a `PostalAddress` or an array of either.
+fn postal_address(value: &Value) -> Option<PostalAddress> {
+    match value {
+        Value::Array(items) => items.first().and_then(postal_address),
+        Value::String(text) => {
+            let formatted = clean_text(text);
+            (!formatted.is_empty()).then(|| PostalAddress {
+                formatted,
+                ..Default::default()
+            })
+        }
+        Value::Object(_) => {
+            let part = |key| text_property(value, key).map(|text| clean_text(&text));
+            let country = part("addressCountry").or_else(|| {
+                value
+                    .get("addressCountry")
+                    .and_then(|country| text_property(country, "name"))
+            });
+            let mut address = PostalAddress {
+                street_address: part("streetAddress"),
+                locality: part("addressLocality"),
+                region: part("addressRegion"),
+                postal_code: part("postalCode"),
+                country,
+                formatted: String::new(),
+            };
+
+            address.formatted = [
+                &address.street_address,
+                &address.locality,
+                &address.region,
+                &address.postal_code,
+                &address.country,
+            ]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(", ");
+
+            (!address.formatted.is_empty()).then_some(address)
+        }
+        _ => None,
+    }
+}
+
+/// Reads `GeoCoordinates`, accepting numbers or numeric strings.
+fn geo_coordinates(value: &Value) -> Option<GeoCoordinates> {
+    let coordinate = |key| text_property(value, key)?.parse::<f64>().ok();
+    let (latitude, longitude) = (coordinate("latitude")?, coordinate("longitude")?);
+
+    ((-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude)).then_some(
+        GeoCoordinates {
+            latitude,
+            longitude,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn video_upload_date_is_read_as_utc() {
+        let video = |upload_date: &str| {
+            let node = json!({"@type": "VideoObject", "name": "Clip", "uploadDate": upload_date});
+            extract_video(&[node], None).unwrap().upload_date
+        };
+
+        assert_eq!(
+            video("2024-01-15").map(|date| date.to_rfc3339()).as_deref(),
+            Some("2024-01-15T00:00:00+00:00")
+        );
+        assert_eq!(
+            video("2024-01-15T10:30:00+02:00")
+                .map(|date| date.to_rfc3339())
+                .as_deref(),
+            Some("2024-01-15T08:30:00+00:00")
+        );
+        assert_eq!(video("last Tuesday"), None);
+    }
+
+    #[test]
+    fn price_separators_follow_their_position() {
+        assert_eq!(parse_price_amount("$1,299.00"), Some(1299.0));
+        assert_eq!(parse_price_amount("1.299,00 €"), Some(1299.0));
+        assert_eq!(parse_price_amount("12,50"), Some(12.5));
+        assert_eq!(parse_price_amount("1,299"), Some(1299.0));
+        assert_eq!(parse_price_amount("1.234.567"), Some(1_234_567.0));
+        assert_eq!(parse_price_amount("free"), None);
+    }
+
+    #[test]
+    fn event_offer_price_uses_the_price_parser() {
+        let node = json!({
+            "@type": "Event",
+            "name": "Gala",
+            "offers": {"price": "1,299.00", "priceCurrency": "USD"}
+        });
+
+        assert_eq!(extract_events(&[node], false)[0].price, Some(1299.0));
+    }
+
+    #[test]
+    fn parses_iso8601_durations() {
+        assert_eq!(parse_iso8601_duration("PT1H2M3S"), Some(3723));
+        assert_eq!(parse_iso8601_duration("P1DT30M"), Some(88_200));
+        assert_eq!(parse_iso8601_duration(" pt1m30.9s "), Some(90));
+        assert_eq!(parse_iso8601_duration("P1W"), Some(604_800));
+    }
+
+    #[test]
+    fn rejects_malformed_durations() {
+        for raw in [
+            "PT", "P", "P1Y", "P1M", "P1DT", "1H", "PT1H1H", "PT3S2M", "PTxM", "garbage",
+        ] {
+            assert_eq!(parse_iso8601_duration(raw), None, "{raw}");
+        }
+    }
+}
@@ -0,0 +1,189 @@
+//! XML sitemap recognition and URL extraction.
+//!
+//! Crawlers occasionally publish `sitemap.xml` bodies to the HTML topic for
+//! URL discovery. Sitemaps are a tiny, fixed vocabulary (`<urlset>` of `<url>`
+//! entries, or a `<sitemapindex>` of `<sitemap>` entries, each with `<loc>`,
+//! `<lastmod>` and, for URLs, `<priority>`), so a plain tag scan is enough and
+//! avoids pulling in an XML parser. Namespace prefixes and `CDATA` sections
+//! are handled; other XML features are not.
+
+use url::Url;
+
+use super::models::SitemapEntry;
+
+/// Bytes at the start of the payload searched for the root element.
+const ROOT_SCAN_BYTES: usize = 4096;
+
+/// Whether `payload` is a sitemap: its root element is `<urlset>` or
+/// `<sitemapindex>` (after any XML declaration, comments or doctype). When
+/// `content_type` is an XML type, either element anywhere near the start of
+/// the payload is enough.
+pub fn is_sitemap(payload: &[u8], content_type: &str) -> bool {
+    let head = &payload[..payload.len().min(ROOT_SCAN_BYTES)];
+    let head = String::from_utf8_lossy(head);
+    let is_xml = content_type.ends_with("/xml") || content_type.ends_with("+xml");
+    if is_xml && (head.contains("<urlset") || head.contains("<sitemapindex")) {
+        return true;
+    }
+
+    let mut rest = head.trim_start_matches('\u{feff}').trim_start();
+
+    // Skip the prolog: `<?xml ...?>`, `<!-- ... -->` and `<!DOCTYPE ...>`.
+    loop {
+        let end = if rest.starts_with("<?") {
+            rest.find("?>").map(|i| i + 2)
+        } else if rest.starts_with("<!--") {
+            rest.find("-->").map(|i| i + 3)
+        } else if rest.starts_with("<!") {
+            rest.find('>').map(|i| i + 1)
+        } else {
+            break;
+        };
+        let Some(end) = end else {
+            return false;
+        };
+        rest = rest[end..].trim_start();
+    }
+
+    let Some(tag) = rest.strip_prefix('<') else {
+        return false;
+    };
+    let name: String = tag
+        .chars()
+        .take_while(|c| !c.is_whitespace() && *c != '>' && *c != '/')
+        .collect();
+    matches!(local_name(&name), "urlset" | "sitemapindex")
+}
+
+/// Extracts every `<url>` and `<sitemap>` entry with a `<loc>`.
+///
+/// Relative locations are resolved against `base`; unparsable ones are
+/// skipped. `priority` is kept only when it is a number in `[0, 1]`.
+pub fn extract_sitemap(xml: &str, base: Option<&Url>) -> Vec<SitemapEntry> {
+    let mut entries = Vec::new();
+
+    for (entry_tag, is_index) in [("url", false), ("sitemap", true)] {
+        for block in elements(xml, entry_tag) {
+            let Some(loc) = elements(block, "loc").next().map(text_content) else {
+                continue;
+            };
+            let loc = match base {
+                Some(base) => base.join(&loc),
+                None => Url::parse(&loc),
+            };
+            let Ok(loc) = loc else {
+                continue;
+            };
+
+            let field = |tag| {
+                elements(block, tag)
+                    .next()
+                    .map(text_content)
+                    .filter(|v| !v.is_empty())
+            };
+            entries.push(SitemapEntry {
+                loc: loc.to_string(),
+                lastmod: field("lastmod"),
+                priority: field("priority")
+                    .and_then(|p| p.parse::<f32>().ok())
+                    .filter(|p| (0.0..=1.0).contains(p)),
+                is_sitemap: is_index,
+            });
+        }
+    }
+
+    entries
+}
+
+/// The name without a namespace prefix (`sm:urlset` → `urlset`).
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+/// Inner contents of each `<tag>…</tag>` element, outermost first.
+///
+/// Elements match on their local name, so `<sm:url>` is a `url`; the name is
+/// compared case-sensitively as XML requires, and self-closing elements are
+/// skipped.
+fn elements<'a>(xml: &'a str, tag: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    let mut rest = xml;
+
+    std::iter::from_fn(move || loop {
+        let start = rest.find('<')?;
+        let after = &rest[start + 1..];
+        let name_end = after
+            .find(|c: char| c == '>' || c == '/' || c.is_whitespace())
+            .unwrap_or(after.len());
+        let name = &after[..name_end];
+        // `<url>` or `<sm:url attr…>`, not `<urlset>` or a closing tag.
+        if name.is_empty() || local_name(name) != tag {
+            rest = after;
+            continue;
+        }
+        let open_end = after.find('>')?;
+        if after[..open_end].ends_with('/') {
+            rest = &after[open_end + 1..];
+            continue;
+        }
+        let body = &after[open_end + 1..];
+        let close = format!("</{name}>");
+        let end = body.find(&close)?;
+        rest = &body[end + close.len()..];
+        return Some(&body[..end]);
+    })
+}
+
+/// Text of an element body: `CDATA` unwrapped, entities decoded, trimmed.
+fn text_content(body: &str) -> String {
+    let body = body.trim();
+    let text = body
+        .strip_prefix("<![CDATA[")
+        .and_then(|inner| inner.strip_suffix("]]>"))
+        .map(str::to_string)
+        .unwrap_or_else(|| {
+            body.replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&quot;", "\"")
+                .replace("&apos;", "'")
+                .replace("&amp;", "&")
+        });
+    text.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_prefixed_sitemap_entries() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<sm:urlset xmlns:sm="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <sm:url>
+    <sm:loc>https://example.com/a</sm:loc>
+    <sm:lastmod>2024-05-01</sm:lastmod>
+    <sm:priority>0.8</sm:priority>
+  </sm:url>
+  <sm:url><sm:loc>/b</sm:loc></sm:url>
+</sm:urlset>"#;
+        assert!(is_sitemap(xml.as_bytes(), "text/html"));
+
+        let base = Url::parse("https://example.com/sitemap.xml").unwrap();
+        let entries = extract_sitemap(xml, Some(&base));
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].loc, "https://example.com/a");
+        assert_eq!(entries[0].lastmod.as_deref(), Some("2024-05-01"));
+        assert_eq!(entries[0].priority, Some(0.8));
+        assert_eq!(entries[1].loc, "https://example.com/b");
+    }
+
+    #[test]
+    fn entry_tags_do_not_match_longer_names() {
+        let xml = "<urlset><url><loc>https://example.com/</loc></url></urlset>";
+        let entries = extract_sitemap(xml, None);
+
+        // `<urlset>` is not a `<url>` entry.
+        assert_eq!(entries.len(), 1);
+        assert!(!entries[0].is_sitemap);
+    }
+}
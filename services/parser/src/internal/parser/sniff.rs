@@ -0,0 +1,28 @@
+//! Magic-number sniffing for payloads that are clearly not HTML.
+//!
+//! The crawler occasionally publishes binary bodies (images, PDFs, archives) to
+//! the HTML topic. Checking a handful of well-known signatures is enough to
+//! drop them before any decoding or DOM work happens.
+
+/// `(signature, kind)` pairs matched against the start of the payload.
+const SIGNATURES: &[(&[u8], &str)] = &[
+    (b"\x89PNG\r\n\x1a\n", "png"),
+    (b"\xff\xd8\xff", "jpeg"),
+    (b"GIF87a", "gif"),
+    (b"GIF89a", "gif"),
+    (b"%PDF-", "pdf"),
+    (b"PK\x03\x04", "zip"),
+    (b"\x1f\x8b", "gzip"),
+];
+
+/// Returns the kind of binary file `payload` starts with, if it matches a known signature.
+pub fn sniff_binary(payload: &[u8]) -> Option<&'static str> {
+    if payload.len() >= 12 && payload.starts_with(b"RIFF") && &payload[8..12] == b"WEBP" {
+        return Some("webp");
+    }
+
+    SIGNATURES
+        .iter()
+        .find(|(signature, _)| payload.starts_with(signature))
+        .map(|(_, kind)| *kind)
+}
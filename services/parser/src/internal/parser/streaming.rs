@@ -0,0 +1,202 @@
+//! Streaming extraction without building a DOM.
+//!
+//! Feeds the HTML straight through `html5ever`'s tokenizer and collects the
+//! cheap fields (title, meta tags, canonical URL, text and links) in a single
+//! pass. Memory stays proportional to the extracted output rather than to the
+//! size of the document tree, at the cost of readability-based content
+//! selection and the DOM-only extractors (headings, images, JSON-LD, ...).
+
+use html5ever::tendril::StrTendril;
+use html5ever::tokenizer::states::RawKind;
+use html5ever::tokenizer::{
+    BufferQueue, Tag, TagKind, Token, TokenSink, TokenSinkResult, Tokenizer, TokenizerOpts,
+};
+use std::cell::RefCell;
+
+/// Fields collected by the streaming pass. Text values are raw (uncleaned).
+#[derive(Default)]
+pub struct StreamedPage {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub keywords: Option<String>,
+    pub robots: Option<String>,
+    pub canonical_url: Option<String>,
+    pub base_href: Option<String>,
+    pub html_lang: Option<String>,
+    pub og_locale: Option<String>,
+    pub og_url: Option<String>,
+    pub viewport: Option<String>,
+    pub rating: Option<String>,
+    /// Text outside `<head>`, `<script>`, `<style>` and similar elements.
+    pub text: String,
+    /// `(href, anchor text)` pairs in document order.
+    pub links: Vec<(String, String)>,
+}
+
+#[derive(Default)]
+struct SinkState {
+    page: StreamedPage,
+    in_head: bool,
+    in_title: bool,
+    /// Inside an element whose raw text is not page text (script, style, ...).
+    in_raw: bool,
+    open_link: Option<(String, String)>,
+}
+
+struct ExtractSink {
+    state: RefCell<SinkState>,
+    collect_text: bool,
+}
+
+fn attr<'a>(tag: &'a Tag, name: &str) -> Option<&'a str> {
+    tag.attrs
+        .iter()
+        .find(|a| &*a.name.local == name)
+        .map(|a| &*a.value)
+}
+
+impl ExtractSink {
+    fn start_tag(&self, state: &mut SinkState, tag: &Tag) -> TokenSinkResult<()> {
+        match &*tag.name {
+            "html" => {
+                state.page.html_lang = attr(tag, "lang").map(str::to_string);
+            }
+            "head" => state.in_head = true,
+            "body" => state.in_head = false,
+            "title" if state.page.title.is_none() => {
+                state.in_title = true;
+                state.page.title = Some(String::new());
+                return TokenSinkResult::RawData(RawKind::Rcdata);
+            }
+            "meta" => {
+                let content = attr(tag, "content").map(str::to_string);
+                match attr(tag, "name").map(str::to_ascii_lowercase).as_deref() {
+                    Some("description") if state.page.description.is_none() => {
+                        state.page.description = content
+                    }
+                    Some("keywords") if state.page.keywords.is_none() => {
+                        state.page.keywords = content
+                    }
+                    Some("robots") if state.page.robots.is_none() => state.page.robots = content,
+                    Some("viewport") if state.page.viewport.is_none() => {
+                        state.page.viewport = content
+                    }
+                    Some("rating") if state.page.rating.is_none() => state.page.rating = content,
+                    _ => match attr(tag, "property") {
+                        Some("og:locale") if state.page.og_locale.is_none() => {
+                            state.page.og_locale = content
+                        }
+                        Some("og:url") if state.page.og_url.is_none() => {
+                            state.page.og_url = content
+                        }
+                        _ => {}
+                    },
+                }
+            }
+            "link"
+                if state.page.canonical_url.is_none()
+                    && attr(tag, "rel")
+                        .is_some_and(|rel| rel.eq_ignore_ascii_case("canonical")) =>
+            {
+                state.page.canonical_url = attr(tag, "href").map(str::to_string);
+            }
+            "base" if state.page.base_href.is_none() => {
+                state.page.base_href = attr(tag, "href").map(str::to_string);
+            }
+            "a" => {
+                if let Some(href) = attr(tag, "href") {
+                    state.open_link = Some((href.to_string(), String::new()));
+                }
+            }
+            "script" => {
+                state.in_raw = true;
+                return TokenSinkResult::RawData(RawKind::ScriptData);
+            }
+            "style" | "noscript" | "iframe" | "xmp" | "noembed" | "noframes" => {
+                state.in_raw = true;
+                return TokenSinkResult::RawData(RawKind::Rawtext);
+            }
+            "textarea" => {
+                state.in_raw = true;
+                return TokenSinkResult::RawData(RawKind::Rcdata);
+            }
+            _ => {}
+        }
+        TokenSinkResult::Continue
+    }
+
+    fn end_tag(&self, state: &mut SinkState, tag: &Tag) {
+        match &*tag.name {
+            "head" => state.in_head = false,
+            "title" => state.in_title = false,
+            "a" => {
+                if let Some(link) = state.open_link.take() {
+                    state.page.links.push(link);
+                }
+            }
+            "script" | "style" | "noscript" | "iframe" | "xmp" | "noembed" | "noframes"
+            | "textarea" => state.in_raw = false,
+            _ => {}
+        }
+    }
+
+    fn characters(&self, state: &mut SinkState, text: &str) {
+        if state.in_title {
+            if let Some(title) = state.page.title.as_mut() {
+                title.push_str(text);
+            }
+            return;
+        }
+        if state.in_raw || state.in_head {
+            return;
+        }
+        if let Some((_, anchor)) = state.open_link.as_mut() {
+            anchor.push_str(text);
+        }
+        if self.collect_text {
+            state.page.text.push_str(text);
+        }
+    }
+}
+
+impl TokenSink for ExtractSink {
+    type Handle = ();
+
+    fn process_token(&self, token: Token, _line_number: u64) -> TokenSinkResult<()> {
+        let mut state = self.state.borrow_mut();
+        match token {
+            Token::TagToken(tag) => match tag.kind {
+                TagKind::StartTag => return self.start_tag(&mut state, &tag),
+                TagKind::EndTag => self.end_tag(&mut state, &tag),
+            },
+            Token::CharacterTokens(text) => self.characters(&mut state, &text),
+            _ => {}
+        }
+        TokenSinkResult::Continue
+    }
+}
+
+/// Extracts title, meta tags, canonical URL, links and (optionally) text in one pass.
+///
+/// # Arguments
+/// - `html`: The raw HTML.
+/// - `collect_text`: Whether to accumulate page text; link text is always collected.
+pub fn extract_streaming(html: &str, collect_text: bool) -> StreamedPage {
+    let sink = ExtractSink {
+        state: RefCell::new(SinkState::default()),
+        collect_text,
+    };
+
+    let input = BufferQueue::default();
+    input.push_back(StrTendril::from_slice(html));
+
+    let tokenizer = Tokenizer::new(sink, TokenizerOpts::default());
+    let _ = tokenizer.feed(&input);
+    tokenizer.end();
+
+    let mut state = tokenizer.sink.state.into_inner();
+    if let Some(link) = state.open_link.take() {
+        state.page.links.push(link);
+    }
+    state.page
+}
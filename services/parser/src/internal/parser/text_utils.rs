@@ -4,6 +4,9 @@
 
 use once_cell::sync::Lazy;
 use regex::Regex;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use unicode_segmentation::UnicodeSegmentation;
 
 /// Precompiled regex to match one or more whitespace characters.
 static RE_WHITESPACE: Lazy<Regex> =
@@ -31,3 +34,414 @@ static RE_WHITESPACE: Lazy<Regex> =
 pub fn clean_text(text: &str) -> String {
     RE_WHITESPACE.replace_all(text.trim(), " ").to_string()
 }
+
+/// Cleans text while keeping line and paragraph breaks.
+///
+/// Each line is cleaned like `clean_text` (which also drops whitespace-only
+/// lines). A single newline between two lines is kept as a line break; any
+/// longer run, however many empty lines or `<br>`s produced it, becomes one
+/// paragraph break (`\n\n`). Leading and trailing breaks are removed.
+///
+/// # Example
+///
+/// ```
+/// let cleaned = clean_text_preserving_paragraphs(" One \n\n \n\n\n  Two\nthree ");
+/// assert_eq!(cleaned, "One\n\nTwo\nthree");
+/// ```
+pub fn clean_text_preserving_paragraphs(text: &str) -> String {
+    let mut cleaned = String::with_capacity(text.len());
+    let mut empty_lines = 0;
+
+    for line in text.lines().map(clean_text) {
+        if line.is_empty() {
+            empty_lines += 1;
+            continue;
+        }
+        if !cleaned.is_empty() {
+            cleaned.push_str(if empty_lines == 0 { "\n" } else { "\n\n" });
+        }
+        cleaned.push_str(&line);
+        empty_lines = 0;
+    }
+
+    cleaned
+}
+
+/// Normalizes text into a lowercase key suitable for faceting.
+///
+/// Lowercases and collapses whitespace; when `strip_punctuation` is set,
+/// punctuation is replaced by spaces first.
+///
+/// # Example
+///
+/// ```
+/// assert_eq!(normalize_key("  Getting Started: Install!  ", true), "getting started install");
+/// ```
+pub fn normalize_key(text: &str, strip_punctuation: bool) -> String {
+    let lowered = text.to_lowercase();
+    if !strip_punctuation {
+        return clean_text(&lowered);
+    }
+
+    let stripped: String = lowered
+        .chars()
+        .map(|c| {
+            if c.is_ascii_punctuation() || c.is_ascii_control() {
+                ' '
+            } else {
+                c
+            }
+        })
+        .collect();
+    clean_text(&stripped)
+}
+
+/// Separators commonly placed between a page title and the site name.
+const TITLE_SEPARATORS: &[&str] = &[" | ", " — ", " – ", " - ", " :: ", " · "];
+
+/// Maximum number of words in a suffix the heuristic treats as a site name.
+const MAX_SITE_SUFFIX_WORDS: usize = 4;
+
+/// Removes a trailing site-name suffix such as `" | Site Name"` from a title.
+///
+/// With a known `site_name` (e.g. from `og:site_name`) only that exact suffix is
+/// removed. Without one, the part after the last separator is removed if it
+/// is short (at most four words) and shorter than the rest of the title.
+/// Returns `None` when nothing was stripped.
+///
+/// # Example
+///
+/// ```
+/// let title = strip_site_suffix("Rust 2.0 Released | Example News", None);
+/// assert_eq!(title.as_deref(), Some("Rust 2.0 Released"));
+/// ```
+pub fn strip_site_suffix(title: &str, site_name: Option<&str>) -> Option<String> {
+    if let Some(site) = site_name.map(str::trim).filter(|s| !s.is_empty()) {
+        return TITLE_SEPARATORS.iter().find_map(|sep| {
+            let suffix = format!("{}{}", sep, site);
+            let start = title.len().checked_sub(suffix.len())?;
+            let matches = title.is_char_boundary(start)
+                && title[start..].to_lowercase() == suffix.to_lowercase();
+            let head = title[..start].trim();
+            (matches && !head.is_empty()).then(|| head.to_string())
+        });
+    }
+
+    let (index, sep) = TITLE_SEPARATORS
+        .iter()
+        .filter_map(|sep| title.rfind(sep).map(|i| (i, *sep)))
+        .max_by_key(|(i, _)| *i)?;
+
+    let head = title[..index].trim();
+    let suffix = title[index + sep.len()..].trim();
+    let is_site_like = !suffix.is_empty()
+        && suffix.split_whitespace().count() <= MAX_SITE_SUFFIX_WORDS
+        && suffix.chars().count() < head.chars().count();
+
+    is_site_like.then(|| head.to_string())
+}
+
+/// Truncates text to at most `max_len` grapheme clusters for use as a snippet.
+///
+/// Text that already fits is returned unchanged. Otherwise the cut is made at
+/// the last whitespace within the limit (or at the limit itself if the first
+/// word is longer), trailing whitespace and punctuation are trimmed, and `…`
+/// is appended. Cutting on grapheme boundaries also keeps UTF-8 boundaries.
+///
+/// # Example
+///
+/// ```
+/// assert_eq!(make_preview("The quick brown fox", 12), "The quick…");
+/// ```
+pub fn make_preview(text: &str, max_len: usize) -> String {
+    let mut graphemes = text.grapheme_indices(true);
+    let Some((limit, _)) = graphemes.nth(max_len) else {
+        return text.to_string();
+    };
+
+    let head = &text[..limit];
+    let cut = match head.rfind(char::is_whitespace) {
+        Some(space) if space > 0 => &head[..space],
+        _ => head,
+    };
+    let cut = cut.trim_end_matches(|c: char| c.is_whitespace() || c.is_ascii_punctuation());
+
+    format!("{cut}…")
+}
+
+/// Hex-encoded SHA-256 of `text`, used as a content fingerprint for dedup.
+pub fn content_hash(text: &str) -> String {
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
+
+/// Fewest words a text needs for `readability_score` to rate it.
+const READABILITY_MIN_WORDS: usize = 100;
+
+/// Flesch reading ease of `text`: `206.835 - 1.015 × words/sentences -
+/// 84.6 × syllables/words`, higher meaning easier.
+///
+/// The formula and the vowel-group syllable estimate are English-only, so
+/// texts whose `language` (the PostgreSQL config name) isn't `english` are not
+/// scored. Only alphabetic words count, and sentences end at Unicode sentence
+/// boundaries. Returns `None` for texts under `READABILITY_MIN_WORDS` words.
+pub fn readability_score(text: &str, language: &str) -> Option<f64> {
+    if language != "english" {
+        return None;
+    }
+
+    let words: Vec<&str> = text
+        .unicode_words()
+        .filter(|word| word.chars().all(char::is_alphabetic))
+        .collect();
+    if words.len() < READABILITY_MIN_WORDS {
+        return None;
+    }
+
+    let sentences = text
+        .unicode_sentences()
+        .filter(|sentence| sentence.chars().any(char::is_alphabetic))
+        .count()
+        .max(1);
+    let syllables: usize = words.iter().map(|word| count_syllables(word)).sum();
+
+    let words = words.len() as f64;
+    Some(206.835 - 1.015 * (words / sentences as f64) - 84.6 * (syllables as f64 / words))
+}
+
+/// Estimated syllables of a word: groups of vowels, minus a silent final `e`.
+fn count_syllables(word: &str) -> usize {
+    let word = word.to_lowercase();
+    let is_vowel = |c: char| "aeiouy".contains(c);
+
+    let mut groups = 0;
+    let mut previous_vowel = false;
+    for c in word.chars() {
+        let vowel = is_vowel(c);
+        if vowel && !previous_vowel {
+            groups += 1;
+        }
+        previous_vowel = vowel;
+    }
+    if groups > 1 && word.ends_with('e') && !word.ends_with("le") {
+        groups -= 1;
+    }
+
+    groups.max(1)
+}
+
+/// Common English words left out of `token_counts` for `english` pages.
+const ENGLISH_STOPWORDS: &[&str] = &[
+    "a",
+    "about",
+    "above",
+    "after",
+    "again",
+    "against",
+    "all",
+    "am",
+    "an",
+    "and",
+    "any",
+    "are",
+    "as",
+    "at",
+    "be",
+    "because",
+    "been",
+    "before",
+    "being",
+    "below",
+    "between",
+    "both",
+    "but",
+    "by",
+    "can",
+    "could",
+    "did",
+    "do",
+    "does",
+    "doing",
+    "down",
+    "during",
+    "each",
+    "few",
+    "for",
+    "from",
+    "further",
+    "had",
+    "has",
+    "have",
+    "having",
+    "he",
+    "her",
+    "here",
+    "hers",
+    "herself",
+    "him",
+    "himself",
+    "his",
+    "how",
+    "i",
+    "if",
+    "in",
+    "into",
+    "is",
+    "it",
+    "its",
+    "itself",
+    "just",
+    "me",
+    "more",
+    "most",
+    "my",
+    "myself",
+    "no",
+    "nor",
+    "not",
+    "now",
+    "of",
+    "off",
+    "on",
+    "once",
+    "only",
+    "or",
+    "other",
+    "our",
+    "ours",
+    "ourselves",
+    "out",
+    "over",
+    "own",
+    "same",
+    "she",
+    "should",
+    "so",
+    "some",
+    "such",
+    "than",
+    "that",
+    "the",
+    "their",
+    "theirs",
+    "them",
+    "themselves",
+    "then",
+    "there",
+    "these",
+    "they",
+    "this",
+    "those",
+    "through",
+    "to",
+    "too",
+    "under",
+    "until",
+    "up",
+    "very",
+    "was",
+    "we",
+    "were",
+    "what",
+    "when",
+    "where",
+    "which",
+    "while",
+    "who",
+    "whom",
+    "why",
+    "will",
+    "with",
+    "would",
+    "you",
+    "your",
+    "yours",
+    "yourself",
+    "yourselves",
+];
+
+/// Counts the lowercased Unicode words (UAX #29) of `text`, keeping the
+/// `top_k` most frequent (ties broken alphabetically).
+///
+/// `language` is the PostgreSQL text search configuration of the page:
+/// `english` pages drop `ENGLISH_STOPWORDS`; other languages keep every word.
+/// Tokens without a letter or digit are ignored.
+pub fn token_counts(text: &str, language: &str, top_k: usize) -> HashMap<String, u32> {
+    let stopwords: &[&str] = match language {
+        "english" => ENGLISH_STOPWORDS,
+        _ => &[],
+    };
+
+    let mut counts: HashMap<String, u32> = HashMap::new();
+    for word in text.unicode_words() {
+        let token = word.to_lowercase();
+        if !token.chars().any(char::is_alphanumeric) || stopwords.contains(&token.as_str()) {
+            continue;
+        }
+        *counts.entry(token).or_default() += 1;
+    }
+
+    if counts.len() > top_k {
+        let mut ranked: Vec<(String, u32)> = counts.into_iter().collect();
+        ranked.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)));
+        ranked.truncate(top_k);
+        counts = ranked.into_iter().collect();
+    }
+    counts
+}
+
+/// Counts words using Unicode word boundaries (UAX #29).
+///
+/// Unlike splitting on whitespace, this counts each CJK ideograph as a word
+/// and ignores punctuation-only tokens, so titles in scripts written without
+/// spaces get a meaningful count.
+///
+/// # Example
+///
+/// ```
+/// assert_eq!(count_words("Hello, world!"), 2);
+/// assert_eq!(count_words("東京タワー"), 3);
+/// ```
+pub fn count_words(text: &str) -> usize {
+    text.unicode_words().count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_words_segments_cjk_text() {
+        assert_eq!(count_words("Hello, world!"), 2);
+        // Each Han character is a word; a katakana run is one.
+        assert_eq!(count_words("東京タワー"), 3);
+        assert_eq!(count_words("東京タワー visit guide"), 5);
+    }
+
+    #[test]
+    fn readability_scores_only_english() {
+        let text = "The cat sat on the mat. It was warm! Was it happy? ".repeat(10);
+
+        let score = readability_score(&text, "english").unwrap();
+        assert!(score > 90.0, "simple English scored {score}");
+        assert_eq!(readability_score(&text, "simple"), None);
+        assert_eq!(readability_score("Too short.", "english"), None);
+    }
+
+    #[test]
+    fn readability_splits_on_unicode_terminators() {
+        // Ideographic full stops end sentences too.
+        let text = "The cat sat on the mat。It was warm！Was it happy？".repeat(10);
+
+        let score = readability_score(&text, "english").unwrap();
+        assert!(score > 90.0, "scored {score}");
+    }
+
+    #[test]
+    fn preserving_paragraphs_collapses_runs_of_blank_lines() {
+        assert_eq!(
+            clean_text_preserving_paragraphs(" One \n\n \n\n\n  Two\nthree "),
+            "One\n\nTwo\nthree"
+        );
+        assert_eq!(clean_text_preserving_paragraphs("\n\n\u{a0}\n\n"), "");
+    }
+}
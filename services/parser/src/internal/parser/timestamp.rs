@@ -0,0 +1,95 @@
+//! Serialization of `ParsedPage` dates according to `timestamp_format`.
+//!
+//! The format is process-wide and set once at startup with
+//! `set_timestamp_format`; until then (and for unknown names) dates are
+//! written as RFC 3339 strings. Deserialization accepts RFC 3339 strings and
+//! integers in the configured epoch unit.
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Deserializer, Serializer};
+use std::sync::OnceLock;
+
+/// How dates are written to JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TimestampFormat {
+    Rfc3339,
+    EpochMillis,
+    EpochSecs,
+}
+
+static FORMAT: OnceLock<TimestampFormat> = OnceLock::new();
+
+/// Sets the date format from `timestamp_format` (`rfc3339`, `epoch_millis`
+/// or `epoch_secs`). Only the first call has an effect.
+pub fn set_timestamp_format(name: &str) {
+    let format = match name {
+        "epoch_millis" => TimestampFormat::EpochMillis,
+        "epoch_secs" => TimestampFormat::EpochSecs,
+        _ => TimestampFormat::Rfc3339,
+    };
+    let _ = FORMAT.set(format);
+}
+
+fn format() -> TimestampFormat {
+    FORMAT.get().copied().unwrap_or(TimestampFormat::Rfc3339)
+}
+
+/// A date as found in JSON: an RFC 3339 string or an epoch number.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawTimestamp {
+    Text(String),
+    Epoch(i64),
+}
+
+impl RawTimestamp {
+    fn into_datetime<E: serde::de::Error>(self) -> Result<DateTime<Utc>, E> {
+        let parsed = match self {
+            Self::Text(text) => DateTime::parse_from_rfc3339(&text)
+                .ok()
+                .map(|date| date.with_timezone(&Utc)),
+            Self::Epoch(secs) if format() == TimestampFormat::EpochSecs => {
+                DateTime::from_timestamp(secs, 0)
+            }
+            Self::Epoch(millis) => DateTime::from_timestamp_millis(millis),
+        };
+        parsed.ok_or_else(|| E::custom("invalid timestamp"))
+    }
+}
+
+pub fn serialize<S: Serializer>(date: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error> {
+    match format() {
+        TimestampFormat::Rfc3339 => {
+            serializer.serialize_str(&date.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+        }
+        TimestampFormat::EpochMillis => serializer.serialize_i64(date.timestamp_millis()),
+        TimestampFormat::EpochSecs => serializer.serialize_i64(date.timestamp()),
+    }
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime<Utc>, D::Error> {
+    RawTimestamp::deserialize(deserializer)?.into_datetime()
+}
+
+/// The same for `Option<DateTime<Utc>>` fields (`None` is `null`).
+pub mod option {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        date: &Option<DateTime<Utc>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match date {
+            Some(date) => super::serialize(date, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<DateTime<Utc>>, D::Error> {
+        Option::<RawTimestamp>::deserialize(deserializer)?
+            .map(RawTimestamp::into_datetime)
+            .transpose()
+    }
+}
@@ -10,6 +10,7 @@ mod internal;
 use internal::config::Config;
 use internal::core::KafkaHandler;
 use internal::monitor::{start_monitor_server, Metrics};
+use internal::parser::timestamp::set_timestamp_format;
 use internal::parser::HtmlParser;
 
 /// Initializes and runs the parser service.
@@ -17,24 +18,54 @@ async fn run() -> Result<()> {
     // Load .env file if it exists (for local development)
     dotenv::dotenv().ok();
 
-    // Load config from environment; fall back to defaults if missing.
-    let config: Arc<Config> = Arc::new(envy::from_env().unwrap_or_default());
+    // Load config from environment; unset vars take their defaults, but a var
+    // that fails to parse is fatal rather than silently ignored.
+    let mut config: Config = match envy::from_env() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("Configuration error: failed to read environment: {}", err);
+            std::process::exit(1);
+        }
+    };
     config.init_logging();
     if let Err(err) = config.validate() {
         eprintln!("Configuration error: {}", err);
         std::process::exit(1);
     }
+    config.apply_read_only_validation();
+    let config = Arc::new(config);
+    set_timestamp_format(&config.timestamp_format);
 
     // Initialize Kafka handler and HTML parser.
-    let kafka_handler = Arc::new(KafkaHandler::new(Arc::clone(&config)).await?);
-    let parser = HtmlParser::new(&config);
     let metrics = Arc::new(Metrics::new());
+    let kafka_handler = if config.startup_timeout_secs > 0 {
+        let limit = Duration::from_secs(config.startup_timeout_secs);
+        let startup = async {
+            let handler = KafkaHandler::new(Arc::clone(&config), &metrics).await?;
+            handler.wait_until_connected().await;
+            anyhow::Ok(handler)
+        };
+        match time::timeout(limit, startup).await {
+            Ok(handler) => Arc::new(handler?),
+            Err(_) => {
+                // Exit rather than linger half-initialized, so the orchestrator restarts us.
+                error!(
+                    "Kafka handler not initialized within {}s, exiting",
+                    config.startup_timeout_secs
+                );
+                std::process::exit(1);
+            }
+        }
+    } else {
+        Arc::new(KafkaHandler::new(Arc::clone(&config), &metrics).await?)
+    };
+    let parser = Arc::new(HtmlParser::new(&config, Arc::clone(&metrics)));
 
     // Shutdown signal notifier
     let (shutdown_tx, shutdown_rx) = watch::channel(false);
 
     // Start monitor server
-    let monitor_port = config.monitor_port;
+    let monitor_config = Arc::clone(&config);
     let metrics_clone = metrics.clone();
     let kafka_clone = kafka_handler.clone();
     let kafka_shutdown_send = shutdown_tx.clone();
@@ -43,7 +74,7 @@ async fn run() -> Result<()> {
 
     let mut monitor_task: Option<JoinHandle<()>> = Some(tokio::spawn(async move {
         if let Err(e) = start_monitor_server(
-            monitor_port,
+            monitor_config,
             metrics_clone,
             kafka_clone,
             monitor_shutdown,
@@ -55,6 +86,14 @@ async fn run() -> Result<()> {
         }
     }));
 
+    // Periodic heartbeat log, independent of message arrival
+    if config.heartbeat_secs > 0 {
+        let kafka_handler = kafka_handler.clone();
+        let metrics = metrics.clone();
+        let shutdown_rx = shutdown_rx.clone();
+        tokio::spawn(async move { kafka_handler.run_heartbeat(metrics, shutdown_rx).await });
+    }
+
     // Kafka processing task
     let mut kafka_task: Option<JoinHandle<()>> = Some(tokio::spawn({
         let shutdown_rx = shutdown_rx.clone();